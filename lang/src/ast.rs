@@ -1,225 +1,321 @@
 
-use std::collections::HashMap;
-use crate::packages::array;
+// where a node is in the source, lines and columns start at 1 and the end column is exclusive
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum AST {
     LetDeclaration {
         name: Option<String>,
         value: Box<AST>,
-        line: usize, // for error msgs
+        span: Span,
     },
 
     IfStatement {
         condition: Box<AST>,
         body: Vec<AST>,
-        line: usize,
+        else_body: Option<Vec<AST>>, // `else if` is an if statement inside the else body
+        span: Span,
+    },
+
+    WhileLoop {
+        condition: Box<AST>,
+        body: Vec<AST>,
+        span: Span,
+    },
+
+    ForLoop {
+        variable: String,
+        iterable: Box<AST>,
+        body: Vec<AST>,
+        span: Span,
+    },
+
+    TryCatch {
+        body: Vec<AST>,
+        error_name: String, // the caught error is put in this variable
+        catch_body: Vec<AST>,
+        span: Span,
+    },
+
+    Throw {
+        value: Box<AST>,
+        span: Span,
+    },
+
+    Break {
+        span: Span,
+    },
+
+    Continue {
+        span: Span,
     },
 
     Import {
         file: Option<String>,
         as_: Option<String>,
-        line: usize,
-    },
-
-    Object {
-        properties: HashMap<String, AST>,
-        line: usize,
+        span: Span,
     },
 
+    // `object.property`, the object can be any expression, so accesses can be chained
     PropertyAccess {
-        object: Option<String>,
-        property: Option<String>,
-        line: usize,
+        object: Box<AST>,
+        property: String,
+        span: Span,
     },
 
     PropertyCall {
-        object: Option<String>,
-        property: Option<String>,
+        object: Box<AST>,
+        property: String,
         args: Vec<AST>,
-        line: usize,
+        span: Span,
     },
 
     Call {
         name: String,
         args: Vec<AST>,
-        line: usize,
+        span: Span,
+    },
+
+    // `callee(args)` where the callee is any expression, like `fns[0]()` or `make_adder(5)(10)`
+    CallExpression {
+        callee: Box<AST>,
+        args: Vec<AST>,
+        span: Span,
     },
 
     Function {
         name: String,
         args: Vec<String>,
         body: Vec<AST>,
-        line: usize,
+        span: Span,
     },
 
-    Return {
+    // `"Hello ${name}"`, the parts are evaluated and joined together
+    InterpolatedString {
+        parts: Vec<AST>,
+        span: Span,
+    },
+
+    // `[1, 2]`
+    ArrayLiteral {
+        items: Vec<AST>,
+        span: Span,
+    },
+
+    // `{ name: "x" }`
+    ObjectLiteral {
+        properties: Vec<(String, AST)>,
+        span: Span,
+    },
+
+    // `a[0]` or `obj["key"]`
+    Index {
+        object: Box<AST>,
+        index: Box<AST>,
+        span: Span,
+    },
+
+    // `target = value`, the target is a variable, a property or an index
+    Assign {
+        target: Box<AST>,
         value: Box<AST>,
-        line: usize,
+        span: Span,
     },
 
-    InternalFunction {
-        name: String,
+    // `target += value`
+    AddAssign {
+        target: Box<AST>,
+        value: Box<AST>,
+        span: Span,
+    },
+
+    // `target -= value`
+    SubtractAssign {
+        target: Box<AST>,
+        value: Box<AST>,
+        span: Span,
+    },
+
+    // `fn (x) { ... }` used as a value
+    AnonymousFunction {
         args: Vec<String>,
-        call_fn: fn(Vec<AST>, &mut HashMap<String, AST>) -> Result<(AST, AST), String>,
+        body: Vec<AST>,
+        span: Span,
+    },
+
+    Return {
+        value: Box<AST>,
+        span: Span,
     },
 
     Exists {
         value: Box<AST>,
-        line: usize,
+        span: Span,
     },
 
     IsEqual {
         left: Box<AST>,
         right: Box<AST>,
-        line: usize,
+        span: Span,
     },
 
     LessThan {
         left: Box<AST>,
         right: Box<AST>,
-        line: usize,
+        span: Span,
     },
 
     GreaterThan {
         left: Box<AST>,
         right: Box<AST>,
-        line: usize,
+        span: Span,
     },
 
     LessThanOrEqual {
         left: Box<AST>,
         right: Box<AST>,
-        line: usize,
+        span: Span,
     },
 
     GreaterThanOrEqual {
         left: Box<AST>,
         right: Box<AST>,
-        line: usize,
+        span: Span,
     },
 
     IsUnequal {
         left: Box<AST>,
         right: Box<AST>,
-        line: usize,
+        span: Span,
     },
 
-    Addition {
+    And {
         left: Box<AST>,
         right: Box<AST>,
-        line: usize,
+        span: Span,
     },
 
-    Subtraction {
+    Or {
         left: Box<AST>,
         right: Box<AST>,
-        line: usize,
+        span: Span,
     },
 
-    Identifer(String),
-
-    Number(i64),
+    Not {
+        value: Box<AST>,
+        span: Span,
+    },
 
-    String(String),
+    Addition {
+        left: Box<AST>,
+        right: Box<AST>,
+        span: Span,
+    },
 
-    Boolean(bool),
+    Subtraction {
+        left: Box<AST>,
+        right: Box<AST>,
+        span: Span,
+    },
 
-    Float(f64),
+    Multiplication {
+        left: Box<AST>,
+        right: Box<AST>,
+        span: Span,
+    },
 
-    Null,
+    Division {
+        left: Box<AST>,
+        right: Box<AST>,
+        span: Span,
+    },
 
-    Semicolon,
+    Modulo {
+        left: Box<AST>,
+        right: Box<AST>,
+        span: Span,
+    },
 
-    Lparen,
+    Exponent {
+        left: Box<AST>,
+        right: Box<AST>,
+        span: Span,
+    },
 
-    Rparen,
+    Identifer(String, Span),
 
-    RBracket,
+    Number(i64, Span),
 
-    Comma,
+    String(String, Span),
 
-    Dot,
+    Boolean(bool, Span),
 
-    Minus,
+    Float(f64, Span),
 
-    Plus,
+    Null,
 }
 
 
-impl std::fmt::Display for AST {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Program {
+    pub body: Vec<AST>,
+}
+
+impl AST {
+    pub fn span(&self) -> Option<Span> {
         match self {
-            // TODO: Implement more
-            AST::String(s) => { 
-                let mut s = s.replace("\\t", "\t")
-                    .replace("\\n", "\n")
-                    .replace("\\r", "\r")
-                    .replace("\\\"", "\"")
-                    .replace("\\\\", "\\");
-            
-                if s.starts_with("\"") && s.ends_with("\"") {
-                    s = s[1..s.len() - 1].to_string();
-                } else if s.starts_with("'") && s.ends_with("'") {
-                    s = s[1..s.len() - 1].to_string();
-                }
-
-                write!(f, "{}", s)
-            },
-            AST::Number(n) => write!(f, "{}", n),
-            AST::Float(n) => write!(f, "{}", n),
-            AST::Boolean(b) => write!(f, "{}", b),
-            AST::Null => write!(f, "null"),
-
-            AST::Object { properties, line: _ } => {
-                if properties.contains_key(array::IDENTITY) && properties[array::IDENTITY].clone() == AST::String("array".to_string()) {
-                    write!(f, "[")?;
-
-                    let mut str = String::new();
-                    
-
-                    for i in 0..properties.len() {
-                        if properties.contains_key(&format!("{}", i)) {
-                            str.push_str(&format!("{}, ", properties[&format!("{}", i)]));
-                        }
-                    }
-
-                    if str.len() > 0 {
-                        write!(f, "{}", &str[..str.len() - 2])?;
-                    }
-
-                    write!(f, "]")?;
-
-                    return Ok(());
-                }
-
-                write!(f, "{{ ")?;
-
-                if properties.len() as i32 - crate::packages::json::BUILTINS.len() as i32 == 0 {
-                    write!(f, "}}")?;
-                } else {
-                    let mut str = String::new();
-
-                    for (key, value) in properties {
-                        if crate::packages::json::BUILTINS.contains(&key.as_str()) {
-                            continue;
-                        }
-
-                        str.push_str(&format!("\"{}\": {}, ", key, value));
-                    }
-
-                    if str.len() > 0 {
-                        write!(f, "{}", &str[..str.len() - 2])?;
-                    }
-
-
-                    write!(f, " }}")?;
-                }
-                
-                Ok(())
-            }
-
-            _ => write!(f, "{:?}", self),
+            AST::LetDeclaration { span, .. } |
+            AST::IfStatement { span, .. } |
+            AST::WhileLoop { span, .. } |
+            AST::ForLoop { span, .. } |
+            AST::TryCatch { span, .. } |
+            AST::Throw { span, .. } |
+            AST::Break { span, .. } |
+            AST::Continue { span, .. } |
+            AST::Import { span, .. } |
+            AST::PropertyAccess { span, .. } |
+            AST::PropertyCall { span, .. } |
+            AST::Call { span, .. } |
+            AST::CallExpression { span, .. } |
+            AST::Function { span, .. } |
+            AST::AnonymousFunction { span, .. } |
+            AST::InterpolatedString { span, .. } |
+            AST::ArrayLiteral { span, .. } |
+            AST::ObjectLiteral { span, .. } |
+            AST::Index { span, .. } |
+            AST::Assign { span, .. } |
+            AST::AddAssign { span, .. } |
+            AST::SubtractAssign { span, .. } |
+            AST::Return { span, .. } |
+            AST::Exists { span, .. } |
+            AST::IsEqual { span, .. } |
+            AST::LessThan { span, .. } |
+            AST::GreaterThan { span, .. } |
+            AST::LessThanOrEqual { span, .. } |
+            AST::GreaterThanOrEqual { span, .. } |
+            AST::IsUnequal { span, .. } |
+            AST::And { span, .. } |
+            AST::Or { span, .. } |
+            AST::Not { span, .. } |
+            AST::Addition { span, .. } |
+            AST::Subtraction { span, .. } |
+            AST::Multiplication { span, .. } |
+            AST::Division { span, .. } |
+            AST::Modulo { span, .. } |
+            AST::Exponent { span, .. } |
+            AST::Identifer(_, span) |
+            AST::Number(_, span) |
+            AST::String(_, span) |
+            AST::Boolean(_, span) |
+            AST::Float(_, span) => Some(*span),
+
+            _ => None,
         }
     }
-}
\ No newline at end of file
+}
@@ -1,6 +1,7 @@
 pub mod run;
 pub mod repl;
 pub mod server;
+pub mod report;
 pub mod login;
 pub mod init;
 pub mod publish;
@@ -1,73 +1,75 @@
-use crate::utils;
-use crate::parser::parse;
-use bat::PrettyPrinter;
-use bat::line_range::{LineRanges, LineRange};
+use crate::error::ModuError;
+use crate::interpreter::Interpreter;
+use crate::parser::parse_program;
+use crate::cli::report::report;
 
 use std::io::Write;
 
 pub fn repl() {
     println!("Modu REPL");
 
-    let context = &mut utils::create_context();
-    
-    let mut current_line = 0;
+    let mut interpreter = Interpreter::new();
+
     let mut history: Vec<String> = Vec::new();
-    let mut open_functions = 0;
     let mut input = String::new();
 
     loop {
-        current_line += 1;
-
-        if open_functions > 0 {
-            print!("|{}", " ".repeat(open_functions * 4));
-        } else {
-            input.clear();
-
+        if input.is_empty() {
             print!("> ");
+        } else {
+            print!("|    ");
         }
 
         std::io::stdout().flush().unwrap();
 
         let mut this_input = String::new();
 
-        std::io::stdin().read_line(&mut this_input).unwrap();
+        if std::io::stdin().read_line(&mut this_input).unwrap() == 0 {
+            println!();
+            return;
+        }
 
-        history.push(input.clone());
+        input.push_str(&this_input);
 
-        if this_input.contains("{") {
-            open_functions += 1;
-        }
+        // lines of the input start after everything entered before it
+        let first_line = history.len();
 
-        if this_input.contains("}") {
-            open_functions -= 1;
-        }
+        let result = match parse_program(&input) {
+            Ok(program) => {
+                history.extend(input.lines().map(String::from));
 
-        input.push_str(&this_input);
+                interpreter.run(&program)
+            }
+
+            // keep reading until the statement is complete, like an open function body
+            Err(e) if e.unexpected_eof => {
+                continue;
+            }
+
+            Err(e) => {
+                history.extend(input.lines().map(String::from));
+
+                Err(ModuError::from(e))
+            }
+        };
+
+        input.clear();
+
+        if let Err(mut e) = result {
+            // lines of the error are relative to this input, show them relative to the whole session
+            let info = e.info_mut();
+
+            let spans = info.stack.iter_mut()
+                .filter(|frame| frame.file.is_none())
+                .map(|frame| &mut frame.span)
+                .chain(info.span.as_mut().filter(|_| info.file.is_none()));
+
+            for span in spans {
+                span.line += first_line;
+                span.end_line += first_line;
+            }
 
-        if open_functions == 0 {
-            parse(&input, context).unwrap_or_else(|e| {
-                println!("\n⚠️  {}", e.0);
-                println!("Traceback (most recent call last):");
-                println!("    File \"<stdin>\", line {}", current_line);
-
-                let joined = history.join("");
-                let bytes = joined.as_bytes();
-
-                PrettyPrinter::new()
-                    .language("rust")
-                    .header(true)
-                    .line_numbers(true)
-                    .highlight(current_line)
-                    .grid(true)
-                    .input_from_bytes(bytes)
-                    .line_ranges(
-                        LineRanges::from(vec![LineRange::from(&format!("{}:{}", current_line - 1, current_line + 1)).unwrap()])
-                    )
-                    .print()
-                    .unwrap();
-
-                println!("Believe this is a bug? Report it: https://github.com/Cyteon/modu/issues/new");
-            });
+            report(&e, "<stdin>", &history.join("\n"));
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,68 @@
+use crate::ast::Span;
+use crate::error::ModuError;
+use bat::{Input, PrettyPrinter};
+use bat::line_range::{LineRanges, LineRange};
+
+// width of the line number gutter bat draws in front of each line, like "   6 │ "
+const GUTTER_WIDTH: usize = 7;
+
+// prints an error with a frame for every call it went through, `source` is used for code in the file being run
+pub fn report(err: &ModuError, name: &str, source: &str) {
+    let info = err.info();
+
+    println!("\n⚠️  {}", err);
+    println!("Traceback (most recent call last):");
+
+    // each frame is a call site, the code after it runs in the function it called
+    let mut function = "<module>";
+
+    for frame in &info.stack {
+        print_location(&frame.file, Some(frame.span), function, name, source, 0);
+        function = frame.name.as_str();
+    }
+
+    print_location(&info.file, info.span, function, name, source, 1);
+
+    println!("Believe this is a bug? Report it: https://github.com/Cyteon/modu/issues/new");
+}
+
+fn print_location(file: &Option<String>, span: Option<Span>, function: &str, name: &str, source: &str, lines_before: usize) {
+    let line = span.map(|span| span.line).unwrap_or(1);
+
+    let (file, contents) = match file {
+        Some(file) => (file.clone(), std::fs::read_to_string(file).unwrap_or_default()),
+        None => (name.to_string(), source.to_string()),
+    };
+
+    println!("    File \"{}\", line {}, in {}", file, line, function);
+
+    PrettyPrinter::new()
+        .language("rust")
+        .header(true)
+        .line_numbers(true)
+        .highlight(line)
+        .grid(true)
+        .input(Input::from_bytes(contents.as_bytes()).name(&file))
+        .line_ranges(
+            LineRanges::from(vec![LineRange::from(&format!("{}:{}", line.saturating_sub(lines_before), line)).unwrap()])
+        )
+        .print()
+        .unwrap();
+
+    // point at the exact columns, a span over several lines is underlined to the end of its first line
+    if let Some(span) = span {
+        let line_length = contents.lines().nth(line - 1).map(|l| l.chars().count()).unwrap_or(0);
+
+        let end = if span.end_line == span.line {
+            span.end_column
+        } else {
+            line_length + 1
+        };
+
+        println!(
+            "{}\x1b[1;31m{}\x1b[0m",
+            " ".repeat(GUTTER_WIDTH + span.column - 1),
+            "^".repeat(end.saturating_sub(span.column).max(1)),
+        );
+    }
+}
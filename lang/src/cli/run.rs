@@ -1,40 +1,50 @@
-use crate::utils;
-use crate::parser::parse;
-use bat::PrettyPrinter;
-use bat::line_range::{LineRanges, LineRange};
+use crate::error::ModuError;
+use crate::interpreter::Interpreter;
+use crate::parser::parse_program;
+use crate::cli::report::report;
+
+// `strict = true` in the project.toml of the directory modu is run in, at the top or under [package]
+fn strict_in_project() -> bool {
+    let contents = match std::fs::read_to_string("project.toml") {
+        Ok(contents) => contents,
+        Err(_) => return false,
+    };
+
+    let toml = match contents.parse::<toml::Value>() {
+        Ok(toml) => toml,
+        Err(_) => return false,
+    };
+
+    let strict = toml.get("strict")
+        .or_else(|| toml.get("package").and_then(|package| package.get("strict")));
+
+    strict.and_then(|strict| strict.as_bool()).unwrap_or(false)
+}
 
 pub fn run() {
     let args = std::env::args().collect::<Vec<String>>();
 
     if args.len() < 3 {
-        println!("Usage: modu run [file]");
+        println!("Usage: modu run [file] [--strict]");
         return;
     }
 
-    let file: String = std::fs::read_to_string(&args[2]).unwrap();
-
-    let context = &mut utils::create_context();
-
-    parse(&file, context).unwrap_or_else(|e| {
-        println!("\n⚠️  {}", e.0);
-        println!("Traceback (most recent call last):"); 
-        println!("    File \"{}\", line {}", &args[2], e.1);
-
-        PrettyPrinter::new()
-            .language("rust")
-            .header(true)
-            .line_numbers(true)
-            .highlight(e.1)
-            .grid(true)
-            .input_file(std::path::Path::new(&args[2]))
-            .line_ranges(
-                LineRanges::from(vec![LineRange::from(&format!("{}:{}", e.1 - 1, e.1 + 1)).unwrap()])
-            )
-            .print()
-            .unwrap();
-
-        println!("Believe this is a bug? Report it: https://github.com/Cyteon/modu/issues/new");
-            
+    let strict = args.iter().any(|arg| arg == "--strict") || strict_in_project();
+
+    let file = std::fs::read_to_string(&args[2]).unwrap_or_else(|e| {
+        report(&ModuError::io(e.to_string()), &args[2], "");
         std::process::exit(1);
     });
-}
\ No newline at end of file
+
+    let mut interpreter = Interpreter::new();
+    interpreter.context.set_strict(strict);
+
+    let result = parse_program(&file)
+        .map_err(ModuError::from)
+        .and_then(|program| interpreter.run(&program));
+
+    if let Err(e) = result {
+        report(&e, &args[2], &file);
+        std::process::exit(1);
+    }
+}
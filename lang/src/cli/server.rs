@@ -1,7 +1,12 @@
 use rouille::router;
-use crate::utils;
-use crate::parser::parse;
+use crate::error::ModuError;
+use crate::interpreter::Interpreter;
+use crate::parser::parse_program;
 use std::sync::Arc;
+use std::time::Duration;
+
+// how long a program sent to /eval can run before it is stopped
+const TIME_LIMIT: Duration = Duration::from_secs(5);
 
 pub fn server() {
     let args = std::env::args().collect::<Vec<String>>();
@@ -49,26 +54,31 @@ pub fn server() {
                     };
                 }
 
-                let context = &mut utils::create_context();
+                // request threads have a small stack, so the program runs and has its output captured on a bigger one
+                let captured = crate::eval::with_big_stack(|| {
+                    std::io::set_output_capture(Some(Default::default()));
+                    crate::eval::set_time_limit(Some(TIME_LIMIT));
 
-                std::io::set_output_capture(Some(Default::default()));
+                    parse_program(&text)
+                        .map_err(ModuError::from)
+                        .and_then(|program| Interpreter::new().run(&program))
+                        .unwrap_or_else(|e| {
+                            println!("\n⚠️ {}", e);
+                            println!("Traceback (most recent call last):");
+                            println!("    File \"<stdin>\", line {}", e.line());
+                            println!("Believe this is a bug? Report it: https://github.com/Cyteon/modu/issues/new");
+                        });
 
-                parse(&text, context).unwrap_or_else(|e| {
-                    println!("\n⚠️ {}", e.0);
-                    println!("Traceback (most recent call last):");
-                    println!("    File \"<stdin>\", line {}", e.1);
-                    println!("Believe this is a bug? Report it: https://github.com/Cyteon/modu/issues/new");
+                    String::from_utf8(
+                        Arc::try_unwrap(
+                            std::io::set_output_capture(None).unwrap()
+                        )
+                            .unwrap()
+                            .into_inner()
+                            .unwrap()
+                    ).unwrap()
                 });
 
-                let captured = String::from_utf8(
-                    Arc::try_unwrap(
-                        std::io::set_output_capture(None).unwrap()
-                    )
-                        .unwrap()
-                        .into_inner()
-                        .unwrap()
-                ).unwrap();
-
                 rouille::Response {
                     status_code: 200,
                     headers: vec![
@@ -0,0 +1,231 @@
+use crate::value::{DefinedIn, Function, Value};
+
+use std::{cell::RefCell, collections::HashMap, rc::{Rc, Weak}};
+
+#[derive(Default)]
+struct Scope {
+    variables: HashMap<String, Value>,
+    parent: Option<Environment>,
+    strict: bool, // only set on the global scope
+    depth: usize, // how many function calls deep the code running in this scope is
+}
+
+// a scope and the scopes around it, cloning it gives another handle to the same scope
+//
+// the file being run gets the global scope, every function call gets a new scope inside the globals of the file
+// the function is from, blocks like if and while share the scope they are in
+#[derive(Clone, Default)]
+pub struct Environment {
+    scope: Rc<RefCell<Scope>>,
+}
+
+// functions hold the scope they were defined in, which usually holds the function again, so this doesn't print the variables
+impl std::fmt::Debug for Environment {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Environment")
+    }
+}
+
+// two handles are equal when they are the same scope
+impl PartialEq for Environment {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.scope, &other.scope)
+    }
+}
+
+// a handle that doesn't keep the scope alive
+#[derive(Clone)]
+pub struct WeakEnvironment {
+    scope: Weak<RefCell<Scope>>,
+}
+
+impl std::fmt::Debug for WeakEnvironment {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "WeakEnvironment")
+    }
+}
+
+impl PartialEq for WeakEnvironment {
+    fn eq(&self, other: &Self) -> bool {
+        Weak::ptr_eq(&self.scope, &other.scope)
+    }
+}
+
+impl WeakEnvironment {
+    // the scope, if something else still keeps it alive
+    pub fn upgrade(&self) -> Option<Environment> {
+        self.scope.upgrade().map(|scope| Environment { scope })
+    }
+}
+
+impl Environment {
+    pub fn from_map(variables: HashMap<String, Value>) -> Self {
+        Environment {
+            scope: Rc::new(RefCell::new(Scope { variables, parent: None, strict: false, depth: 0 })),
+        }
+    }
+
+    // a new scope inside this one
+    pub fn child(&self) -> Self {
+        Environment {
+            scope: Rc::new(RefCell::new(Scope { variables: HashMap::new(), parent: Some(self.clone()), strict: false, depth: self.depth() })),
+        }
+    }
+
+    // looks for a variable here, then in every scope around this one
+    pub fn get(&self, name: &str) -> Option<Value> {
+        let scope = self.scope.borrow();
+
+        match scope.variables.get(name) {
+            Some(value) => Some(Self::loaded(value)),
+            None => scope.parent.as_ref().and_then(|parent| parent.get(name)),
+        }
+    }
+
+    // defines a variable in this scope, a variable with the same name in an outer scope is shadowed, not changed
+    pub fn insert(&self, name: String, value: Value) {
+        let value = self.stored(value);
+
+        self.scope.borrow_mut().variables.insert(name, value);
+    }
+
+    // changes the variable in the closest scope that has it, returns false if no scope has it
+    pub fn assign(&self, name: &str, value: Value) -> bool {
+        let mut scope = self.scope.borrow_mut();
+
+        if let Some(variable) = scope.variables.get_mut(name) {
+            *variable = self.stored(value);
+            return true;
+        }
+
+        match &scope.parent {
+            Some(parent) => parent.assign(name, value),
+            None => false,
+        }
+    }
+
+    // in strict mode using a variable that doesn't exist is an error instead of null
+    pub fn set_strict(&self, strict: bool) {
+        self.scope.borrow_mut().strict = strict;
+    }
+
+    pub fn is_strict(&self) -> bool {
+        let scope = self.scope.borrow();
+
+        scope.strict || scope.parent.as_ref().is_some_and(|parent| parent.is_strict())
+    }
+
+    // a function's scope is one call deeper than the scope it was called from, not the one it was defined in
+    pub fn set_depth(&self, depth: usize) {
+        self.scope.borrow_mut().depth = depth;
+    }
+
+    pub fn depth(&self) -> usize {
+        self.scope.borrow().depth
+    }
+
+    // the names of every variable that can be seen from here
+    pub fn names(&self) -> Vec<String> {
+        let scope = self.scope.borrow();
+        let mut names = scope.variables.keys().cloned().collect::<Vec<String>>();
+
+        if let Some(parent) = &scope.parent {
+            names.extend(parent.names());
+        }
+
+        names
+    }
+
+    // the variables defined in this scope, without the ones around it
+    pub fn variables(&self) -> HashMap<String, Value> {
+        self.scope.borrow().variables.iter().map(|(name, value)| (name.clone(), Self::loaded(value))).collect()
+    }
+
+    pub fn downgrade(&self) -> WeakEnvironment {
+        WeakEnvironment { scope: Rc::downgrade(&self.scope) }
+    }
+
+    // a function kept in the scope it was defined in would keep that scope alive through itself, even after the call
+    // it belongs to is over, so it only holds the scope weakly while it is stored there
+    //
+    // a function kept in an array or object, or in a scope around the one it was defined in, still holds it strongly,
+    // so a scope that ends up inside of one of those is never freed
+    fn stored(&self, value: Value) -> Value {
+        match &value {
+            Value::Function(function) if function.env == DefinedIn::Scope(self.clone()) => {
+                Value::Function(Rc::new(Function { env: DefinedIn::Weak(self.downgrade()), ..Function::clone(function) }))
+            }
+
+            _ => value,
+        }
+    }
+
+    // a function read out of a scope holds it strongly again, it could be returned or stored somewhere else
+    fn loaded(value: &Value) -> Value {
+        if let Value::Function(function) = value {
+            if let DefinedIn::Weak(scope) = &function.env {
+                if let Some(scope) = scope.upgrade() {
+                    return Value::Function(Rc::new(Function { env: DefinedIn::Scope(scope), ..Function::clone(function) }));
+                }
+            }
+        }
+
+        value.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_goes_outwards() {
+        let global = Environment::default();
+        global.insert("a".to_string(), Value::Int(1));
+
+        let local = global.child();
+        local.insert("b".to_string(), Value::Int(2));
+
+        assert_eq!(local.get("a"), Some(Value::Int(1)));
+        assert_eq!(local.get("b"), Some(Value::Int(2)));
+        assert_eq!(global.get("b"), None);
+    }
+
+    #[test]
+    fn insert_shadows_assign_changes() {
+        let global = Environment::default();
+        global.insert("a".to_string(), Value::Int(1));
+
+        let local = global.child();
+        local.insert("a".to_string(), Value::Int(2));
+
+        assert_eq!(global.get("a"), Some(Value::Int(1)));
+        assert_eq!(local.get("a"), Some(Value::Int(2)));
+
+        let other = global.child();
+
+        assert_eq!(other.assign("a", Value::Int(3)), true);
+        assert_eq!(global.get("a"), Some(Value::Int(3)));
+        assert_eq!(other.assign("missing", Value::Null), false);
+    }
+
+    #[test]
+    fn functions_dont_keep_their_scope_alive() {
+        let global = Environment::default();
+        let local = global.child();
+
+        let function = Function { name: "f".to_string(), args: vec![], body: Rc::new(vec![]), file: None, env: DefinedIn::Scope(local.clone()) };
+        local.insert("f".to_string(), Value::Function(Rc::new(function)));
+
+        // read out of the scope it holds it strongly again
+        match local.get("f") {
+            Some(Value::Function(function)) => assert_eq!(function.env, DefinedIn::Scope(local.clone())),
+            _ => panic!("Expected a function"),
+        }
+
+        let weak = local.downgrade();
+        drop(local);
+
+        assert!(weak.upgrade().is_none());
+    }
+}
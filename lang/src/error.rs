@@ -0,0 +1,219 @@
+use crate::ast::Span;
+use crate::parser::ParseError;
+
+// a function call that was active when the error happened
+#[derive(Debug, PartialEq, Clone)]
+pub struct Frame {
+    pub name: String,
+    pub file: Option<String>,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct ErrorInfo {
+    pub message: String,
+    pub file: Option<String>, // None for the file being run
+    pub span: Option<Span>,
+    pub stack: Vec<Frame>, // outermost call first
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ModuError {
+    Lex(ErrorInfo),
+    Parse(ErrorInfo),
+    Type(ErrorInfo),
+    Name(ErrorInfo),
+    Runtime(ErrorInfo),
+    Io(ErrorInfo),
+    Thrown(ErrorInfo), // from a throw statement
+}
+
+impl ModuError {
+    fn info_from(message: impl Into<String>) -> ErrorInfo {
+        ErrorInfo {
+            message: message.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn type_error(message: impl Into<String>) -> Self {
+        ModuError::Type(Self::info_from(message))
+    }
+
+    pub fn name(message: impl Into<String>) -> Self {
+        ModuError::Name(Self::info_from(message))
+    }
+
+    pub fn runtime(message: impl Into<String>) -> Self {
+        ModuError::Runtime(Self::info_from(message))
+    }
+
+    pub fn io(message: impl Into<String>) -> Self {
+        ModuError::Io(Self::info_from(message))
+    }
+
+    pub fn thrown(message: impl Into<String>) -> Self {
+        ModuError::Thrown(Self::info_from(message))
+    }
+
+    // the reverse of kind(), so a caught error can be thrown again as what it was
+    pub fn from_kind(kind: &str, message: impl Into<String>) -> Self {
+        let info = Self::info_from(message);
+
+        match kind {
+            "LexError" => ModuError::Lex(info),
+            "SyntaxError" => ModuError::Parse(info),
+            "TypeError" => ModuError::Type(info),
+            "NameError" => ModuError::Name(info),
+            "RuntimeError" => ModuError::Runtime(info),
+            "IOError" => ModuError::Io(info),
+            _ => ModuError::Thrown(info),
+        }
+    }
+
+    pub fn info(&self) -> &ErrorInfo {
+        match self {
+            ModuError::Lex(info) | ModuError::Parse(info) | ModuError::Type(info)
+            | ModuError::Name(info) | ModuError::Runtime(info) | ModuError::Io(info)
+            | ModuError::Thrown(info) => info,
+        }
+    }
+
+    pub fn info_mut(&mut self) -> &mut ErrorInfo {
+        match self {
+            ModuError::Lex(info) | ModuError::Parse(info) | ModuError::Type(info)
+            | ModuError::Name(info) | ModuError::Runtime(info) | ModuError::Io(info)
+            | ModuError::Thrown(info) => info,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.info().message
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ModuError::Lex(_) => "LexError",
+            ModuError::Parse(_) => "SyntaxError",
+            ModuError::Type(_) => "TypeError",
+            ModuError::Name(_) => "NameError",
+            ModuError::Runtime(_) => "RuntimeError",
+            ModuError::Io(_) => "IOError",
+            ModuError::Thrown(_) => "Error",
+        }
+    }
+
+    pub fn line(&self) -> usize {
+        self.info().span.map(|span| span.line).unwrap_or(1)
+    }
+
+    // the innermost node with a span is where the error happened, so outer ones don't override it
+    pub fn with_span(mut self, span: Option<Span>) -> Self {
+        if self.info().span.is_none() {
+            self.info_mut().span = span;
+        }
+
+        self
+    }
+
+    // called while unwinding out of `name`, which was called at `span`
+    // the code that failed so far ran in `file`, the file of the call site is only known one call further out
+    pub fn with_frame(mut self, name: impl Into<String>, file: Option<String>, span: Span) -> Self {
+        let info = self.info_mut();
+
+        let inner_file = match info.stack.first_mut() {
+            Some(frame) => &mut frame.file,
+            None => &mut info.file,
+        };
+
+        if inner_file.is_none() {
+            *inner_file = file;
+        }
+
+        info.stack.insert(0, Frame {
+            name: name.into(),
+            file: None,
+            span,
+        });
+
+        self
+    }
+
+    // internal functions don't know where they were called from, calls they made get the span of the internal call
+    pub fn with_call_site(mut self, span: Span) -> Self {
+        for frame in self.info_mut().stack.iter_mut() {
+            if frame.span == Span::default() {
+                frame.span = span;
+            }
+        }
+
+        self
+    }
+}
+
+impl std::fmt::Display for ModuError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}: {}", self.kind(), self.message())
+    }
+}
+
+impl From<ParseError> for ModuError {
+    fn from(err: ParseError) -> Self {
+        let info = ErrorInfo {
+            message: err.message,
+            span: Some(err.span),
+            ..Default::default()
+        };
+
+        if err.invalid_token {
+            ModuError::Lex(info)
+        } else {
+            ModuError::Parse(info)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn innermost_span_wins() {
+        let inner = Span { line: 2, column: 5, end_line: 2, end_column: 8 };
+        let outer = Span { line: 1, column: 1, end_line: 3, end_column: 2 };
+
+        let err = ModuError::name("Variable x not found").with_span(Some(inner)).with_span(Some(outer));
+
+        assert_eq!(err.info().span, Some(inner));
+        assert_eq!(err.to_string(), "NameError: Variable x not found");
+    }
+
+    #[test]
+    fn kind_round_trips() {
+        for err in [ModuError::type_error("a"), ModuError::io("b"), ModuError::thrown("c")] {
+            assert_eq!(ModuError::from_kind(err.kind(), err.message()), err);
+        }
+    }
+
+    #[test]
+    fn frames_resolve_files() {
+        let site = Span { line: 4, column: 1, end_line: 4, end_column: 6 };
+        let import = Span { line: 1, column: 1, end_line: 1, end_column: 20 };
+
+        // greet from lib.modu fails, it was called by run in other.modu, which the main file imported
+        let err = ModuError::runtime("oops")
+            .with_span(Some(Span::default()))
+            .with_frame("greet", Some("lib.modu".to_string()), site)
+            .with_frame("run", Some("other.modu".to_string()), site)
+            .with_frame("<module>", Some("other.modu".to_string()), import);
+
+        let info = err.info();
+
+        assert_eq!(info.file, Some("lib.modu".to_string()));
+        assert_eq!(info.stack.iter().map(|f| (f.name.as_str(), f.file.clone())).collect::<Vec<_>>(), vec![
+            ("<module>", None),
+            ("run", Some("other.modu".to_string())),
+            ("greet", Some("other.modu".to_string())),
+        ]);
+    }
+}
@@ -1,89 +1,634 @@
-use crate::ast::AST;
+use crate::ast::{AST, Span};
+use crate::environment::Environment;
+use crate::error::ModuError;
+use crate::value::{DefinedIn, Function, Value};
 
-use std::{collections::HashMap, path::PathBuf};
+use std::{cell::Cell, collections::HashMap, path::PathBuf, rc::Rc};
+use std::time::{Duration, Instant};
 use crate::utils;
-use crate::packages::get_package;
+use crate::packages::{array, get_package, json, regex};
+
+static DISABLED_ON_SERVER: [&str; 6] = ["file", "os", "path", "ffi", "http", "httpserver"];
+
+// modu code runs on a thread with a stack of STACK_SIZE, big enough for MAX_CALL_DEPTH calls so deep recursion is an
+// error instead of a stack overflow
+//
+// a call takes about 10KB of it in a release build and about 140KB in a debug build, FRAME_SIZE leaves room on top
+// of that for the expressions inside of the call
+const MAX_CALL_DEPTH: usize = 1000;
+const FRAME_SIZE: usize = if cfg!(debug_assertions) { 256 * 1024 } else { 32 * 1024 };
+pub const STACK_SIZE: usize = MAX_CALL_DEPTH * FRAME_SIZE;
+
+// runs `f` on a thread with a STACK_SIZE stack and waits for it
+pub fn with_big_stack<T: Send>(f: impl FnOnce() -> T + Send) -> T {
+    std::thread::scope(|scope| {
+        let thread = std::thread::Builder::new()
+            .stack_size(STACK_SIZE)
+            .spawn_scoped(scope, f)
+            .expect("could not start the interpreter thread");
+
+        match thread.join() {
+            Ok(value) => value,
+            Err(panic) => std::panic::resume_unwind(panic),
+        }
+    })
+}
 
-static DISABLED_ON_SERVER: [&str; 3] = ["file", "os", "ffi"];
+thread_local! {
+    // when the program running on this thread has to stop, only the server sets one
+    static DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+// the program running on this thread fails once `limit` has passed, it is checked every loop iteration and call
+pub fn set_time_limit(limit: Option<Duration>) {
+    DEADLINE.with(|deadline| deadline.set(limit.map(|limit| Instant::now() + limit)));
+}
+
+fn check_deadline() -> Result<(), ModuError> {
+    match DEADLINE.with(Cell::get) {
+        Some(deadline) if Instant::now() >= deadline => Err(ModuError::runtime("Time limit exceeded")),
+        _ => Ok(()),
+    }
+}
+
+// how a statement finished, return, break and continue are handed outwards to the function or loop they belong to
+#[derive(Debug, PartialEq)]
+pub enum Flow {
+    Next,
+    Return(Value),
+    Break,
+    Continue,
+}
+
+// runs a block body, stopping early at a return, break or continue
+fn exec_body(body: Vec<AST>, context: &mut Environment) -> Result<Flow, ModuError> {
+    for stmt in body {
+        match exec(stmt, context)? {
+            Flow::Next => {}
+
+            flow => {
+                return Ok(flow);
+            }
+        }
+    }
+
+    Ok(Flow::Next)
+}
+
+// applies an arithmetic operator, promoting to a float when either side is one
+fn arithmetic(left: Value, right: Value, verb: &str, int_op: fn(i64, i64) -> Option<i64>, float_op: fn(f64, f64) -> f64) -> Result<Value, ModuError> {
+    match (left, right) {
+        (Value::Int(l), Value::Int(r)) => {
+            match int_op(l, r) {
+                Some(value) => {
+                    return Ok(Value::Int(value));
+                }
+
+                None => {
+                    return Err(ModuError::runtime("Integer overflow"));
+                }
+            }
+        }
+
+        (Value::Float(l), Value::Float(r)) => {
+            return Ok(Value::Float(float_op(l, r)));
+        }
+
+        (Value::Int(l), Value::Float(r)) => {
+            return Ok(Value::Float(float_op(l as f64, r)));
+        }
+
+        (Value::Float(l), Value::Int(r)) => {
+            return Ok(Value::Float(float_op(l, r as f64)));
+        }
+
+        (l, r) => {
+            return Err(ModuError::type_error(format!("Cannot {} {:?} and {:?}", verb, l, r)));
+        }
+    }
+}
+
+// null and false are falsy, everything else is truthy
+pub fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null | Value::Bool(false) => false,
+        _ => true,
+    }
+}
+
+fn loop_items(iterable: Value) -> Result<Vec<Value>, ModuError> {
+    match iterable {
+        Value::List(items) => {
+            Ok(items.borrow().clone())
+        }
+
+        Value::Str(value) => {
+            Ok(value.chars().map(|c| Value::Str(c.to_string())).collect())
+        }
+
+        Value::Map(_) => Err(ModuError::type_error("Cannot iterate over an object, only arrays and strings")),
+
+        val => Err(ModuError::type_error(format!("Cannot iterate over {:?}", val))),
+    }
+}
+
+// what to call the expression a property is read from in errors, like `a.b` or `json.parse()`
+fn object_name(object: &AST) -> String {
+    match object {
+        AST::Identifer(name, _) => name.clone(),
+        AST::PropertyAccess { object, property, .. } => format!("{}.{}", object_name(object), property),
+        AST::PropertyCall { object, property, .. } => format!("{}.{}()", object_name(object), property),
+        AST::Index { object, .. } => format!("{}[...]", object_name(object)),
+        AST::Call { name, .. } => format!("{}()", name),
+        AST::CallExpression { callee, .. } => format!("{}()", object_name(callee)),
+        _ => "value".to_string(),
+    }
+}
+
+// `value.property`, arrays only have a length
+fn get_property(value: Value, name: &str, property: &str) -> Result<Value, ModuError> {
+    match value {
+        Value::Map(properties) => {
+            match properties.borrow().get(property) {
+                Some(value) => Ok(value.clone()),
+                None => Err(ModuError::name(format!("Property {} not found in object {}", property, name))),
+            }
+        }
+
+        Value::Module { members, .. } => {
+            match members.get(property) {
+                Some(value) => Ok(value.clone()),
+                None => Err(ModuError::name(format!("Property {} not found in object {}", property, name))),
+            }
+        }
+
+        Value::List(items) if property == "length" => {
+            Ok(Value::Int(items.borrow().len() as i64))
+        }
+
+        Value::List(_) => Err(ModuError::name(format!("Property {} not found in array {}", property, name))),
+
+        _ => Err(ModuError::type_error(format!("{} is not an object", name))),
+    }
+}
+
+// "x is not an object" is about the object, so it points at it rather than the whole access
+fn type_error_at(e: ModuError, object_span: Option<Span>) -> ModuError {
+    match e {
+        ModuError::Type(_) => e.with_span(object_span),
+        e => e,
+    }
+}
+
+// `value[index]`
+fn get_index(value: Value, index: Value) -> Result<Value, ModuError> {
+    match (value, index) {
+        (Value::List(items), Value::Int(i)) => {
+            let items = items.borrow();
+
+            match usize::try_from(i).ok().and_then(|i| items.get(i)) {
+                Some(item) => Ok(item.clone()),
+                None => Err(ModuError::runtime(format!("Index {} is out of range for an array of length {}", i, items.len()))),
+            }
+        }
+
+        (Value::Map(properties), Value::Str(key)) => {
+            match properties.borrow().get(&key) {
+                Some(value) => Ok(value.clone()),
+                None => Err(ModuError::name(format!("Property {} not found", key))),
+            }
+        }
+
+        (Value::Str(value), Value::Int(i)) => {
+            let c = usize::try_from(i).ok().and_then(|i| value.chars().nth(i));
+
+            match c {
+                Some(c) => Ok(Value::Str(c.to_string())),
+                None => Err(ModuError::runtime(format!("Index {} is out of range for a string of length {}", i, value.chars().count()))),
+            }
+        }
+
+        (value, index) => Err(ModuError::type_error(format!("Cannot index {} with {}", value, index))),
+    }
+}
+
+// sets `index` of an array or object in place, an array grows by one when setting the index right after its end
+fn set_index(value: Value, index: Value, item: Value) -> Result<(), ModuError> {
+    match (value, index) {
+        (Value::List(items), Value::Int(i)) => {
+            let mut items = items.borrow_mut();
+            let length = items.len();
+
+            match usize::try_from(i) {
+                Ok(i) if i < length => {
+                    items[i] = item;
+                }
+
+                Ok(i) if i == length => {
+                    items.push(item);
+                }
 
-pub fn eval(expr: AST, context: &mut HashMap<String, AST>) -> Result<AST, String> {
-    match expr {
-        AST::Call { name, args, line: _ } => {
-            match name.as_str() {
                 _ => {
-                    match context.get(&name) {
-                        Some(value) => {
-                            match value {
-                                AST::Function { name: _, args: f_args, body, line: _ } => {
-                                    if args.len() == f_args.len() || f_args.last().unwrap() == "__args__" {
-                                        let mut new_context = context.clone();
-
-                                        for (i, arg) in f_args.iter().enumerate() {
-                                            new_context.insert(arg.clone(), eval(args[i].clone(), &mut new_context.clone())?);
-                                        }
-
-                                        let mut depth = 0;
-
-                                        for expr in body {
-                                            if depth > 100 {
-                                                return Err("Maximum recursion depth exceeded".to_string());
-                                            }
-
-                                            if let AST::Return { value, line: _ } = expr {
-                                                return eval(*value.clone(), &mut new_context);
-                                            }
-
-                                            depth += 1;
-
-                                            let ast: AST = eval(expr.clone(), &mut new_context)?;
-
-                                            if let AST::Return { value, line: _ } = ast {
-                                                return Ok(*value);
-                                            }
-                                        }
-                                    } else {                                        
-                                        return Err(format!("{} takes {} argument(s)", name, f_args.len()));
-                                    }
-                                }
+                    return Err(ModuError::runtime(format!("Index {} is out of range for an array of length {}", i, length)));
+                }
+            }
 
-                                AST::InternalFunction { name: _, args: f_args, call_fn } => {
-                                    if args.len() == f_args.len() || f_args.last().unwrap() == "__args__" {
-                                        return Ok(call_fn(args, context)?.0);
-                                    } else {
-                                        return Err(format!("{} takes {} argument(s)", name, f_args.len()));
-                                    }
-                                }
+            Ok(())
+        }
 
-                                _ => {
-                                    return Err(format!("{} is not a function", name));
-                                }
-                            }
-                        }
+        (Value::Map(properties), Value::Str(key)) => {
+            properties.borrow_mut().insert(key, item);
 
-                        None => {
-                            return Err(format!("Function {} not found", name));
-                        }
+            Ok(())
+        }
+
+        (value, index) => Err(ModuError::type_error(format!("Cannot set index {} on {}", index, value))),
+    }
+}
+
+// where an assignment stores its value, a variable or an item of an array or object
+enum Place {
+    Variable(String),
+    Item(Value, Value),
+}
+
+// evaluates the parts of an assignment target once, so `a[f()] += 1` only calls f once
+fn place_of(target: AST, context: &mut Environment) -> Result<Place, ModuError> {
+    match target {
+        AST::Identifer(name, _) => {
+            return Ok(Place::Variable(name));
+        }
+
+        AST::PropertyAccess { object, property, span: _ } => {
+            return Ok(Place::Item(eval(*object, context)?, Value::Str(property)));
+        }
+
+        AST::Index { object, index, span: _ } => {
+            let (object, index) = (eval(*object, context)?, eval(*index, context)?);
+
+            return Ok(Place::Item(object, index));
+        }
+
+        _ => {
+            return Err(ModuError::runtime("Cannot assign to this expression"));
+        }
+    }
+}
+
+fn read_place(place: &Place, context: &Environment) -> Result<Value, ModuError> {
+    match place {
+        Place::Variable(name) => {
+            match context.get(name) {
+                Some(value) => Ok(value),
+                None => Err(ModuError::name(format!("Variable {} not found", name))),
+            }
+        }
+
+        Place::Item(object, index) => get_index(object.clone(), index.clone()),
+    }
+}
+
+// a variable has to be declared with `let` before it can be assigned
+fn write_place(place: Place, value: Value, context: &mut Environment) -> Result<(), ModuError> {
+    match place {
+        Place::Variable(name) => {
+            if context.assign(&name, value) {
+                return Ok(());
+            }
+
+            return Err(ModuError::name(format!("Variable {} not found", name)));
+        }
+
+        Place::Item(object, index) => set_index(object, index, value),
+    }
+}
+
+fn add(left: Value, right: Value) -> Result<Value, ModuError> {
+    match (left, right) {
+        (Value::Str(l), Value::Str(r)) => {
+            return Ok(Value::Str(format!("{}{}", l, r)));
+        }
+
+        // a string on the left joins with whatever is on the right
+        (Value::Str(l), r @ (Value::Int(_) | Value::Float(_) | Value::Bool(_) | Value::Null)) => {
+            return Ok(Value::Str(format!("{}{}", l, r)));
+        }
+
+        (l, r) => {
+            return arithmetic(l, r, "add", |l, r| l.checked_add(r), |l, r| l + r);
+        }
+    }
+}
+
+fn subtract(left: Value, right: Value) -> Result<Value, ModuError> {
+    match (left, right) {
+        (Value::Null, Value::Int(r)) => {
+            return Ok(Value::Int(-r));
+        }
+
+        (Value::Null, Value::Float(r)) => {
+            return Ok(Value::Float(-r));
+        }
+
+        (Value::Int(l), Value::Null) => {
+            return Ok(Value::Int(l));
+        }
+
+        (Value::Float(l), Value::Null) => {
+            return Ok(Value::Float(l));
+        }
+
+        (l, r) => {
+            return arithmetic(l, r, "subtract", |l, r| l.checked_sub(r), |l, r| l - r);
+        }
+    }
+}
+
+// what a catch block gets to look at
+fn error_object(err: &ModuError) -> Value {
+    let mut properties = HashMap::new();
+
+    properties.insert("message".to_string(), Value::Str(err.message().to_string()));
+    properties.insert("kind".to_string(), Value::Str(err.kind().to_string()));
+    properties.insert("line".to_string(), Value::Int(err.line() as i64));
+
+    Value::map(properties)
+}
+
+// functions an imported file defined remember where they came from, so errors inside them point there
+// gives back the variables of the file, for the importer
+fn mark_functions(module: &Environment, path: &str) -> HashMap<String, Value> {
+    let mut variables = module.variables();
+
+    for (name, value) in variables.iter_mut() {
+        if let Value::Function(function) = value {
+            if function.file.is_none() {
+                *function = Rc::new(Function { file: Some(path.to_string()), ..Function::clone(function) });
+                module.insert(name.clone(), value.clone());
+            }
+        }
+    }
+
+    variables
+}
+
+// calls a function value with arguments that are already evaluated, `span` is where it was called from
+// internal functions use this to call functions they were given
+pub fn call_function(function: &Value, args: Vec<Value>, span: Span, context: &mut Environment) -> Result<Value, ModuError> {
+    match function {
+        Value::Function(function) => {
+            let Function { name, args: f_args, body, file, env } = function.as_ref();
+
+            if args.len() != f_args.len() && f_args.last().map(String::as_str) != Some("__args__") {
+                return Err(ModuError::type_error(format!("{} takes {} argument(s)", name, f_args.len())));
+            }
+
+            check_deadline()?;
+
+            if context.depth() >= MAX_CALL_DEPTH {
+                return Err(ModuError::runtime("Maximum recursion depth exceeded"));
+            }
+
+            // the function sees the scope it was defined in, not the variables of the caller
+            let env = match env {
+                DefinedIn::Scope(env) => env.clone(),
+                DefinedIn::Weak(env) => env.upgrade().ok_or_else(|| ModuError::runtime(format!("The scope {} was defined in no longer exists", name)))?,
+            };
+
+            let mut new_context = env.child();
+            new_context.set_depth(context.depth() + 1);
+
+            for (arg, value) in f_args.iter().zip(args) {
+                new_context.insert(arg.clone(), value);
+            }
+
+            for stmt in body.iter() {
+                let flow = exec(stmt.clone(), &mut new_context)
+                    .map_err(|e| e.with_frame(name, file.clone(), span))?;
+
+                match flow {
+                    Flow::Next => {}
+
+                    Flow::Return(value) => {
+                        return Ok(value);
                     }
+
+                    Flow::Break => {
+                        return Err(ModuError::runtime("'break' outside of a loop"));
+                    }
+
+                    Flow::Continue => {
+                        return Err(ModuError::runtime("'continue' outside of a loop"));
+                    }
+                }
+            }
+
+            Ok(Value::Null)
+        }
+
+        Value::NativeFunction { name, args: f_args, call_fn } => {
+            if args.len() == f_args.len() || f_args.last().map(String::as_str) == Some("__args__") {
+                call_fn(args, context).map_err(|e| e.with_call_site(span))
+            } else {
+                Err(ModuError::type_error(format!("{} takes {} argument(s)", name, f_args.len())))
+            }
+        }
+
+        value => Err(ModuError::type_error(format!("{:?} is not a function", value))),
+    }
+}
+
+// calls a method of an array or object, it gets the value it was called on as its first argument
+fn is_method(function: &Value) -> bool {
+    match function {
+        Value::NativeFunction { args, .. } => args.first().map(String::as_str) == Some("self"),
+        _ => false,
+    }
+}
+
+fn call_method(method: &Value, this: Value, args: Vec<Value>, span: Span, context: &mut Environment) -> Result<Value, ModuError> {
+    match method {
+        Value::NativeFunction { name, args: f_args, call_fn } => {
+            let variadic = f_args.last().map(String::as_str) == Some("__args__");
+
+            if args.len() != f_args.len() - 1 && !variadic {
+                return Err(ModuError::type_error(format!("{} takes {} argument(s)", name, f_args.len() - 1)));
+            }
+
+            let mut values = vec![this];
+            values.extend(args);
+
+            call_fn(values, context).map_err(|e| e.with_call_site(span))
+        }
+
+        value => Err(ModuError::type_error(format!("{:?} is not a method", value))),
+    }
+}
+
+fn eval_args(args: Vec<AST>, context: &mut Environment) -> Result<Vec<Value>, ModuError> {
+    let mut values = Vec::new();
+
+    for arg in args {
+        values.push(eval(arg, context)?);
+    }
+
+    Ok(values)
+}
+
+// runs a statement, the error it fails with points at the innermost node that has a span
+pub fn exec(stmt: AST, context: &mut Environment) -> Result<Flow, ModuError> {
+    let span = stmt.span();
+
+    exec_node(stmt, context).map_err(|e| e.with_span(span))
+}
+
+fn exec_node(stmt: AST, context: &mut Environment) -> Result<Flow, ModuError> {
+    match stmt {
+        AST::Return { value, span: _ } => {
+            return Ok(Flow::Return(eval(*value, context)?));
+        }
+
+        AST::Break { .. } => {
+            return Ok(Flow::Break);
+        }
+
+        AST::Continue { .. } => {
+            return Ok(Flow::Continue);
+        }
+
+        AST::IfStatement { condition, body, else_body, span: _ } => {
+            if is_truthy(&eval(*condition, context)?) {
+                return exec_body(body, context);
+            }
+
+            if let Some(else_body) = else_body {
+                return exec_body(else_body, context);
+            }
+        }
+
+        AST::WhileLoop { condition, body, span: _ } => {
+            loop {
+                check_deadline()?;
+
+                if !is_truthy(&eval(*condition.clone(), context)?) {
+                    break;
+                }
+
+                match exec_body(body.clone(), context)? {
+                    Flow::Break => {
+                        break;
+                    }
+
+                    flow @ Flow::Return(_) => {
+                        return Ok(flow);
+                    }
+
+                    _ => {}
+                }
+            }
+        }
+
+        AST::ForLoop { variable, iterable, body, span: _ } => {
+            let iterable = eval(*iterable, context)?;
+
+            for item in loop_items(iterable)? {
+                check_deadline()?;
+
+                context.insert(variable.clone(), item);
+
+                match exec_body(body.clone(), context)? {
+                    Flow::Break => {
+                        break;
+                    }
+
+                    flow @ Flow::Return(_) => {
+                        return Ok(flow);
+                    }
+
+                    _ => {}
                 }
             }
         }
 
-        AST::LetDeclaration { name, value, line: _ } => {
+        AST::TryCatch { body, error_name, catch_body, span: _ } => {
+            match exec_body(body, context) {
+                Ok(flow) => {
+                    return Ok(flow);
+                }
+
+                Err(e) => {
+                    context.insert(error_name, error_object(&e));
+
+                    return exec_body(catch_body, context);
+                }
+            }
+        }
+
+        _ => {
+            eval(stmt, context)?;
+        }
+    }
+
+    Ok(Flow::Next)
+}
+
+pub fn eval(expr: AST, context: &mut Environment) -> Result<Value, ModuError> {
+    let span = expr.span();
+
+    eval_node(expr, context).map_err(|e| e.with_span(span))
+}
+
+fn eval_node(expr: AST, context: &mut Environment) -> Result<Value, ModuError> {
+    match expr {
+        AST::Call { name, args, span } => {
+            match context.get(&name) {
+                Some(function @ (Value::Function(_) | Value::NativeFunction { .. })) => {
+                    let values = eval_args(args, context)?;
+
+                    return call_function(&function, values, span, context);
+                }
+
+                Some(_) => {
+                    return Err(ModuError::type_error(format!("{} is not a function", name)));
+                }
+
+                None => {
+                    return Err(ModuError::name(format!("Function {} not found", name)));
+                }
+            }
+        }
+
+        AST::CallExpression { callee, args, span } => {
+            let name = object_name(&callee);
+
+            match eval(*callee, context)? {
+                function @ (Value::Function(_) | Value::NativeFunction { .. }) => {
+                    let values = eval_args(args, context)?;
+
+                    return call_function(&function, values, span, context);
+                }
+
+                _ => {
+                    return Err(ModuError::type_error(format!("{} is not a function", name)));
+                }
+            }
+        }
+
+        AST::LetDeclaration { name, value, span: _ } => {
             if utils::is_reserved(name.as_ref().unwrap_or(&"".to_string())) {
-                return Err(format!("{} is a reserved keyword", name.as_ref().unwrap()));
+                return Err(ModuError::name(format!("{} is a reserved keyword", name.as_ref().unwrap())));
             }
 
             if let Some(name) = name {
                 match *value {
-                    AST::Identifer(i_name) => {
+                    AST::Identifer(i_name, i_span) => {
                         match context.get(&i_name) {
                             Some(value) => {
-                                let val = eval(value.clone(), context)?;
-                                context.insert(name, val);
+                                context.insert(name, value);
                             }
 
                             None => {
-                                return Err(format!("Variable {} not found", i_name));
+                                return Err(ModuError::name(format!("Variable {} not found", i_name)).with_span(Some(i_span)));
                             }
                         }
                     }
@@ -96,17 +641,21 @@ pub fn eval(expr: AST, context: &mut HashMap<String, AST>) -> Result<AST, String
             }
         }
 
-        AST::Function { name, args, body, line: _ } => {
-            context.insert(name.clone(), AST::Function { name, args, body, line: 0 });
+        AST::Function { name, args, body, span: _ } => {
+            let function = Function { name: name.clone(), args, body: Rc::new(body), file: None, env: DefinedIn::Scope(context.clone()) };
+
+            context.insert(name, Value::Function(Rc::new(function)));
         }
 
-        AST::Semicolon => {
-            return Ok(AST::Null);
+        AST::AnonymousFunction { args, body, span: _ } => {
+            let function = Function { name: "<anonymous>".to_string(), args, body: Rc::new(body), file: None, env: DefinedIn::Scope(context.clone()) };
+
+            return Ok(Value::Function(Rc::new(function)));
         }
 
-        AST::Import { file, as_, line } => {
+        AST::Import { file, as_, span } => {
             let args = std::env::args().collect::<Vec<String>>();
-            let file = file.unwrap().replace("\"", "");
+            let file = file.unwrap();
 
             let path: PathBuf;
 
@@ -119,33 +668,38 @@ pub fn eval(expr: AST, context: &mut HashMap<String, AST>) -> Result<AST, String
             if file.ends_with(".modu") {
                 match std::fs::read_to_string(&path) {
                     Ok(file) => {
-                        let mut new_context = context.clone();
-    
+                        // the imported file runs on its own, with its own globals
+                        let mut new_context = utils::create_context();
+                        new_context.set_strict(context.is_strict());
+                        let path = path.display().to_string();
+
                         match crate::parser::parse(&file, &mut new_context) {
                             Ok(_) => {
+                                let variables = mark_functions(&new_context, &path);
+
                                 let insert_as = as_.unwrap();
 
                                 if insert_as == "*" {
-                                    for (name, value) in new_context {
+                                    for (name, value) in variables {
                                         context.insert(name, value);
                                     }
-    
-                                    return Ok(AST::Null);
+
+                                    return Ok(Value::Null);
                                 } else {
-                                    context.insert(insert_as, AST::Object { properties: new_context, line });
+                                    context.insert(insert_as.clone(), Value::Module { name: insert_as, members: Rc::new(variables) });
                                 }
                             }
-    
+
                             Err(e) => {
-                                return Err(e.0);
+                                return Err(e.with_frame("<module>", Some(path), span));
                             }
                         }
                     }
-    
+
                     Err(e) => {
                         dbg!(path);
-    
-                        return Err(e.to_string());
+
+                        return Err(ModuError::io(e.to_string()));
                     }
                 }
             } else {
@@ -153,436 +707,437 @@ pub fn eval(expr: AST, context: &mut HashMap<String, AST>) -> Result<AST, String
 
                 if args.len() > 1 && args[1] == "server" {
                     if DISABLED_ON_SERVER.contains(&file.as_str()) {
-                        return Err(format!("{} is disabled on the server", file));
+                        return Err(ModuError::runtime(format!("{} is disabled on the server", file)));
                     }
                 }
 
                 let package = get_package(&file);
 
                 if let Some(package) = package {
-                    if let AST::Object { properties, line } = package {
+                    if let Value::Module { name, members } = package {
                         let insert_as = as_.unwrap();
 
                         if insert_as == "*" {
-                            for (name, value) in properties {
-                                context.insert(name, value);
+                            for (name, value) in members.iter() {
+                                context.insert(name.clone(), value.clone());
                             }
                         } else {
-                            context.insert(insert_as, AST::Object { properties, line });
+                            context.insert(insert_as, Value::Module { name, members });
                         }
                     }
                 } else {
                     if std::fs::exists(format!(".modu/packages/{}", file)).unwrap() {
-                        let mut new_context = context.clone();
+                        let mut new_context = utils::create_context();
+                        new_context.set_strict(context.is_strict());
 
-                        let content = std::fs::read_to_string(format!(".modu/packages/{}/lib.modu", file)).unwrap();
+                        let path = format!(".modu/packages/{}/lib.modu", file);
+                        let content = std::fs::read_to_string(&path).unwrap();
 
                         match crate::parser::parse(&content, &mut new_context) {
                             Ok(_) => {
+                                let variables = mark_functions(&new_context, &path);
+
                                 let insert_as = as_.unwrap();
 
                                 if insert_as == "*" {
-                                    for (name, value) in new_context {
+                                    for (name, value) in variables {
                                         context.insert(name, value);
                                     }
 
-                                    return Ok(AST::Null);
+                                    return Ok(Value::Null);
                                 } else {
-                                    context.insert(insert_as, AST::Object { properties: new_context, line });
+                                    context.insert(insert_as, Value::Module { name: file, members: Rc::new(variables) });
                                 }
                             }
 
-                            _ => {
-                                return Err(format!("Failed to parse package {}", file));
+                            Err(e) => {
+                                return Err(e.with_frame("<module>", Some(path), span));
                             }
                         }
                     } else {
-                        return Err(format!("Package {} not found", file));
+                        return Err(ModuError::io(format!("Package {} not found", file)));
                     }
                 }
             }
         }
 
-        AST::PropertyCall { object, property, args, line: _ } => {
-            match object.clone() {
-                Some(name) => {
-                    match context.get(&name) {
-                        Some(value) => {
-                            match value {
-                                AST::Object { properties, line: _ } => {
-                                    match properties.get(property.as_ref().unwrap()) {
-                                        Some(value) => {
-                                            match value {
-                                                AST::Function { name, args: f_args, body, line: _ } => {
-                                                    if args.len() == f_args.len() || f_args.last().unwrap() == "__args__" {
-                                                        let mut new_context = context.clone();
-
-                                                        for (i, arg) in f_args.iter().enumerate() {
-                                                            new_context.insert(arg.clone(), eval(args[i].clone(), &mut new_context.clone())?);
-                                                        }
-
-                                                        new_context.remove(name);
-                                                        
-                                                        for prop in properties {
-                                                            new_context.insert(prop.0.clone(), prop.1.clone());
-                                                        }
-
-                                                        for expr in body {
-                                                            if let AST::Return { value, line: _ } = expr {
-                                                                return eval(*value.clone(), &mut new_context);
-                                                            }
-
-                                                            let ast = eval(expr.clone(), &mut new_context)?;
-
-                                                            if let AST::Return { value, line: _ } = ast {
-                                                                return Ok(*value);
-                                                            }
-                                                        }
-                                                    } else {
-                                                        return Err(format!("{} takes {} argument(s)", name, f_args.len()));
-                                                    }
-                                                }
-
-                                                AST::InternalFunction { name, args: f_args, call_fn } => {
-                                                    if args.len() == f_args.len() || f_args.last().unwrap() == "__args__" {
-                                                        return Ok(call_fn(args, context)?.0);
-                                                    } else if f_args[0] == "self" && args.len() == f_args.len() - 1 || f_args.last().unwrap() == "__args__" {
-                                                            let mut new_args = vec![AST::Object { properties: properties.clone(), line: 0 }];
-
-                                                            for arg in args {
-                                                                new_args.push(arg);
-                                                            }
-
-                                                            let result = call_fn(new_args, context)?;
-
-                                                            match result.1.clone() {
-                                                                AST::Object { properties, line: _ } => {
-                                                                    context.insert(object.unwrap(), AST::Object { properties, line: 0 });
-                                                                }
-
-                                                                _ => {}
-                                                            }
-
-                                                            return Ok(result.0);
-                                                    } else if f_args[0] == "self" {
-                                                        return Err(format!("{} takes {} argument(s)", name, f_args.len() - 1));
-                                                    } else {
-                                                        return Err(format!("{} takes {} argument(s)", name, f_args.len()));
-                                                    }
-                                                }
-
-                                                _ => {
-                                                    return Err(format!("{} on object {} is not a function", property.as_ref().unwrap(), name));
-                                                }
-                                            }
-                                        }
-
-                                        None => {
-                                            return Err(format!("Property {} not found in object {}", property.as_ref().unwrap(), name));
-                                        }
-                                    }
-                                }
+        AST::PropertyCall { object, property, args, span } => {
+            let name = object_name(&object);
+            let object_span = object.span();
 
-                                _ => {
-                                    return Err(format!("{} is not an object", name));
-                                }
-                            }
-                        }
+            let value = match *object {
+                AST::Identifer(name, span) => match context.get(&name) {
+                    Some(value) => value,
+                    None => return Err(ModuError::name(format!("Object {} not found", name)).with_span(Some(span))),
+                },
 
-                        None => {
-                            return Err(format!("Object {} not found", name));
-                        }
-                    }
+                object => eval(object, context)?,
+            };
+
+            let values = eval_args(args, context)?;
+
+            // arrays and objects have methods, which are used when an object has no property with that name
+            let method = match &value {
+                Value::List(_) => array::get_method(&property),
+                Value::Map(properties) if !properties.borrow().contains_key(&property) => json::get_method(&property),
+                Value::Regex(_) => regex::get_method(&property),
+                _ => None,
+            };
+
+            if let Some(method) = method {
+                return call_method(&method, value, values, span, context);
+            }
+
+            match get_property(value.clone(), &name, &property).map_err(|e| type_error_at(e, object_span))? {
+                // a native function stored on an object that takes "self" gets the object, like an http response's json() or an httpserver app's get()
+                method @ Value::NativeFunction { .. } if is_method(&method) => {
+                    return call_method(&method, value, values, span, context);
                 }
 
-                None => {
-                    return Err("Object appears to be null".to_string());
+                function @ (Value::Function(_) | Value::NativeFunction { .. }) => {
+                    return call_function(&function, values, span, context);
+                }
+
+                _ => {
+                    return Err(ModuError::type_error(format!("{} on object {} is not a function", property, name)));
                 }
             }
         }
 
-        AST::IsEqual { left, right, line: _ } => {
+        AST::IsEqual { left, right, span: _ } => {
             match (eval(*left, context)?, eval(*right, context)?) {
-                (AST::Number(l), AST::Number(r)) => {
-                    return Ok(AST::Boolean(l == r));
+                (Value::Int(l), Value::Int(r)) => {
+                    return Ok(Value::Bool(l == r));
+                }
+
+                (Value::Float(l), Value::Float(r)) => {
+                    return Ok(Value::Bool(l == r));
                 }
 
-                (AST::Float(l), AST::Float(r)) => {
-                    return Ok(AST::Boolean(l == r));
+                (Value::Str(l), Value::Str(r)) => {
+                    return Ok(Value::Bool(l == r));
                 }
 
-                (AST::String(l), AST::String(r)) => {
-                    return Ok(AST::Boolean(l == r));
+                (Value::Bool(l), Value::Bool(r)) => {
+                    return Ok(Value::Bool(l == r));
                 }
 
-                (AST::Boolean(l), AST::Boolean(r)) => {
-                    return Ok(AST::Boolean(l == r));
+                (Value::Null, Value::Null) => {
+                    return Ok(Value::Bool(true));
                 }
 
                 _ => {
-                    return Ok(AST::Boolean(false));
+                    return Ok(Value::Bool(false));
                 }
             }
         }
 
-        AST::IsUnequal { left, right, line: _ } => {
+        AST::IsUnequal { left, right, span: _ } => {
             match (eval(*left, context)?, eval(*right, context)?) {
-                (AST::Number(l), AST::Number(r)) => {
-                    return Ok(AST::Boolean(l != r));
+                (Value::Int(l), Value::Int(r)) => {
+                    return Ok(Value::Bool(l != r));
                 }
 
-                (AST::Float(l), AST::Float(r)) => {
-                    return Ok(AST::Boolean(l != r));
+                (Value::Float(l), Value::Float(r)) => {
+                    return Ok(Value::Bool(l != r));
                 }
 
-                (AST::String(l), AST::String(r)) => {
-                    return Ok(AST::Boolean(l != r));
+                (Value::Str(l), Value::Str(r)) => {
+                    return Ok(Value::Bool(l != r));
                 }
 
-                (AST::Boolean(l), AST::Boolean(r)) => {
-                    return Ok(AST::Boolean(l != r));
+                (Value::Bool(l), Value::Bool(r)) => {
+                    return Ok(Value::Bool(l != r));
+                }
+
+                (Value::Null, Value::Null) => {
+                    return Ok(Value::Bool(false));
                 }
 
                 _ => {
-                    return Ok(AST::Boolean(true));
+                    return Ok(Value::Bool(true));
                 }
             }
         }
 
-        AST::LessThan { left, right, line: _ } => {
+        AST::LessThan { left, right, span: _ } => {
             match (eval(*left, context)?, eval(*right, context)?) {
-                (AST::Number(l), AST::Number(r)) => {
-                    return Ok(AST::Boolean(l < r));
+                (Value::Int(l), Value::Int(r)) => {
+                    return Ok(Value::Bool(l < r));
                 }
 
-                (AST::Float(l), AST::Float(r)) => {
-                    return Ok(AST::Boolean(l < r));
+                (Value::Float(l), Value::Float(r)) => {
+                    return Ok(Value::Bool(l < r));
                 }
 
                 (val, val2) => {
-                    return Err(format!("Cannot compare {:?} and {:?}", val, val2));
+                    return Err(ModuError::type_error(format!("Cannot compare {:?} and {:?}", val, val2)));
                 }
             }
         }
 
-        AST::GreaterThan { left, right, line: _ } => {
+        AST::GreaterThan { left, right, span: _ } => {
             match (eval(*left, context)?, eval(*right, context)?) {
-                (AST::Number(l), AST::Number(r)) => {
-                    return Ok(AST::Boolean(l > r));
+                (Value::Int(l), Value::Int(r)) => {
+                    return Ok(Value::Bool(l > r));
                 }
 
-                (AST::Float(l), AST::Float(r)) => {
-                    return Ok(AST::Boolean(l > r));
+                (Value::Float(l), Value::Float(r)) => {
+                    return Ok(Value::Bool(l > r));
                 }
 
                 (val, val2) => {
-                    return Err(format!("Cannot compare {:?} and {:?}", val, val2));
+                    return Err(ModuError::type_error(format!("Cannot compare {:?} and {:?}", val, val2)));
                 }
             }
         }
 
-        AST::LessThanOrEqual { left, right, line: _ } => {
+        AST::LessThanOrEqual { left, right, span: _ } => {
             match (eval(*left, context)?, eval(*right, context)?) {
-                (AST::Number(l), AST::Number(r)) => {
-                    return Ok(AST::Boolean(l <= r));
+                (Value::Int(l), Value::Int(r)) => {
+                    return Ok(Value::Bool(l <= r));
                 }
 
-                (AST::Float(l), AST::Float(r)) => {
-                    return Ok(AST::Boolean(l <= r));
+                (Value::Float(l), Value::Float(r)) => {
+                    return Ok(Value::Bool(l <= r));
                 }
 
                 (val, val2) => {
-                    return Err(format!("Cannot compare {:?} and {:?}", val, val2));
+                    return Err(ModuError::type_error(format!("Cannot compare {:?} and {:?}", val, val2)));
                 }
             }
         }
 
-        AST::GreaterThanOrEqual { left, right, line: _ } => {
+        AST::GreaterThanOrEqual { left, right, span: _ } => {
             match (eval(*left, context)?, eval(*right, context)?) {
-                (AST::Number(l), AST::Number(r)) => {
-                    return Ok(AST::Boolean(l >= r));
+                (Value::Int(l), Value::Int(r)) => {
+                    return Ok(Value::Bool(l >= r));
                 }
 
-                (AST::Float(l), AST::Float(r)) => {
-                    return Ok(AST::Boolean(l >= r));
+                (Value::Float(l), Value::Float(r)) => {
+                    return Ok(Value::Bool(l >= r));
                 }
 
                 (val, val2) => {
-                    return Err(format!("Cannot compare {:?} and {:?}", val, val2));
+                    return Err(ModuError::type_error(format!("Cannot compare {:?} and {:?}", val, val2)));
                 }
             }
         }
 
-        AST::Exists { value, line: _ } => {
-            match eval(*value, context)? {
-                AST::Null => {
-                    return Ok(AST::Boolean(false));
-                }
+        AST::Exists { value, span: _ } => {
+            return Ok(Value::Bool(is_truthy(&eval(*value, context)?)));
+        }
 
-                AST::Boolean(false) => {
-                    return Ok(AST::Boolean(false));
-                }
+        AST::And { left, right, span: _ } => {
+            if !is_truthy(&eval(*left, context)?) {
+                return Ok(Value::Bool(false));
+            }
 
-                _ => {
-                    return Ok(AST::Boolean(true));
-                }
+            return Ok(Value::Bool(is_truthy(&eval(*right, context)?)));
+        }
+
+        AST::Or { left, right, span: _ } => {
+            if is_truthy(&eval(*left, context)?) {
+                return Ok(Value::Bool(true));
             }
+
+            return Ok(Value::Bool(is_truthy(&eval(*right, context)?)));
         }
 
-        AST::IfStatement { condition, body, line: _ } => {
-            match eval(*condition, context)? {
-                AST::Boolean(b) => {
-                    if b {
-                        for expr in body {
-                            if let AST::Return { value, line } = expr {
-                                return Ok(AST::Return { value, line });
-                            }
+        AST::Not { value, span: _ } => {
+            return Ok(Value::Bool(!is_truthy(&eval(*value, context)?)));
+        }
 
-                            eval(expr, context)?;
-                        }
-                    }
+        AST::Throw { value, span: _ } => {
+            match eval(*value, context)? {
+                // throwing a caught error again keeps its kind
+                Value::Map(properties) if properties.borrow().contains_key("message") && properties.borrow().contains_key("kind") => {
+                    let properties = properties.borrow();
+
+                    return Err(ModuError::from_kind(&properties["kind"].to_string(), properties["message"].to_string()));
                 }
 
-                _ => {
-                    return Err("If statement condition must return a boolean".to_string());
+                value => {
+                    return Err(ModuError::thrown(value.to_string()));
                 }
             }
         }
 
-        AST::Number(_) | AST::Boolean(_) | AST::Float(_) | AST::Object { .. } | AST::Null => {
-            return Ok(expr);
+        AST::Number(n, _) => {
+            return Ok(Value::Int(n));
         }
 
-        AST::String(value) => {
-            return Ok(AST::String(value.replace("\"", "").replace("\\n", "\n").replace("\\t", "\t")));
+        AST::Float(n, _) => {
+            return Ok(Value::Float(n));
         }
 
-        AST::Addition { left, right, line: _ } => {
-            match (eval(*left.clone(), context)?, eval(*right.clone(), context)?) {
-                (AST::Number(l), AST::Number(r)) => {
-                    return Ok(AST::Number(l + r));
-                }
-
-                (AST::Float(l), AST::Float(r)) => {
-                    return Ok(AST::Float(l + r));
-                }
+        AST::Boolean(b, _) => {
+            return Ok(Value::Bool(b));
+        }
 
-                (AST::Number(l), AST::Float(r)) => {
-                    return Ok(AST::Float(l as f64 + r));
-                }
+        AST::Null => {
+            return Ok(Value::Null);
+        }
 
-                (AST::Float(l), AST::Number(r)) => {
-                    return Ok(AST::Float(l + r as f64));
-                }
+        AST::String(value, _) => {
+            return Ok(Value::Str(value));
+        }
 
-                (AST::String(l), AST::String(r)) => {
-                    return Ok(AST::String(format!("{}{}", l, r)));
-                }
+        AST::InterpolatedString { parts, span: _ } => {
+            let mut string = String::new();
 
-                _ => {
-                    return Err(format!("Cannot add {:?} and {:?}", eval(*left, context)?, eval(*right, context)?));
-                }
+            for part in parts {
+                string.push_str(&eval(part, context)?.to_string());
             }
+
+            return Ok(Value::Str(string));
         }
 
-        AST::Subtraction { left, right, line: _ } => {
-            match (eval(*left.clone(), context)?, eval(*right.clone(), context)?) {
-                (AST::Number(l), AST::Number(r)) => {
-                    return Ok(AST::Number(l - r));
-                }
+        AST::Addition { left, right, span: _ } => {
+            let (l, r) = (eval(*left, context)?, eval(*right, context)?);
 
-                (AST::Float(l), AST::Float(r)) => {
-                    return Ok(AST::Float(l - r));
-                }
+            return add(l, r);
+        }
 
-                (AST::Number(l), AST::Float(r)) => {
-                    return Ok(AST::Float(l as f64 - r));
-                }
+        AST::Subtraction { left, right, span: _ } => {
+            let (l, r) = (eval(*left, context)?, eval(*right, context)?);
 
-                (AST::Float(l), AST::Number(r)) => {
-                    return Ok(AST::Float(l - r as f64));
-                }
+            return subtract(l, r);
+        }
 
-                (AST::Null, AST::Number(r)) => {
-                    return Ok(AST::Number(-r));
-                }
+        AST::Multiplication { left, right, span: _ } => {
+            let (l, r) = (eval(*left, context)?, eval(*right, context)?);
 
-                (AST::Null, AST::Float(r)) => {
-                    return Ok(AST::Float(-r));
-                }
+            return arithmetic(l, r, "multiply", |l, r| l.checked_mul(r), |l, r| l * r);
+        }
 
-                (AST::Number(l), AST::Null) => {
-                    return Ok(AST::Number(l));
-                }
+        AST::Division { left, right, span: _ } => {
+            let (l, r) = (eval(*left, context)?, eval(*right, context)?);
 
-                (AST::Float(l), AST::Null) => {
-                    return Ok(AST::Float(l));
+            if let (Value::Int(_), Value::Int(0)) = (&l, &r) {
+                return Err(ModuError::runtime("Division by zero"));
+            }
+
+            // integer division only stays an integer when it divides evenly
+            // checked_rem is None for i64::MIN / -1, which checked_div then reports as an overflow
+            if let (Value::Int(a), Value::Int(b)) = (&l, &r) {
+                if a.checked_rem(*b).is_some_and(|rem| rem != 0) {
+                    return Ok(Value::Float(*a as f64 / *b as f64));
                 }
+            }
 
-                _ => {
-                    return Err(format!("Cannot subtract {:?} and {:?}", eval(*left, context)?, eval(*right, context)?));
+            return arithmetic(l, r, "divide", |l, r| l.checked_div(r), |l, r| l / r);
+        }
+
+        AST::Modulo { left, right, span: _ } => {
+            let (l, r) = (eval(*left, context)?, eval(*right, context)?);
+
+            if let (Value::Int(_), Value::Int(0)) = (&l, &r) {
+                return Err(ModuError::runtime("Modulo by zero"));
+            }
+
+            return arithmetic(l, r, "take the modulo of", |l, r| l.checked_rem(r), |l, r| l % r);
+        }
+
+        AST::Exponent { left, right, span: _ } => {
+            let (l, r) = (eval(*left, context)?, eval(*right, context)?);
+
+            // a negative integer exponent gives a fraction
+            if let (Value::Int(a), Value::Int(b)) = (&l, &r) {
+                if *b < 0 {
+                    return Ok(Value::Float((*a as f64).powf(*b as f64)));
                 }
             }
+
+            return arithmetic(l, r, "raise", |l, r| u32::try_from(r).ok().and_then(|r| l.checked_pow(r)), |l, r| l.powf(r));
         }
 
-        AST::Identifer(name) => {
+        AST::Identifer(name, _) => {
             match context.get(&name) {
                 Some(value) => {
-                    return Ok(value.clone());
+                    return Ok(value);
+                }
+
+                None if context.is_strict() => {
+                    let message = match utils::did_you_mean(&name, &context.names()) {
+                        Some(suggestion) => format!("Variable {} not found, did you mean {}?", name, suggestion),
+                        None => format!("Variable {} not found", name),
+                    };
+
+                    return Err(ModuError::name(message));
                 }
 
                 None => {
-                    return Ok(AST::Null);
+                    return Ok(Value::Null);
                 }
             }
         }
 
-        AST::PropertyAccess { object, property, line: _ } => {
-            match object {
-                Some(name) => {
-                    match context.get(&name) {
-                        Some(value) => {
-                            match value {
-                                AST::Object { properties, line: _ } => {
-                                    match properties.get(property.as_ref().unwrap()) {
-                                        Some(value) => {
-                                            return Ok(value.clone());
-                                        }
+        AST::PropertyAccess { object, property, span: _ } => {
+            let name = object_name(&object);
+            let object_span = object.span();
 
-                                        None => {
-                                            return Err(format!("Property {:?} not found", property));
-                                        }
-                                    }
-                                }
+            let value = match *object {
+                AST::Identifer(name, span) => match context.get(&name) {
+                    Some(value) => value,
+                    None => return Err(ModuError::name(format!("Variable {} not found", name)).with_span(Some(span))),
+                },
 
-                                _ => {
-                                    return Err(format!("{} is not an object", name));
-                                }
-                            }
-                        }
+                object => eval(object, context)?,
+            };
 
-                        None => {
-                            return Err(format!("Variable {} not found", name));
-                        }
-                    }
-                }
+            return get_property(value, &name, &property).map_err(|e| type_error_at(e, object_span));
+        }
 
-                None => {
-                    return Err("Object not found".to_string());
-                }
+        AST::ArrayLiteral { items, span: _ } => {
+            return Ok(Value::list(eval_args(items, context)?));
+        }
+
+        AST::ObjectLiteral { properties, span: _ } => {
+            let mut values = HashMap::new();
+
+            for (key, value) in properties {
+                values.insert(key, eval(value, context)?);
             }
+
+            return Ok(Value::map(values));
         }
 
-        AST::Return { value, line: _ } => {
-            return Ok(*value);
+        AST::Index { object, index, span: _ } => {
+            let (value, index) = (eval(*object, context)?, eval(*index, context)?);
+
+            return get_index(value, index);
+        }
+
+        AST::Assign { target, value, span: _ } => {
+            let value = eval(*value, context)?;
+            let place = place_of(*target, context)?;
+
+            write_place(place, value, context)?;
+        }
+
+        AST::AddAssign { target, value, span: _ } => {
+            let place = place_of(*target, context)?;
+            let current = read_place(&place, context)?;
+            let value = eval(*value, context)?;
+
+            write_place(place, add(current, value)?, context)?;
+        }
+
+        AST::SubtractAssign { target, value, span: _ } => {
+            let place = place_of(*target, context)?;
+            let current = read_place(&place, context)?;
+            let value = eval(*value, context)?;
+
+            write_place(place, subtract(current, value)?, context)?;
         }
 
         _ => {
-            return Err(format!("Unknown expression, got {:?}", expr));
+            return Err(ModuError::runtime(format!("Unknown expression, got {:?}", expr)));
         }
     }
 
-    Ok(AST::Null)
+    Ok(Value::Null)
 }
 
 #[cfg(test)]
@@ -593,16 +1148,32 @@ mod tests {
     fn unknown_variable() {
         let mut context = crate::utils::create_context();
 
-        let expr = AST::Identifer("unknown".to_string());
+        let expr = AST::Identifer("unknown".to_string(), Span::default());
+
+        assert_eq!(eval(expr, &mut context).unwrap(), Value::Null);
+    }
+
+    #[test]
+    fn strict_unknown_variable() {
+        let mut context = crate::utils::create_context();
+        context.set_strict(true);
+
+        let result = crate::parser::parse("let print_count = 1\nfn show() {\n    print(pritn_count)\n}\nshow()", &mut context);
+        assert_eq!(result.map_err(|e| (e.to_string(), e.line())), Err(("NameError: Variable pritn_count not found, did you mean print_count?".to_string(), 3)));
+
+        let result = crate::parser::parse("let x = potato + 1", &mut context);
+        assert_eq!(result.map_err(|e| e.to_string()), Err("NameError: Variable potato not found".to_string()));
 
-        assert_eq!(eval(expr, &mut context).unwrap(), AST::Null);
+        let result = crate::parser::parse("fn nothing() {\n    return null\n}\nlet items = [null, nothing()]\nlet empty = items[1] == null", &mut context);
+        assert_eq!(result, Ok(()));
+        assert_eq!(context.get("empty"), Some(Value::Bool(true)));
     }
 
     #[test]
     fn unknown_function() {
         let mut context = crate::utils::create_context();
 
-        let expr = AST::Call { name: "cookie".to_string(), args: vec![], line: 0 };
+        let expr = AST::Call { name: "cookie".to_string(), args: vec![], span: Span::default() };
 
         match eval(expr, &mut context) {
             Ok(_) => {
@@ -610,7 +1181,7 @@ mod tests {
             }
 
             Err(e) => {
-                assert_eq!(e, "Function cookie not found");
+                assert_eq!(e.to_string(), "NameError: Function cookie not found");
             }
         }
     }
@@ -619,61 +1190,61 @@ mod tests {
     fn addition() {
         let mut context = crate::utils::create_context();
 
-        let expr = AST::Addition { left: Box::new(AST::Number(1)), right: Box::new(AST::Number(2)), line: 0 };
+        let expr = AST::Addition { left: Box::new(AST::Number(1, Span::default())), right: Box::new(AST::Number(2, Span::default())), span: Span::default() };
 
-        assert_eq!(eval(expr, &mut context).unwrap(), AST::Number(3));
+        assert_eq!(eval(expr, &mut context).unwrap(), Value::Int(3));
     }
 
     #[test]
     fn subtraction() {
         let mut context = crate::utils::create_context();
 
-        let expr = AST::Subtraction { left: Box::new(AST::Number(1)), right: Box::new(AST::Number(2)), line: 0 };
+        let expr = AST::Subtraction { left: Box::new(AST::Number(1, Span::default())), right: Box::new(AST::Number(2, Span::default())), span: Span::default() };
 
-        assert_eq!(eval(expr, &mut context).unwrap(), AST::Number(-1));
+        assert_eq!(eval(expr, &mut context).unwrap(), Value::Int(-1));
     }
 
     #[test]
     fn negative_num() {
         let mut context = crate::utils::create_context();
 
-        let expr = AST::Subtraction { left: Box::new(AST::Null), right: Box::new(AST::Number(2)), line: 0 };
+        let expr = AST::Subtraction { left: Box::new(AST::Null), right: Box::new(AST::Number(2, Span::default())), span: Span::default() };
 
-        assert_eq!(eval(expr, &mut context).unwrap(), AST::Number(-2));
+        assert_eq!(eval(expr, &mut context).unwrap(), Value::Int(-2));
     }
 
     #[test]
     fn join_strings() {
         let mut context = crate::utils::create_context();
 
-        let expr = AST::Addition { left: Box::new(AST::String("Hello,".to_string())), right: Box::new(AST::String(" World!".to_string())), line: 0 };
+        let expr = AST::Addition { left: Box::new(AST::String("Hello,".to_string(), Span::default())), right: Box::new(AST::String(" World!".to_string(), Span::default())), span: Span::default() };
 
-        assert_eq!(eval(expr, &mut context).unwrap(), AST::String("Hello, World!".to_string()));
+        assert_eq!(eval(expr, &mut context).unwrap(), Value::Str("Hello, World!".to_string()));
     }
 
     #[test]
     fn add_floats() {
         let mut context = crate::utils::create_context();
 
-        let expr = AST::Addition { left: Box::new(AST::Float(1.0)), right: Box::new(AST::Float(2.0)), line: 0 };
+        let expr = AST::Addition { left: Box::new(AST::Float(1.0, Span::default())), right: Box::new(AST::Float(2.0, Span::default())), span: Span::default() };
 
-        assert_eq!(eval(expr, &mut context).unwrap(), AST::Float(3.0));
+        assert_eq!(eval(expr, &mut context).unwrap(), Value::Float(3.0));
     }
 
     #[test]
     fn add_float_and_int() {
         let mut context = crate::utils::create_context();
 
-        let expr = AST::Addition { left: Box::new(AST::Float(1.0)), right: Box::new(AST::Number(2)), line: 0 };
+        let expr = AST::Addition { left: Box::new(AST::Float(1.0, Span::default())), right: Box::new(AST::Number(2, Span::default())), span: Span::default() };
 
-        assert_eq!(eval(expr, &mut context).unwrap(), AST::Float(3.0));
+        assert_eq!(eval(expr, &mut context).unwrap(), Value::Float(3.0));
     }
 
     #[test]
     fn add_int_and_string() {
         let mut context = crate::utils::create_context();
 
-        let expr = AST::Addition { left: Box::new(AST::Number(1)), right: Box::new(AST::String(" cookie".to_string())), line: 0 };
+        let expr = AST::Addition { left: Box::new(AST::Number(1, Span::default())), right: Box::new(AST::String(" cookie".to_string(), Span::default())), span: Span::default() };
 
         match eval(expr, &mut context) {
             Ok(_) => {
@@ -681,8 +1252,162 @@ mod tests {
             }
 
             Err(e) => {
-                assert_eq!(e, "Cannot add Number(1) and String(\" cookie\")");
+                assert_eq!(e.to_string(), "TypeError: Cannot add Int(1) and Str(\" cookie\")");
             }
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn multiply_int_and_float() {
+        let mut context = crate::utils::create_context();
+
+        let expr = AST::Multiplication { left: Box::new(AST::Number(2, Span::default())), right: Box::new(AST::Float(1.5, Span::default())), span: Span::default() };
+
+        assert_eq!(eval(expr, &mut context).unwrap(), Value::Float(3.0));
+    }
+
+    #[test]
+    fn modulo_ints() {
+        let mut context = crate::utils::create_context();
+
+        let expr = AST::Modulo { left: Box::new(AST::Number(10, Span::default())), right: Box::new(AST::Number(4, Span::default())), span: Span::default() };
+
+        assert_eq!(eval(expr, &mut context).unwrap(), Value::Int(2));
+    }
+
+    #[test]
+    fn integer_overflow() {
+        let mut context = crate::utils::create_context();
+
+        for source in ["(-9223372036854775807 - 1) / -1", "(-9223372036854775807 - 1) % -1", "9223372036854775807 * 2"] {
+            let result = crate::parser::parse(&format!("let x = {}", source), &mut context);
+            assert_eq!(result.map_err(|e| e.to_string()), Err("RuntimeError: Integer overflow".to_string()));
+        }
+    }
+
+    #[test]
+    fn recursion_limit() {
+        with_big_stack(|| {
+            let mut context = crate::utils::create_context();
+
+            let source = "fn count(n) {\n    if n == 0 {\n        return 0\n    }\n\n    return 1 + count(n - 1)\n}\n";
+
+            crate::parser::parse(&format!("{}let x = count(900)", source), &mut context).unwrap();
+            assert_eq!(context.get("x"), Some(Value::Int(900)));
+
+            let result = crate::parser::parse(&format!("{}let y = count(5000)", source), &mut context);
+            assert_eq!(result.map_err(|e| e.to_string()), Err("RuntimeError: Maximum recursion depth exceeded".to_string()));
+        });
+    }
+
+    #[test]
+    fn recursion_limit_before_stack_overflow() {
+        with_big_stack(|| {
+            let mut context = crate::utils::create_context();
+
+            // every level goes through map and a callback, so it takes more of the stack than a plain call
+            let source = "fn count(n) {\n    let results = [1].map(fn (x) {\n        return count(n - 1)\n    })\n\n    return results[0] + 1\n}\n";
+
+            let result = crate::parser::parse(&format!("{}let x = count(5000)", source), &mut context);
+            assert_eq!(result.map_err(|e| e.to_string()), Err("RuntimeError: Maximum recursion depth exceeded".to_string()));
+        });
+    }
+
+    #[test]
+    fn truthy_conditions() {
+        let mut context = crate::utils::create_context();
+
+        let code = "let hits = []\nif 5 {\n    hits.push(\"int\")\n}\nif \"\" {\n    hits.push(\"string\")\n}\nif null {\n    hits.push(\"null\")\n}\nlet next = 0\nwhile next {\n    hits.push(\"loop\")\n    next = null\n}\nlet both = 5 && \"x\"\nlet negated = !\"s\"";
+        crate::parser::parse(code, &mut context).unwrap();
+
+        assert_eq!(context.get("hits").map(|hits| hits.to_string()), Some("[int, string, loop]".to_string()));
+        assert_eq!(context.get("both"), Some(Value::Bool(true)));
+        assert_eq!(context.get("negated"), Some(Value::Bool(false)));
+    }
+
+    #[test]
+    fn time_limit() {
+        let mut context = crate::utils::create_context();
+
+        set_time_limit(Some(Duration::from_millis(50)));
+        let result = crate::parser::parse("let i = 0\nwhile true {\n    try {\n        i += 1\n    } catch e {}\n}", &mut context);
+        set_time_limit(None);
+
+        assert_eq!(result.map_err(|e| e.to_string()), Err("RuntimeError: Time limit exceeded".to_string()));
+    }
+
+    #[test]
+    fn long_function_bodies() {
+        let mut context = crate::utils::create_context();
+
+        let body = "    total += 1\n".repeat(150);
+        crate::parser::parse(&format!("let total = 0\nfn add() {{\n{}}}\nadd()", body), &mut context).unwrap();
+
+        assert_eq!(context.get("total"), Some(Value::Int(150)));
+    }
+
+    #[test]
+    fn call_stack() {
+        let mut context = crate::utils::create_context();
+
+        let err = crate::parser::parse("fn inner() {\n    print(missing())\n}\n\nfn outer() {\n    inner()\n}\n\nouter()", &mut context).unwrap_err();
+
+        let frames = err.info().stack.iter().map(|frame| (frame.name.as_str(), frame.span.line)).collect::<Vec<_>>();
+
+        assert_eq!(frames, vec![("outer", 9), ("inner", 6)]);
+        assert_eq!(err.line(), 2);
+    }
+
+    #[test]
+    fn returned_inner_functions() {
+        let mut context = crate::utils::create_context();
+
+        // `next` is kept in the scope of the call, which must outlive the call because it was returned
+        let code = "fn counter() {\n    let count = 0\n    fn next() {\n        count += 1\n        return count\n    }\n    return next\n}\nlet next = counter()\nnext()\nlet last = next()";
+        crate::parser::parse(code, &mut context).unwrap();
+
+        assert_eq!(context.get("last"), Some(Value::Int(2)));
+    }
+
+    #[test]
+    fn call_function_value() {
+        let mut context = crate::utils::create_context();
+
+        // what an internal function gets when it is passed `fn (x) { return x * 2 }`
+        let function = eval(AST::AnonymousFunction {
+            args: vec!["x".to_string()],
+            body: vec![AST::Return {
+                value: Box::new(AST::Multiplication {
+                    left: Box::new(AST::Identifer("x".to_string(), Span::default())),
+                    right: Box::new(AST::Number(2, Span::default())),
+                    span: Span::default(),
+                }),
+                span: Span::default(),
+            }],
+            span: Span::default(),
+        }, &mut context).unwrap();
+
+        assert_eq!(call_function(&function, vec![Value::Int(21)], Span::default(), &mut context), Ok(Value::Int(42)));
+
+        match call_function(&function, vec![], Span::default(), &mut context) {
+            Ok(_) => {
+                assert!(false);
+            }
+
+            Err(e) => {
+                assert_eq!(e.to_string(), "TypeError: <anonymous> takes 1 argument(s)");
+            }
+        }
+    }
+
+    #[test]
+    fn shared_references() {
+        let mut context = crate::utils::create_context();
+
+        let result = crate::parser::parse("fn add(list) {\n    list.push(2)\n}\n\nlet a = [1]\nadd(a)\nlet obj = { child: a }\nlet child = obj.child\nchild.push(3)\nobj.child[0] = 10\nlet copy = obj\ncopy.set(\"name\", \"x\")", &mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(context.get("a").map(|a| a.to_string()), Some("[10, 2, 3]".to_string()));
+        assert_eq!(context.get("obj").map(|o| o.to_string().contains("\"name\": x")), Some(true));
+    }
+}
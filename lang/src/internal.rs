@@ -1,42 +1,25 @@
 // internal modu functions
 
-use std::collections::HashMap;
+use crate::value::Value;
+use crate::environment::Environment;
+use crate::error::ModuError;
 
-use crate::ast::AST;
-use crate::eval::eval;
-
-pub fn print(args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
+pub fn print(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
     for arg in args {
-        match eval(arg, context) {
-            Ok(value) => {
-                print!("{}", value);
-            }
-
-            Err(e) => {
-                return Err(e);
-            }
-        }
+        print!("{}", arg);
     }
 
     println!();
 
-    Ok((AST::Null, AST::Null))
+    Ok(Value::Null)
 }
 
-pub fn input(args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
+pub fn input(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
     if args.len() > 0 {
         use std::io::Write;
 
         for arg in args {
-            match eval(arg, context) {
-                Ok(value) => {
-                    print!("{}", value);
-                }
-    
-                Err(e) => {
-                    return Err(e);
-                }
-            }
+            print!("{}", arg);
         }
 
         std::io::stdout().flush().unwrap();
@@ -46,82 +29,64 @@ pub fn input(args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(AST,
 
     std::io::stdin().read_line(&mut input).unwrap();
 
-    Ok((AST::String(input.trim().to_string()), AST::Null))
+    Ok(Value::Str(input.trim().to_string()))
 }
 
-pub fn int(args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), context) {
-        Ok(v) => {
-            match v {
-                AST::String(value) => {
-                    match value.parse::<i64>() {
-                        Ok(value) => { return Ok((AST::Number(value), AST::Null)); },
-                        Err(_) => (),
-                    }
-
-                    match value.parse::<f64>() {
-                        Ok(value) => { return Ok((AST::Number(value as i64), AST::Null)); },
-                        Err(_) => (),
-                    }
-
-                    return Err("int() requires a string or boolean".to_string());
-                }
-
-                AST::Boolean(value) => Ok((AST::Number(if value {1} else {0}), AST::Null)),
-        
-                AST::Number(value) => Ok((AST::Number(value), AST::Null)),
-        
-                _ => Err("int() requires a string or boolean".to_string())
+pub fn int(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Str(value) => {
+            match value.parse::<i64>() {
+                Ok(value) => { return Ok(Value::Int(value)); },
+                Err(_) => (),
+            }
+
+            match value.parse::<f64>() {
+                Ok(value) => { return Ok(Value::Int(value as i64)); },
+                Err(_) => (),
             }
+
+            return Err(ModuError::type_error("int() requires a string or boolean"));
         }
 
-        Err(e) => Err(e)
+        Value::Bool(value) => Ok(Value::Int(if value {1} else {0})),
+
+        Value::Int(value) => Ok(Value::Int(value)),
+
+        _ => Err(ModuError::type_error("int() requires a string or boolean"))
     }
 }
 
-pub fn float(args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), context) {
-        Ok(v) => {
-            match v {
-                AST::String(value) => {
-                    match value.parse::<f64>() {
-                        Ok(value) => Ok((AST::Float(value), AST::Null)),
-                        Err(_) => Err("float() requires a string or boolean".to_string())
-                    }
-                }
-
-                AST::Boolean(value) => Ok((AST::Float(if value {1.0} else {0.0}), AST::Null)),
-        
-                AST::Number(value) => Ok((AST::Float(value as f64), AST::Null)),
-                AST::Float(value) => Ok((AST::Float(value), AST::Null)),
-        
-                _ => Err("float() requires a string or boolean".to_string())
+pub fn float(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Str(value) => {
+            match value.parse::<f64>() {
+                Ok(value) => Ok(Value::Float(value)),
+                Err(_) => Err(ModuError::type_error("float() requires a string or boolean"))
             }
         }
 
-        Err(e) => Err(e)
+        Value::Bool(value) => Ok(Value::Float(if value {1.0} else {0.0})),
+
+        Value::Int(value) => Ok(Value::Float(value as f64)),
+        Value::Float(value) => Ok(Value::Float(value)),
+
+        _ => Err(ModuError::type_error("float() requires a string or boolean"))
     }
 }
 
-pub fn str(args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), context) {
-        Ok(v) => {
-            match v {
-                AST::String(value) => Ok((AST::String(value), AST::Null)),
-        
-                AST::Number(value) => Ok((AST::String(value.to_string()), AST::Null)),
-                AST::Float(value) => Ok((AST::String(value.to_string()), AST::Null)),
-                AST::Boolean(value) => Ok((AST::String(value.to_string()), AST::Null)),
-                AST::Null => Ok((AST::String("null".to_string()), AST::Null)),
-        
-                _ => Err("str() requires a string, number or boolean".to_string())
-            }
-        }
+pub fn str(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Str(value) => Ok(Value::Str(value)),
+
+        Value::Int(value) => Ok(Value::Str(value.to_string())),
+        Value::Float(value) => Ok(Value::Str(value.to_string())),
+        Value::Bool(value) => Ok(Value::Str(value.to_string())),
+        Value::Null => Ok(Value::Str("null".to_string())),
 
-        Err(e) => Err(e)
+        _ => Err(ModuError::type_error("str() requires a string, number or boolean"))
     }
 }
 
-pub fn exit(_: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
+pub fn exit(_: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
     std::process::exit(0);
 }
\ No newline at end of file
@@ -0,0 +1,116 @@
+use crate::ast::{AST, Program};
+use crate::environment::Environment;
+use crate::error::ModuError;
+use crate::eval::{eval, exec};
+use crate::value::Value;
+use crate::utils;
+
+pub struct Interpreter {
+    pub context: Environment,
+}
+
+impl Interpreter {
+    pub fn new() -> Self {
+        Interpreter {
+            context: utils::create_context(),
+        }
+    }
+
+    // runs a program, variables and functions it defines stay around for the next run
+    pub fn run(&mut self, program: &Program) -> Result<(), ModuError> {
+        execute(program, &mut self.context)
+    }
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn execute(program: &Program, context: &mut Environment) -> Result<(), ModuError> {
+    let verbose = std::env::args().collect::<Vec<String>>()
+                            .iter().any(|arg| arg == "--verbose");
+
+    if verbose {
+        dbg!(&program);
+    }
+
+    for item in program.body.iter().cloned() {
+        match item {
+            // bare values are printed, like in the repl
+            AST::Identifer(..) | AST::PropertyAccess { .. } | AST::Index { .. } => {
+                print_res(eval(item, context)?);
+            }
+
+            _ => {
+                exec(item, context)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn print_res(res: Value) {
+    match res {
+        Value::Str(v) => {
+            println!("{}", v);
+        }
+
+        Value::Int(v) => {
+            println!("{}", v);
+        }
+
+        Value::Float(v) => {
+            println!("{}", v);
+        }
+
+        Value::Bool(v) => {
+            println!("{}", v);
+        }
+
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::Span;
+    use crate::parser::parse_program;
+
+    #[test]
+    fn run_keeps_context() {
+        let mut interpreter = Interpreter::new();
+
+        interpreter.run(&parse_program("let a = 1").unwrap()).unwrap();
+        interpreter.run(&parse_program("let b = a + 1").unwrap()).unwrap();
+
+        assert_eq!(interpreter.context.get("b"), Some(Value::Int(2)));
+    }
+
+    #[test]
+    fn parse_without_running() {
+        let program = parse_program("print(unknown_function())").unwrap();
+
+        assert_eq!(program.body.len(), 1);
+        let err = Interpreter::new().run(&program).unwrap_err();
+
+        assert_eq!(err.to_string(), "NameError: Function unknown_function not found");
+        assert_eq!(err.info().span, Some(Span { line: 1, column: 7, end_line: 1, end_column: 25 }));
+    }
+
+    #[test]
+    fn error_points_at_name() {
+        let mut interpreter = Interpreter::new();
+        interpreter.context.set_strict(true);
+
+        let err = interpreter.run(&parse_program("let count = 1\nlet total = count + cuont * 2").unwrap()).unwrap_err();
+        assert_eq!(err.info().span, Some(Span { line: 2, column: 21, end_line: 2, end_column: 26 }));
+
+        let err = interpreter.run(&parse_program("let n = 5\nprint(n.size)").unwrap()).unwrap_err();
+        assert_eq!(err.to_string(), "TypeError: n is not an object");
+        assert_eq!(err.info().span, Some(Span { line: 2, column: 7, end_line: 2, end_column: 8 }));
+    }
+}
@@ -4,7 +4,8 @@ use logos::Logos;
 pub enum LexingError {
     #[default]
     UnexpectedToken,
-    InvalidInteger(String)
+    InvalidInteger(String),
+    InvalidEscape(String),
 }
 
 impl From<std::num::ParseIntError> for LexingError {
@@ -18,6 +19,138 @@ impl From<std::num::ParseIntError> for LexingError {
     }
 }
 
+// a string literal is text with `${code}` parts in between
+#[derive(Debug, PartialEq, Clone)]
+pub enum StringPart {
+    Text(String),
+    Code(String, usize), // the code inside `${}`, and its byte offset from the opening quote
+}
+
+// the character an escape sequence stands for, and how many bytes after the backslash it took up
+fn escape(rest: &str) -> Result<(char, usize), LexingError> {
+    let c = rest.chars().next().ok_or(LexingError::UnexpectedToken)?;
+
+    match c {
+        'n' => Ok(('\n', 1)),
+        't' => Ok(('\t', 1)),
+        'r' => Ok(('\r', 1)),
+        '0' => Ok(('\0', 1)),
+        '"' | '\'' | '\\' | '$' => Ok((c, 1)),
+
+        // `\u{1F600}`
+        'u' => {
+            let end = match (rest.starts_with("u{"), rest.find('}')) {
+                (true, Some(end)) => end,
+                _ => return Err(LexingError::InvalidEscape("\\u".to_string())),
+            };
+
+            let sequence = &rest[..end + 1];
+
+            let code = u32::from_str_radix(&rest[2..end], 16).ok()
+                .and_then(char::from_u32);
+
+            match code {
+                Some(c) => Ok((c, sequence.len())),
+                None => Err(LexingError::InvalidEscape(format!("\\{}", sequence))),
+            }
+        }
+
+        _ => Err(LexingError::InvalidEscape(format!("\\{}", c))),
+    }
+}
+
+// the index right after the `}` closing a `${`, strings inside can have braces of their own
+fn skip_code(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = start;
+
+    while let Some(c) = bytes.get(i) {
+        match c {
+            b'{' => depth += 1,
+            b'}' if depth == 0 => return Some(i + 1),
+            b'}' => depth -= 1,
+
+            b'"' | b'\'' => {
+                i = skip_string(bytes, i + 1, *c)?;
+                continue;
+            }
+
+            _ => {}
+        }
+
+        i += 1;
+    }
+
+    None
+}
+
+// the index right after the closing quote of a string inside `${}`
+fn skip_string(bytes: &[u8], start: usize, quote: u8) -> Option<usize> {
+    let mut i = start;
+
+    loop {
+        match bytes.get(i)? {
+            b'\\' => i += 2,
+            b'$' if bytes.get(i + 1) == Some(&b'{') => i = skip_code(bytes, i + 2)?,
+            c if *c == quote => return Some(i + 1),
+            _ => i += 1,
+        }
+    }
+}
+
+// reads a string literal after its opening quote, escapes are decoded here so nothing after this has to
+fn string(lex: &mut logos::Lexer<Token>) -> Result<Vec<StringPart>, LexingError> {
+    let quote = lex.slice().as_bytes()[0];
+    let rest = lex.remainder();
+    let bytes = rest.as_bytes();
+
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut i = 0;
+
+    loop {
+        match bytes.get(i) {
+            None => return Err(LexingError::UnexpectedToken),
+
+            Some(c) if *c == quote => break,
+
+            Some(b'\\') => {
+                let (c, length) = escape(&rest[i + 1..])?;
+
+                text.push(c);
+                i += 1 + length;
+            }
+
+            Some(b'$') if bytes.get(i + 1) == Some(&b'{') => {
+                let end = skip_code(bytes, i + 2).ok_or(LexingError::UnexpectedToken)?;
+
+                if !text.is_empty() {
+                    parts.push(StringPart::Text(std::mem::take(&mut text)));
+                }
+
+                // the offset counts the opening quote, which isn't part of `rest`
+                parts.push(StringPart::Code(rest[i + 2..end - 1].to_string(), i + 3));
+                i = end;
+            }
+
+            Some(_) => {
+                let c = rest[i..].chars().next().unwrap();
+
+                text.push(c);
+                i += c.len_utf8();
+            }
+        }
+    }
+
+    if !text.is_empty() || parts.is_empty() {
+        parts.push(StringPart::Text(text));
+    }
+
+    lex.bump(i + 1);
+
+    Ok(parts)
+}
+
 #[derive(Logos, Debug, PartialEq, Clone)]
 #[logos(error = LexingError)]
 #[logos(skip r"[ \t\n\f\r]+")]
@@ -55,6 +188,33 @@ pub enum Token {
     #[token("if")]
     If,
 
+    #[token("else")]
+    Else,
+
+    #[token("while")]
+    While,
+
+    #[token("for")]
+    For,
+
+    #[token("in")]
+    In,
+
+    #[token("break")]
+    Break,
+
+    #[token("continue")]
+    Continue,
+
+    #[token("try")]
+    Try,
+
+    #[token("catch")]
+    Catch,
+
+    #[token("throw")]
+    Throw,
+
     #[token(".")]
     Dot,
 
@@ -70,18 +230,27 @@ pub enum Token {
     #[token("=")]
     Assign,
 
+    #[token("+=")]
+    PlusAssign,
+
+    #[token("-=")]
+    MinusAssign,
+
     #[regex("[0-9]+", |lex| lex.slice().parse())]
     Number(i64),
 
     #[regex("[0-9]+\\.[0-9]+")]
     Float,
 
-    #[regex(r#""[^"]*"|'[^']*'"#)]
-    String,
+    #[regex(r#"["']"#, string)]
+    String(Vec<StringPart>),
 
     #[regex("true|false")]
     Boolean,
 
+    #[token("null")]
+    Null,
+
     #[token("(")]
     LParen,
 
@@ -94,6 +263,15 @@ pub enum Token {
     #[token("}")]
     RBracket,
 
+    #[token("[")]
+    LSquareBracket,
+
+    #[token("]")]
+    RSquareBracket,
+
+    #[token(":")]
+    Colon,
+
     #[token("==")]
     IsEqual,
 
@@ -114,6 +292,24 @@ pub enum Token {
 
     #[token("*")]
     Star,
+
+    #[token("/")]
+    Slash,
+
+    #[token("%")]
+    Percent,
+
+    #[token("**")]
+    Power,
+
+    #[token("&&")]
+    And,
+
+    #[token("||")]
+    Or,
+
+    #[token("!")]
+    Not,
 }
 
 #[cfg(test)]
@@ -123,13 +319,13 @@ mod tests {
     #[test]
     fn string_1() {
         let mut lexer = Token::lexer("\"Hello, world!\"");
-        assert_eq!(lexer.next(), Some(Ok(Token::String)));
+        assert_eq!(lexer.next(), Some(Ok(Token::String(vec![StringPart::Text("Hello, world!".to_string())]))));
     }
 
     #[test]
     fn string_2() {
         let mut lexer = Token::lexer("'Hello, world!'");
-        assert_eq!(lexer.next(), Some(Ok(Token::String)));
+        assert_eq!(lexer.next(), Some(Ok(Token::String(vec![StringPart::Text("Hello, world!".to_string())]))));
     }
 
     #[test]
@@ -138,13 +334,43 @@ mod tests {
         assert_eq!(lexer.next(), Some(Err(LexingError::UnexpectedToken)));
     }
 
+    #[test]
+    fn string_escapes() {
+        let mut lexer = Token::lexer(r#""a\n\t\"b\" \\ \u{1F600} \$" 'it\'s'"#);
+        assert_eq!(lexer.next(), Some(Ok(Token::String(vec![StringPart::Text("a\n\t\"b\" \\ 😀 $".to_string())]))));
+        assert_eq!(lexer.next(), Some(Ok(Token::String(vec![StringPart::Text("it's".to_string())]))));
+
+        let mut lexer = Token::lexer(r#""\d""#);
+        assert_eq!(lexer.next(), Some(Err(LexingError::InvalidEscape("\\d".to_string()))));
+
+        let mut lexer = Token::lexer(r#""\u{110000}""#);
+        assert_eq!(lexer.next(), Some(Err(LexingError::InvalidEscape("\\u{110000}".to_string()))));
+    }
+
+    #[test]
+    fn string_interpolation() {
+        let mut lexer = Token::lexer(r#""Hi ${name}, ${ {"a": "}"}["a"] }!" 1"#);
+
+        assert_eq!(lexer.next(), Some(Ok(Token::String(vec![
+            StringPart::Text("Hi ".to_string()),
+            StringPart::Code("name".to_string(), 6),
+            StringPart::Text(", ".to_string()),
+            StringPart::Code(" {\"a\": \"}\"}[\"a\"] ".to_string(), 15),
+            StringPart::Text("!".to_string()),
+        ]))));
+        assert_eq!(lexer.next(), Some(Ok(Token::Number(1))));
+
+        let mut lexer = Token::lexer(r#""${unclosed""#);
+        assert_eq!(lexer.next(), Some(Err(LexingError::UnexpectedToken)));
+    }
+
     #[test]
     fn asing_str() {
         let mut lexer = Token::lexer("let x = \"test\"");
         assert_eq!(lexer.next(), Some(Ok(Token::Let)));
         assert_eq!(lexer.next(), Some(Ok(Token::Identifer)));
         assert_eq!(lexer.next(), Some(Ok(Token::Assign)));
-        assert_eq!(lexer.next(), Some(Ok(Token::String)));
+        assert_eq!(lexer.next(), Some(Ok(Token::String(vec![StringPart::Text("test".to_string())]))));
     }
 
     #[test]
@@ -174,6 +400,67 @@ mod tests {
         }
     }
 
+    #[test]
+    fn literals() {
+        let mut lexer = Token::lexer("[1, {a: 2}]");
+        assert_eq!(lexer.next(), Some(Ok(Token::LSquareBracket)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Number(1))));
+        assert_eq!(lexer.next(), Some(Ok(Token::Comma)));
+        assert_eq!(lexer.next(), Some(Ok(Token::LBracket)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Identifer)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Colon)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Number(2))));
+        assert_eq!(lexer.next(), Some(Ok(Token::RBracket)));
+        assert_eq!(lexer.next(), Some(Ok(Token::RSquareBracket)));
+    }
+
+    #[test]
+    fn loop_keywords() {
+        let mut lexer = Token::lexer("while for in break continue input");
+        assert_eq!(lexer.next(), Some(Ok(Token::While)));
+        assert_eq!(lexer.next(), Some(Ok(Token::For)));
+        assert_eq!(lexer.next(), Some(Ok(Token::In)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Break)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Continue)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Identifer)));
+    }
+
+    #[test]
+    fn error_keywords() {
+        let mut lexer = Token::lexer("try catch throw trying");
+        assert_eq!(lexer.next(), Some(Ok(Token::Try)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Catch)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Throw)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Identifer)));
+    }
+
+    #[test]
+    fn logical_operators() {
+        let mut lexer = Token::lexer("!a && b || c != d");
+        assert_eq!(lexer.next(), Some(Ok(Token::Not)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Identifer)));
+        assert_eq!(lexer.next(), Some(Ok(Token::And)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Identifer)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Or)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Identifer)));
+        assert_eq!(lexer.next(), Some(Ok(Token::IsUnequal)));
+    }
+
+    #[test]
+    fn arithmetic_operators() {
+        let mut lexer = Token::lexer("a * b / c % d ** e // comment");
+        assert_eq!(lexer.next(), Some(Ok(Token::Identifer)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Star)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Identifer)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Slash)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Identifer)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Percent)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Identifer)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Power)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Identifer)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Comment)));
+    }
+
     #[test]
     fn int_overflow() {
         let mut lexer = Token::lexer("let x = 9223372036854775808");
@@ -183,4 +470,15 @@ mod tests {
         assert_eq!(lexer.next(), Some(Ok(Token::Assign)));
         assert_eq!(lexer.next(), Some(Err(LexingError::InvalidInteger("Integer overflow".to_string()))));
     }
+
+    #[test]
+    fn compound_assign() {
+        let mut lexer = Token::lexer("x += 1 -= =");
+
+        assert_eq!(lexer.next(), Some(Ok(Token::Identifer)));
+        assert_eq!(lexer.next(), Some(Ok(Token::PlusAssign)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Number(1))));
+        assert_eq!(lexer.next(), Some(Ok(Token::MinusAssign)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Assign)));
+    }
 }
@@ -0,0 +1,23 @@
+#![feature(internal_output_capture)]
+
+// the parser, the interpreter and their errors can be used without the cli, for tools like linters and formatters
+
+pub mod ast;
+pub mod parser;
+pub mod interpreter;
+pub mod error;
+pub mod environment;
+pub mod value;
+
+mod lexer;
+mod eval;
+mod utils;
+mod internal;
+mod packages;
+
+// only here for the modu binary
+#[doc(hidden)]
+pub mod cli;
+
+#[doc(hidden)]
+pub use eval::with_big_stack;
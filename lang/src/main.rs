@@ -1,21 +1,11 @@
-#![feature(internal_output_capture)]
-
-mod lexer;
-mod ast;
-mod parser;
-mod eval;
-mod utils;
-mod internal;
-mod cli;
-mod packages;
-
+use modu::cli;
 
 fn main() {
     let args = std::env::args().collect::<Vec<String>>();
 
     if args.len() < 2 {
         println!("Commands:
-    run     <file> - Run a Modu file
+    run     <file> [--strict] - Run a Modu file, --strict makes unknown variables an error
     repl           - Start the Modu REPL
     server  [port] - Start the Modu server, default port is 2424
     init           - Initialize a new Modu package
@@ -26,9 +16,12 @@ fn main() {
         return;
     }
 
-    let action = &args[1];
+    // deep recursion in modu code needs a bigger stack than the main thread has
+    modu::with_big_stack(|| run_action(&args[1]));
+}
 
-    match action.as_str() {
+fn run_action(action: &str) {
+    match action {
         "run" => cli::run::run(),
         "repl" => cli::repl::repl(),
         "server" => cli::server::server(),
@@ -1,351 +1,387 @@
-use std::collections::HashMap;
-use crate::ast::AST;
-use crate::eval::eval;
+use std::{cmp::Ordering, collections::HashMap};
+use crate::ast::Span;
+use crate::environment::Environment;
+use crate::error::ModuError;
+use crate::eval::call_function;
+use crate::value::{NativeFn, Value};
+
+pub fn new(_: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    Ok(Value::list(Vec::new()))
+}
 
-pub static IDENTITY: &str = "\x1b \x1b"; // name of the property used to indentify arrays
-pub static BUILTINS: [&str; 6] = ["length", "at", "push", "pop", "shift", "unshift"];
+pub fn isarray(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    Ok(Value::Bool(matches!(args[0], Value::List(_))))
+}
 
-pub fn new(_: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    let mut obj: HashMap<String, AST> = HashMap::new();
+// Self-functions
 
-    obj.insert(
-        IDENTITY.to_string(),
-        AST::String("array".to_string())
-    ); // this is how you should identify arrays
+pub fn at(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match (&args[0], &args[1]) {
+        (Value::List(items), Value::Int(i)) => {
+            match usize::try_from(*i).ok().and_then(|i| items.borrow().get(i).cloned()) {
+                Some(item) => Ok(item),
+                None => Err(ModuError::runtime("no such element at that index")),
+            }
+        },
 
-    obj.insert(
-        "length".to_string(),
-        AST::Number(0)
-    );
+        _ => Err(ModuError::type_error("at() expects an array and a number"))
+    }
+}
 
-    obj.insert(
-        "at".to_string(),
-        AST::InternalFunction {
-            name: "at".to_string(),
-            args: vec!["self".to_string(), "index".to_string()],
-            call_fn: at
-        }
-    );
-    obj.insert(
-        "push".to_string(),
-        AST::InternalFunction {
-            name: "push".to_string(),
-            args: vec!["self".to_string(), "item".to_string()],
-            call_fn: push
-        }
-    );
-    obj.insert(
-        "pop".to_string(),
-        AST::InternalFunction {
-            name: "pop".to_string(),
-            args: vec!["self".to_string()],
-            call_fn: pop
-        }
-    );
-    obj.insert(
-        "shift".to_string(),
-        AST::InternalFunction {
-            name: "shift".to_string(),
-            args: vec!["self".to_string()],
-            call_fn: shift
-        }
-    );
-    obj.insert(
-        "unshift".to_string(),
-        AST::InternalFunction {
-            name: "unshift".to_string(),
-            args: vec!["self".to_string(), "item".to_string()],
-            call_fn: unshift
+pub fn push(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match &args[0] {
+        Value::List(items) => {
+            items.borrow_mut().push(args[1].clone());
+
+            Ok(Value::Null)
         }
-    );
 
-    Ok((AST::Object { properties: obj, line: 0 }, AST::Null))
+        _ => Err(ModuError::type_error("push() expects an array and a value"))
+    }
 }
 
-// tests if an object is an array
-fn check_array(obj: HashMap<String, AST>) -> bool {
-    let prop = obj.get(&IDENTITY.to_string());
-    if let Some(val) = prop {
-        if let AST::String(valstr) = val {
-            return *valstr == "array".to_string();
+pub fn pop(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match &args[0] {
+        Value::List(items) => {
+            match items.borrow_mut().pop() {
+                Some(last) => Ok(last),
+                None => Err(ModuError::runtime("empty array")),
+            }
         }
-        return false;
+
+        _ => Err(ModuError::type_error("pop() expects an array"))
     }
-    false
 }
 
-pub fn isarray(args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    let arr = eval(args[0].clone(), context)?;
+pub fn shift(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match &args[0] {
+        Value::List(items) => {
+            let mut items = items.borrow_mut();
+
+            if items.is_empty() {
+                return Err(ModuError::runtime("empty array"));
+            }
 
-    if let AST::Object { properties: obj, line: _ } = arr {
-        return Ok((AST::Boolean(check_array(obj.clone())), AST::Null));
+            Ok(items.remove(0))
+        }
+
+        _ => Err(ModuError::type_error("shift() expects an array"))
     }
-    Ok((AST::Boolean(false), AST::Null))
 }
 
-// Self-functions
+pub fn unshift(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match &args[0] {
+        Value::List(items) => {
+            items.borrow_mut().insert(0, args[1].clone());
 
-pub fn at(args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    let arr = eval(args[0].clone(), context)?;
-    let index = eval(args[1].clone(), context)?;
-    
-    match (arr, index) {
-        (AST::Object { properties: obj, line: _ }, AST::Number(i)) => {
-            if !check_array(obj.clone()) {
-                return Err("first argument is not an array".to_string())
-            }
-            let itemornone = obj.get(&i.to_string());
-            if let None = itemornone {
-                return Err("no such element at that index".to_string());
-            }
-            let item = itemornone.unwrap();
-            Ok((item.clone(), AST::Null))
-        },
+            Ok(Value::Null)
+        }
 
-        _ => Err("at() expects an array and a number".to_string())
+        _ => Err(ModuError::type_error("unshift() expects an array"))
     }
 }
 
-pub fn push(args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    let arr = eval(args[0].clone(), context)?;
-    let item = eval(args[1].clone(), context)?;
-
-    match arr {
-        AST::Object { properties: mut obj, line: _ } => {
-            let objclone = obj.clone();
-            let lenornone = objclone.get(&"length".to_string());
+// a copy of the items of the array a self-function was called on, so functions it calls can change the array
+fn self_items(args: &[Value], name: &str) -> Result<Vec<Value>, ModuError> {
+    match &args[0] {
+        Value::List(items) => Ok(items.borrow().clone()),
+        _ => Err(ModuError::type_error(format!("{}() expects an array", name))),
+    }
+}
 
-            if let None = lenornone {
-                return Err("corrupted array".to_string());
-            }
+fn function_arg(args: &[Value], index: usize, name: &str) -> Result<Value, ModuError> {
+    match &args[index] {
+        function @ (Value::Function(_) | Value::NativeFunction { .. }) => Ok(function.clone()),
+        _ => Err(ModuError::type_error(format!("{}() expects a function", name))),
+    }
+}
 
-            let len = lenornone.unwrap();
+fn index_arg(args: &[Value], index: usize, name: &str) -> Result<i64, ModuError> {
+    match &args[index] {
+        Value::Int(n) => Ok(*n),
+        _ => Err(ModuError::type_error(format!("{}() expects numbers as indexes", name))),
+    }
+}
 
-            match len {
-                AST::Number(length) => {
-                    obj.insert(
-                        length.to_string(),
-                        item
-                    );
+fn replace_items(array: &Value, items: Vec<Value>) {
+    if let Value::List(array) = array {
+        *array.borrow_mut() = items;
+    }
+}
 
-                    obj.insert("length".to_string(), AST::Number(length+1));
+fn call(function: &Value, args: Vec<Value>, context: &mut Environment) -> Result<Value, ModuError> {
+    call_function(function, args, Span::default(), context)
+}
 
-                    Ok((AST::Null, AST::Object { properties: obj, line: 0 }))
-                }
+pub fn map(args: Vec<Value>, context: &mut Environment) -> Result<Value, ModuError> {
+    let items = self_items(&args, "map")?;
+    let function = function_arg(&args, 1, "map")?;
 
-                _ => Err("corrupted array".to_string())
-            }
-        }
+    let mut mapped = Vec::new();
 
-        _ => Err("push() expects an array and a value".to_string())
+    for item in items {
+        mapped.push(call(&function, vec![item], context)?);
     }
+
+    Ok(Value::list(mapped))
 }
 
-pub fn pop(args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    let arr = eval(args[0].clone(), context)?;
+pub fn filter(args: Vec<Value>, context: &mut Environment) -> Result<Value, ModuError> {
+    let items = self_items(&args, "filter")?;
+    let function = function_arg(&args, 1, "filter")?;
 
-    match arr {
-        AST::Object { properties: mut obj, line: _ } => {
-            let objclone = obj.clone();
-            if !check_array(obj.clone()) {
-                return Err("first argument is not an array".to_string())
-            }
-            let lenornone = objclone.get(&"length".to_string());
+    let mut kept = Vec::new();
 
-            if let None = lenornone {
-                return Err("corrupted array".to_string());
-            }
+    for item in items {
+        if crate::eval::is_truthy(&call(&function, vec![item.clone()], context)?) {
+            kept.push(item);
+        }
+    }
 
-            let len = lenornone.unwrap();
+    Ok(Value::list(kept))
+}
 
-            match len {
-                AST::Number(length) => {
-                    if *length < 1 {
-                        return Err("empty array".to_string());
-                    }
+// reduce(function) starts with the first item, reduce(function, initial) with `initial`
+pub fn reduce(args: Vec<Value>, context: &mut Environment) -> Result<Value, ModuError> {
+    if args.len() != 2 && args.len() != 3 {
+        return Err(ModuError::type_error("reduce() takes a function and an optional initial value"));
+    }
 
-                    let lastornone = objclone.get(&(length-1).to_string());
+    let mut items = self_items(&args, "reduce")?.into_iter();
+    let function = function_arg(&args, 1, "reduce")?;
 
-                    if let None = lastornone {
-                        return Err("corrupted array".to_string());
-                    }
+    let mut accumulator = if args.len() == 3 {
+        args[2].clone()
+    } else {
+        match items.next() {
+            Some(item) => item,
+            None => return Err(ModuError::runtime("reduce() of an empty array needs an initial value")),
+        }
+    };
 
-                    let last = lastornone.unwrap();
+    for item in items {
+        accumulator = call(&function, vec![accumulator, item], context)?;
+    }
 
-                    obj.remove(&(length-1).to_string());
-                    obj.insert("length".to_string(), AST::Number(length-1));
+    Ok(accumulator)
+}
 
-                    Ok((last.clone(), AST::Object { properties: obj, line: 0 }))
-                }
-                
-                _ => Err("corrupted array".to_string())
-            }
-        }
+pub fn for_each(args: Vec<Value>, context: &mut Environment) -> Result<Value, ModuError> {
+    let items = self_items(&args, "for_each")?;
+    let function = function_arg(&args, 1, "for_each")?;
 
-        _ => Err("pop() expects an array".to_string())
+    for item in items {
+        call(&function, vec![item], context)?;
     }
+
+    Ok(Value::Null)
 }
 
-pub fn shift(args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    let arr = eval(args[0].clone(), context)?;
+// the first item the function returns true for, or null
+pub fn find(args: Vec<Value>, context: &mut Environment) -> Result<Value, ModuError> {
+    let items = self_items(&args, "find")?;
+    let function = function_arg(&args, 1, "find")?;
 
-    match arr {
-        AST::Object { properties: mut obj, line: _ } => {
-            let objclone = obj.clone();
-            if !check_array(obj.clone()) {
-                return Err("first argument is not an array".to_string())
-            }
-            let lenornone = objclone.get(&"length".to_string());
+    for item in items {
+        if crate::eval::is_truthy(&call(&function, vec![item.clone()], context)?) {
+            return Ok(item);
+        }
+    }
 
-            if let None = lenornone {
-                return Err("corrupted array".to_string());
-            }
+    Ok(Value::Null)
+}
 
-            let len = lenornone.unwrap();
+// the index of the first item equal to the argument, or -1
+pub fn index_of(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let items = self_items(&args, "index_of")?;
 
-            match len {
-                AST::Number(length) => {
-                    if *length < 1 {
-                        return Err("empty array".to_string());
-                    }
+    match items.iter().position(|i| *i == args[1]) {
+        Some(index) => Ok(Value::Int(index as i64)),
+        None => Ok(Value::Int(-1)),
+    }
+}
 
-                    let firstornone = objclone.get(&0.to_string());
+pub fn includes(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let items = self_items(&args, "includes")?;
 
-                    if let None = firstornone {
-                        return Err("corrupted array".to_string());
-                    }
+    Ok(Value::Bool(items.contains(&args[1])))
+}
 
-                    let first = firstornone.unwrap();
+fn compare(a: &Value, b: &Value) -> Result<Ordering, ModuError> {
+    match (a, b) {
+        (Value::Int(a), Value::Int(b)) => Ok(a.cmp(b)),
+        (Value::Float(a), Value::Float(b)) => Ok(a.total_cmp(b)),
+        (Value::Int(a), Value::Float(b)) => Ok((*a as f64).total_cmp(b)),
+        (Value::Float(a), Value::Int(b)) => Ok(a.total_cmp(&(*b as f64))),
+        (Value::Str(a), Value::Str(b)) => Ok(a.cmp(b)),
+        (Value::Bool(a), Value::Bool(b)) => Ok(a.cmp(b)),
+        _ => Err(ModuError::type_error(format!("Cannot compare {:?} and {:?}", a, b))),
+    }
+}
 
-                    let mut elems: Vec<AST> = vec![];
+// the standard library's sort panics when the comparison isn't a consistent order, and a modu comparator
+// doesn't have to be one, so this sort takes whatever order it gets and stops at the first error
+fn merge_sort(mut items: Vec<Value>, compare: &mut dyn FnMut(&Value, &Value) -> Result<Ordering, ModuError>) -> Result<Vec<Value>, ModuError> {
+    if items.len() <= 1 {
+        return Ok(items);
+    }
 
-                    if *length > 1 {
-                        for i in 1..*length {
-                            let elemornone = objclone.get(&i.to_string());
+    let right = items.split_off(items.len() / 2);
+    let left = merge_sort(items, compare)?;
+    let right = merge_sort(right, compare)?;
 
-                            if let None = elemornone {
-                                return Err("corrupted array".to_string());
-                            }
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
 
-                            let elem = elemornone.unwrap();
+    while let (Some(l), Some(r)) = (left.peek(), right.peek()) {
+        // equal items are taken from the left first, so they keep their order
+        if compare(l, r)? == Ordering::Greater {
+            merged.extend(right.next());
+        } else {
+            merged.extend(left.next());
+        }
+    }
 
-                            elems.push(elem.clone());
-                        }
-                    }
+    merged.extend(left);
+    merged.extend(right);
 
-                    obj.clear();
+    Ok(merged)
+}
 
-                    if *length > 1 {
-                        let mut i: i64 = 0;
-                        for elem in elems {
-                            obj.insert(i.to_string(), elem);
-                            i += 1;
-                        }
-                    }
+// sorts the array in place and returns it, a comparator gets two items and returns a negative number,
+// zero or a positive number when the first goes before, with or after the second
+pub fn sort(args: Vec<Value>, context: &mut Environment) -> Result<Value, ModuError> {
+    if args.len() > 2 {
+        return Err(ModuError::type_error("sort() takes an optional comparator function"));
+    }
 
-                    let default_obj = new(vec![], context).unwrap().0;
+    let items = self_items(&args, "sort")?;
 
-                    match default_obj {
-                        AST::Object { properties: default_obj, line: _ } => {
-                            for (key, value) in default_obj {
-                                obj.insert(key, value);
-                            }
-                        }
-                        _ => {}
-                    }
+    let comparator = if args.len() == 2 {
+        Some(function_arg(&args, 1, "sort")?)
+    } else {
+        None
+    };
 
-                    obj.insert("length".to_string(), AST::Number(length-1));
+    let items = merge_sort(items, &mut |a, b| match &comparator {
+        Some(comparator) => match call(comparator, vec![a.clone(), b.clone()], context)? {
+            Value::Int(n) => Ok(n.cmp(&0)),
+            Value::Float(n) => Ok(n.total_cmp(&0.0)),
+            value => Err(ModuError::type_error(format!("sort() comparator must return a number, got {:?}", value))),
+        },
 
-                    Ok((first.clone(), AST::Object { properties: obj, line: 0 }))
-                }
+        None => compare(a, b),
+    })?;
 
-                _ => Err("corrupted array".to_string())
-            }
-        }
+    replace_items(&args[0], items);
 
-        _ => Err("shift() expects an array".to_string())
-    }
+    Ok(args[0].clone())
 }
 
-pub fn unshift(args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    let arr = eval(args[0].clone(), context)?;
-    let item = eval(args[1].clone(), context)?;
-
-    match arr {
-        AST::Object { properties: mut obj, line: _ } => {
-            let objclone = obj.clone();
-            if !check_array(obj.clone()) {
-                return Err("first argument is not an array".to_string())
-            }
-
-            let lenornone = objclone.get(&"length".to_string());
+// reverses the array in place and returns it
+pub fn reverse(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let mut items = self_items(&args, "reverse")?;
+    items.reverse();
 
-            if let None = lenornone {
-                return Err("corrupted array".to_string());
-            }
+    replace_items(&args[0], items);
 
-            let len = lenornone.unwrap();
+    Ok(args[0].clone())
+}
 
-            match len {
-                AST::Number(length) => {
-                    let mut elems: Vec<AST> = vec![];
+// slice(start) or slice(start, end), negative indexes count from the end
+pub fn slice(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    if args.len() != 2 && args.len() != 3 {
+        return Err(ModuError::type_error("slice() takes a start and an optional end index"));
+    }
 
-                    if *length > 0 {
-                        for i in 0..*length {
-                            let elemornone = objclone.get(&i.to_string());
+    let items = self_items(&args, "slice")?;
+    let length = items.len() as i64;
 
-                            if let None = elemornone {
-                                return Err("corrupted array".to_string());
-                            }
+    let resolve = |index: i64| -> usize {
+        if index < 0 {
+            (length + index).max(0) as usize
+        } else {
+            index.min(length) as usize
+        }
+    };
 
-                            let elem = elemornone.unwrap();
+    let start = resolve(index_arg(&args, 1, "slice")?);
 
-                            elems.push(elem.clone());
-                        }
-                    }
+    let end = if args.len() == 3 {
+        resolve(index_arg(&args, 2, "slice")?)
+    } else {
+        length as usize
+    };
 
-                    obj.clear();
+    Ok(Value::list(items[start..end.max(start)].to_vec()))
+}
 
-                    obj.insert(0.to_string(), item);
+pub fn concat(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let mut items = self_items(&args, "concat")?;
+    items.extend(self_items(&args[1..], "concat")?);
 
-                    if *length > 0 {
-                        let mut i: i64 = 1;
-                        for elem in elems {
-                            obj.insert(i.to_string(), elem);
-                            i += 1;
-                        }
-                    }
+    Ok(Value::list(items))
+}
 
-                    let default_obj = new(vec![], context).unwrap().0;
+// join() or join(separator), the separator defaults to ","
+pub fn join(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    if args.len() > 2 {
+        return Err(ModuError::type_error("join() takes an optional separator"));
+    }
 
-                    match default_obj {
-                        AST::Object { properties: default_obj, line: _ } => {
-                            for (key, value) in default_obj {
-                                obj.insert(key, value);
-                            }
-                        }
-                        _ => {}
-                    }
+    let items = self_items(&args, "join")?;
 
-                    obj.insert("length".to_string(), AST::Number(length+1));
+    let separator = if args.len() == 2 {
+        match &args[1] {
+            Value::Str(separator) => separator.clone(),
+            _ => return Err(ModuError::type_error("join() expects a string as the separator")),
+        }
+    } else {
+        ",".to_string()
+    };
 
-                    Ok((AST::Null, AST::Object { properties: obj, line: 0 }))
-                }
+    let joined = items.iter().map(|item| item.to_string()).collect::<Vec<String>>().join(&separator);
 
-                _ => Err("corrupted array".to_string())
-            }
-        }
+    Ok(Value::Str(joined))
+}
 
-        _ => Err("unshift() expects an array".to_string())
-    }
+// methods every array has, besides them arrays only have a length
+pub fn get_method(name: &str) -> Option<Value> {
+    let (args, call_fn): (&[&str], NativeFn) = match name {
+        "at" => (&["self", "index"], at),
+        "push" => (&["self", "item"], push),
+        "pop" => (&["self"], pop),
+        "shift" => (&["self"], shift),
+        "unshift" => (&["self", "item"], unshift),
+        "map" => (&["self", "function"], map),
+        "filter" => (&["self", "function"], filter),
+        "reduce" => (&["self", "__args__"], reduce),
+        "for_each" => (&["self", "function"], for_each),
+        "find" => (&["self", "function"], find),
+        "index_of" => (&["self", "item"], index_of),
+        "includes" => (&["self", "item"], includes),
+        "sort" => (&["self", "__args__"], sort),
+        "reverse" => (&["self"], reverse),
+        "slice" => (&["self", "__args__"], slice),
+        "concat" => (&["self", "other"], concat),
+        "join" => (&["self", "__args__"], join),
+        _ => return None,
+    };
+
+    Some(Value::NativeFunction {
+        name: name.to_string(),
+        args: args.iter().map(|arg| arg.to_string()).collect(),
+        call_fn,
+    })
 }
 
-pub fn get_object() -> HashMap<String, AST> {
+pub fn get_object() -> HashMap<String, Value> {
     let mut object = HashMap::new();
 
     object.insert(
         "new".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "new".to_string(),
             args: vec![],
             call_fn: new
@@ -354,7 +390,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     object.insert(
         "isarray".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "isarray".to_string(),
             args: vec!["arr".to_string()],
             call_fn: isarray
@@ -374,4 +410,68 @@ mod tests {
 
         assert_eq!(object.len(), 2);
     }
-}
\ No newline at end of file
+
+    fn numbers(values: &[i64]) -> Value {
+        Value::list(values.iter().map(|n| Value::Int(*n)).collect())
+    }
+
+    fn run(code: &str) -> Environment {
+        let mut context = crate::utils::create_context();
+
+        crate::parser::parse(&format!("import \"array\" as array\nlet a = array.new()\na.push(3)\na.push(1)\na.push(2)\n{}", code), &mut context).unwrap();
+
+        context
+    }
+
+    #[test]
+    fn map_filter_reduce() {
+        let context = run("let m = a.map(fn (x) { return x * 2 })\nlet f = a.filter(fn (x) { return x > 1 })\nlet r = a.reduce(fn (acc, x) { return acc + x }, 10)");
+
+        assert_eq!(context.get("m"), Some(numbers(&[6, 2, 4])));
+        assert_eq!(context.get("f"), Some(numbers(&[3, 2])));
+        assert_eq!(context.get("r"), Some(Value::Int(16)));
+    }
+
+    #[test]
+    fn sort_in_place() {
+        let context = run("a.sort()\nlet sorted = a.join(\",\")\na.sort(fn (x, y) { return y - x })");
+
+        assert_eq!(context.get("sorted"), Some(Value::Str("1,2,3".to_string())));
+        assert_eq!(context.get("a"), Some(numbers(&[3, 2, 1])));
+    }
+
+    #[test]
+    fn slice_and_concat() {
+        let mut context = Environment::default();
+
+        let sliced = slice(vec![numbers(&[1, 2, 3, 4]), Value::Int(1), Value::Int(-1)], &mut context).unwrap();
+        assert_eq!(sliced, numbers(&[2, 3]));
+
+        let joined = concat(vec![numbers(&[1]), numbers(&[2, 3])], &mut context).unwrap();
+        assert_eq!(joined, numbers(&[1, 2, 3]));
+
+        let index = index_of(vec![numbers(&[5, 6]), Value::Int(6)], &mut context).unwrap();
+        assert_eq!(index, Value::Int(1));
+    }
+
+    #[test]
+    fn sort_mixed_types() {
+        let mut context = Environment::default();
+
+        let result = sort(vec![Value::list(vec![Value::Int(1), Value::Str("a".to_string())])], &mut context);
+
+        assert_eq!(result.map_err(|e| e.to_string()), Err("TypeError: Cannot compare Int(1) and Str(\"a\")".to_string()));
+    }
+
+    #[test]
+    fn sort_with_bad_comparators() {
+        // says every pair is out of order, so it isn't an order at all
+        let context = run("let items = array.new()\nlet i = 0\nwhile i < 200 {\n    items.push((i * 7) % 13)\n    i += 1\n}\nitems.sort(fn (x, y) { return 1 })\nlet length = items.length");
+        assert_eq!(context.get("length"), Some(Value::Int(200)));
+
+        let mut context = crate::utils::create_context();
+        let result = crate::parser::parse("let items = [3, 1, 2]\nitems.sort(fn (x, y) {\n    throw \"no\"\n})", &mut context);
+
+        assert_eq!(result.map_err(|e| e.to_string()), Err("Error: no".to_string()));
+    }
+}
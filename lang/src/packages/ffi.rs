@@ -1,30 +1,31 @@
 use std::collections::HashMap;
-use crate::ast::AST;
-use crate::eval::eval;
+use crate::value::Value;
+use crate::environment::Environment;
+use crate::error::ModuError;
 
-pub fn call(mut args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
+pub fn call(mut args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
     // (path_to_lib, function_name, arg1, arg2, ...)
 
     if args.len() < 2 {
-        return Err("ffi.call requires at least 2 arguments".to_string());
+        return Err(ModuError::type_error("ffi.call requires at least 2 arguments"));
     }
 
-    let path = match eval(args[0].clone(), context) {
-        Ok(AST::String(v)) => v,
+    let path = match args[0].clone() {
+        Value::Str(v) => v,
 
-        _ => return Err("ffi.call first argument must be a string".to_string()),
+        _ => return Err(ModuError::type_error("ffi.call first argument must be a string")),
     };
 
-    let name = match eval(args[1].clone(), context) {
-        Ok(AST::String(v)) => v,
+    let name = match args[1].clone() {
+        Value::Str(v) => v,
 
-        _ => return Err("ffi.call second argument must be a string".to_string()),
+        _ => return Err(ModuError::type_error("ffi.call second argument must be a string")),
     };
 
     unsafe {
         let lib = match libloading::Library::new(path) {
             Ok(lib) => lib,
-            Err(e) => return Err(format!("Failed to load library: {}", e)),
+            Err(e) => return Err(ModuError::runtime(format!("Failed to load library: {}", e))),
         };
 
         let func: libloading::Symbol<unsafe extern "C" fn(
@@ -33,7 +34,7 @@ pub fn call(mut args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(A
         ) -> *mut std::ffi::c_void> 
             = match lib.get(name.as_bytes()) {
                 Ok(func) => func,
-                Err(e) => return Err(format!("Failed to load function: {}", e)),
+                Err(e) => return Err(ModuError::runtime(format!("Failed to load function: {}", e))),
             };
 
         let mut args_ptr: Vec<*mut std::ffi::c_char> = Vec::new();
@@ -42,21 +43,22 @@ pub fn call(mut args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(A
         args.remove(0);
 
         for arg in args {
-            match eval(arg, context) {
-                Ok(AST::Number(_)) => {
+            match arg {
+                Value::Int(_) => {
                     //args_ptr.push(v as *mut std::ffi::c_void);
-                    return Err("Cant use numbers in ffi, it was extremely broken, to be fixed\nSuggestion: turn int to str with str(int), then parse that to int in the lib".to_string());
+                    return Err(ModuError::type_error("Cant use numbers in ffi, it was extremely broken, to be fixed\nSuggestion: turn int to str with str(int), then parse that to int in the lib"));
                 }
 
-                Ok(AST::String(v)) => {
-                    let c_str = std::ffi::CString::new(v.replace("\"", "")).unwrap();
+                Value::Str(v) => {
+                    let c_str = match std::ffi::CString::new(v) {
+                        Ok(c_str) => c_str,
+                        Err(_) => return Err(ModuError::runtime("ffi.call arguments can't contain a null character")),
+                    };
                     
                     args_ptr.push(c_str.into_raw() as *mut std::ffi::c_char);
                 }
 
-                Ok(_) => return Err("ffi.call arguments must be numbers or strings".to_string()),
-
-                Err(e) => return Err(e),
+                _ => return Err(ModuError::type_error("ffi.call arguments must be numbers or strings")),
             };
         }
 
@@ -69,24 +71,24 @@ pub fn call(mut args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(A
         lib.close().unwrap();
 
         if result_ptr.is_null() {
-            return Ok((AST::Null, AST::Null));
+            return Ok(Value::Null);
         };
 
         if (result_ptr as i64) <= i32::MAX as i64 && (result_ptr as i64) >= i32::MIN as i64 {
-            return Ok((AST::Number(result_ptr as i64), AST::Null));
+            return Ok(Value::Int(result_ptr as i64));
         } else {
             let str = std::ffi::CStr::from_ptr(result_ptr as *const _);
-            return Ok((AST::String(str.to_string_lossy().into_owned()), AST::Null))
+            return Ok(Value::Str(str.to_string_lossy().into_owned()))
         }
     }
 }
 
-pub fn get_object() -> HashMap<String, AST> {
+pub fn get_object() -> HashMap<String, Value> {
 	let mut object = HashMap::new();
 
 	object.insert(
         "call".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "call".to_string(),
             args: vec!["__args__".to_string()],
             call_fn: call,
@@ -1,90 +1,339 @@
 use std::collections::HashMap;
 use std::fs::OpenOptions;
 use std::io::prelude::*;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
 
-use crate::ast::AST;
-use crate::eval::eval;
+use crate::value::Value;
+use crate::environment::Environment;
+use crate::error::ModuError;
 
-pub fn read(args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    let path = eval(args[0].clone(), context)?;
+pub fn read(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let path = args[0].clone();
 
     match path {
-        AST::String(val) => {
-            let contents = std::fs::read_to_string(val).map_err(|e| e.to_string())?;
-            Ok((AST::String(contents), AST::Null))
+        Value::Str(val) => {
+            let contents = std::fs::read_to_string(&val).map_err(|e| io_error(&val, e))?;
+            Ok(Value::Str(contents))
         }
 
-        _ => Err("read() expects a string".to_string())
+        _ => Err(ModuError::type_error("read() expects a string"))
     }
 }
 
-pub fn write(args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    let path = eval(args[0].clone(), context)?;
-    let contents = eval(args[1].clone(), context)?;
+pub fn write(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let path = args[0].clone();
+    let contents = args[1].clone();
 
     match (path, contents) {
-        (AST::String(path), AST::String(contents)) => {
-            let contents = contents
-                .replace("\\n", "\n")
-                .replace("\\t", "\t");
-
-            std::fs::write(path, contents).map_err(|e| e.to_string())?;
-            Ok((AST::Null, AST::Null))
+        (Value::Str(path), Value::Str(contents)) => {
+            std::fs::write(&path, contents).map_err(|e| io_error(&path, e))?;
+            Ok(Value::Null)
         }
 
-        _ => Err("write() expects two strings".to_string())
+        _ => Err(ModuError::type_error("write() expects two strings"))
     }
 }
 
-pub fn write_append(args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    let path = eval(args[0].clone(), context)?;
-    let contents = eval(args[1].clone(), context)?;
+pub fn write_append(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let path = args[0].clone();
+    let contents = args[1].clone();
 
     match (path, contents) {
-        (AST::String(path), AST::String(contents)) => {
+        (Value::Str(path), Value::Str(contents)) => {
             let mut file = OpenOptions::new()
                 .write(true)
                 .append(true)
-                .open(path)
-                .map_err(|e| e.to_string())?;
-
-            let contents = contents
-                .replace("\\n", "\n")
-                .replace("\\t", "\t");
+                .open(&path)
+                .map_err(|e| io_error(&path, e))?;
 
             if let Err(e) = writeln!(file, "{}", contents) {
-                return Err(e.to_string());
+                return Err(io_error(&path, e));
             }
 
-            Ok((AST::Null, AST::Null))
+            Ok(Value::Null)
         }
 
-        _ => Err("write_append() expects two strings".to_string())
+        _ => Err(ModuError::type_error("write_append() expects two strings"))
+    }
+}
+
+
+fn io_error(path: &str, e: impl std::fmt::Display) -> ModuError {
+    ModuError::io(format!("{}: {}", path, e))
+}
+
+fn path_arg(args: &[Value], index: usize, name: &str) -> Result<String, ModuError> {
+    match &args[index] {
+        Value::Str(path) => Ok(path.clone()),
+        _ => Err(ModuError::type_error(format!("{}() expects a path string", name))),
+    }
+}
+
+fn path_string(path: &Path) -> Value {
+    Value::Str(path.to_string_lossy().to_string())
+}
+
+pub fn exists(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let path = path_arg(&args, 0, "exists")?;
+
+    Ok(Value::Bool(Path::new(&path).exists()))
+}
+
+// removes a file, or a directory with everything in it
+pub fn remove(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let path = path_arg(&args, 0, "remove")?;
+
+    let result = if Path::new(&path).is_dir() {
+        std::fs::remove_dir_all(&path)
+    } else {
+        std::fs::remove_file(&path)
+    };
+
+    result.map_err(|e| io_error(&path, e))?;
+
+    Ok(Value::Null)
+}
+
+pub fn rename(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let from = path_arg(&args, 0, "rename")?;
+    let to = path_arg(&args, 1, "rename")?;
+
+    std::fs::rename(&from, &to).map_err(|e| io_error(&from, e))?;
+
+    Ok(Value::Null)
+}
+
+pub fn copy(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let from = path_arg(&args, 0, "copy")?;
+    let to = path_arg(&args, 1, "copy")?;
+
+    std::fs::copy(&from, &to).map_err(|e| io_error(&from, e))?;
+
+    Ok(Value::Null)
+}
+
+// creates the directory and any missing parents, does nothing if it already exists
+pub fn mkdir(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let path = path_arg(&args, 0, "mkdir")?;
+
+    std::fs::create_dir_all(&path).map_err(|e| io_error(&path, e))?;
+
+    Ok(Value::Null)
+}
+
+// the names of the files and directories in a directory, sorted
+pub fn list_dir(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let path = path_arg(&args, 0, "list_dir")?;
+
+    let mut names = Vec::new();
+
+    for entry in std::fs::read_dir(&path).map_err(|e| io_error(&path, e))? {
+        let entry = entry.map_err(|e| io_error(&path, e))?;
+
+        names.push(entry.file_name().to_string_lossy().to_string());
     }
+
+    names.sort();
+
+    Ok(Value::list(names.into_iter().map(Value::Str).collect()))
 }
 
+// size in bytes, mtime as a unix timestamp like time.now(), is_dir and is_file
+pub fn stat(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let path = path_arg(&args, 0, "stat")?;
+
+    let metadata = std::fs::metadata(&path).map_err(|e| io_error(&path, e))?;
+
+    let mtime = metadata.modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|since| Value::Int(since.as_secs() as i64))
+        .unwrap_or(Value::Null);
+
+    Ok(Value::map(HashMap::from([
+        ("size".to_string(), Value::Int(metadata.len() as i64)),
+        ("mtime".to_string(), mtime),
+        ("is_dir".to_string(), Value::Bool(metadata.is_dir())),
+        ("is_file".to_string(), Value::Bool(metadata.is_file())),
+    ])))
+}
+
+pub fn read_lines(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let path = path_arg(&args, 0, "read_lines")?;
+
+    let contents = std::fs::read_to_string(&path).map_err(|e| io_error(&path, e))?;
+
+    Ok(Value::list(contents.lines().map(|line| Value::Str(line.to_string())).collect()))
+}
+
+// the bytes as an array of numbers from 0 to 255
+pub fn read_bytes(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let path = path_arg(&args, 0, "read_bytes")?;
+
+    let bytes = std::fs::read(&path).map_err(|e| io_error(&path, e))?;
+
+    Ok(Value::list(bytes.into_iter().map(|byte| Value::Int(byte as i64)).collect()))
+}
+
+pub fn write_bytes(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let path = path_arg(&args, 0, "write_bytes")?;
+
+    let bytes = match &args[1] {
+        Value::List(items) => {
+            items.borrow().iter().map(|item| match item {
+                Value::Int(byte) if (0..=255).contains(byte) => Ok(*byte as u8),
+                _ => Err(ModuError::type_error(format!("write_bytes() expects numbers from 0 to 255, got {}", item))),
+            }).collect::<Result<Vec<u8>, ModuError>>()?
+        }
+
+        _ => return Err(ModuError::type_error("write_bytes() expects an array of numbers")),
+    };
+
+    std::fs::write(&path, bytes).map_err(|e| io_error(&path, e))?;
+
+    Ok(Value::Null)
+}
+
+// the paths matching a pattern like "src/**/*.modu", sorted
+pub fn glob(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let pattern = path_arg(&args, 0, "glob")?;
+
+    let paths = ::glob::glob(&pattern)
+        .map_err(|e| ModuError::runtime(format!("Invalid glob pattern {}: {}", pattern, e)))?;
+
+    let mut matches = Vec::new();
+
+    for path in paths {
+        let path = path.map_err(|e| io_error(&e.path().to_string_lossy(), e.error()))?;
+
+        matches.push(path);
+    }
+
+    matches.sort();
+
+    Ok(Value::list(matches.iter().map(|path| path_string(path)).collect()))
+}
 
-pub fn get_object() -> HashMap<String, AST> {
+// an empty file in the system's temp directory, it is kept until it is removed
+pub fn temp_file(_: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let path = tempfile::NamedTempFile::new()
+        .map_err(|e| ModuError::io(e.to_string()))?
+        .into_temp_path()
+        .keep()
+        .map_err(|e| ModuError::io(e.to_string()))?;
+
+    Ok(path_string(&path))
+}
+
+// an empty directory in the system's temp directory, it is kept until it is removed
+pub fn temp_dir(_: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let path = tempfile::tempdir()
+        .map_err(|e| ModuError::io(e.to_string()))?
+        .keep();
+
+    Ok(path_string(&path))
+}
+
+pub fn get_object() -> HashMap<String, Value> {
     let mut object = HashMap::new();
 
     object.insert(
         "read".to_string(),
-        AST::InternalFunction { 
+        Value::NativeFunction { 
             name:"read".to_string(), args: vec!["path".to_string()], call_fn: read }
     );
 
     object.insert(
         "write".to_string(),
-        AST::InternalFunction { 
+        Value::NativeFunction { 
             name:"write".to_string(), args: vec!["path".to_string(), "content".to_string()], call_fn: write }
     );
 
     object.insert(
         "write_append".to_string(),
-        AST::InternalFunction { 
+        Value::NativeFunction { 
             name:"write_append".to_string(), args: vec!["path".to_string(), "content".to_string()], call_fn: write_append }
     );
 
+    object.insert(
+        "exists".to_string(),
+        Value::NativeFunction { 
+            name:"exists".to_string(), args: vec!["path".to_string()], call_fn: exists }
+    );
+
+    object.insert(
+        "remove".to_string(),
+        Value::NativeFunction { 
+            name:"remove".to_string(), args: vec!["path".to_string()], call_fn: remove }
+    );
+
+    object.insert(
+        "rename".to_string(),
+        Value::NativeFunction { 
+            name:"rename".to_string(), args: vec!["from".to_string(), "to".to_string()], call_fn: rename }
+    );
+
+    object.insert(
+        "copy".to_string(),
+        Value::NativeFunction { 
+            name:"copy".to_string(), args: vec!["from".to_string(), "to".to_string()], call_fn: copy }
+    );
+
+    object.insert(
+        "mkdir".to_string(),
+        Value::NativeFunction { 
+            name:"mkdir".to_string(), args: vec!["path".to_string()], call_fn: mkdir }
+    );
+
+    object.insert(
+        "list_dir".to_string(),
+        Value::NativeFunction { 
+            name:"list_dir".to_string(), args: vec!["path".to_string()], call_fn: list_dir }
+    );
+
+    object.insert(
+        "stat".to_string(),
+        Value::NativeFunction { 
+            name:"stat".to_string(), args: vec!["path".to_string()], call_fn: stat }
+    );
+
+    object.insert(
+        "read_lines".to_string(),
+        Value::NativeFunction { 
+            name:"read_lines".to_string(), args: vec!["path".to_string()], call_fn: read_lines }
+    );
+
+    object.insert(
+        "read_bytes".to_string(),
+        Value::NativeFunction { 
+            name:"read_bytes".to_string(), args: vec!["path".to_string()], call_fn: read_bytes }
+    );
+
+    object.insert(
+        "write_bytes".to_string(),
+        Value::NativeFunction { 
+            name:"write_bytes".to_string(), args: vec!["path".to_string(), "bytes".to_string()], call_fn: write_bytes }
+    );
+
+    object.insert(
+        "glob".to_string(),
+        Value::NativeFunction { 
+            name:"glob".to_string(), args: vec!["pattern".to_string()], call_fn: glob }
+    );
+
+    object.insert(
+        "temp_file".to_string(),
+        Value::NativeFunction { 
+            name:"temp_file".to_string(), args: vec![], call_fn: temp_file }
+    );
+
+    object.insert(
+        "temp_dir".to_string(),
+        Value::NativeFunction { 
+            name:"temp_dir".to_string(), args: vec![], call_fn: temp_dir }
+    );
+
     return object;
 }
 
@@ -96,6 +345,62 @@ mod tests {
     fn get_object_test() {
         let object = get_object();
 
-        assert_eq!(object.len(), 3);
+        assert_eq!(object.len(), 16);
+    }
+
+    fn get(object: &Value, key: &str) -> Value {
+        match object {
+            Value::Map(properties) => properties.borrow()[key].clone(),
+            _ => panic!("Expected an object"),
+        }
+    }
+
+    #[test]
+    fn filesystem() {
+        let mut context = Environment::default();
+
+        let dir = match temp_dir(vec![], &mut context).unwrap() {
+            Value::Str(dir) => dir,
+            _ => panic!("temp_dir() did not return a path"),
+        };
+
+        let nested = format!("{}/a/b", dir);
+        mkdir(vec![Value::Str(nested.clone())], &mut context).unwrap();
+
+        let text = format!("{}/a/notes.txt", dir);
+        write(vec![Value::Str(text.clone()), Value::Str("one\ntwo\n".to_string())], &mut context).unwrap();
+
+        assert_eq!(read_lines(vec![Value::Str(text.clone())], &mut context).unwrap().to_string(), "[one, two]");
+        assert_eq!(list_dir(vec![Value::Str(format!("{}/a", dir))], &mut context).unwrap().to_string(), "[b, notes.txt]");
+
+        let info = stat(vec![Value::Str(text.clone())], &mut context).unwrap();
+        assert_eq!(get(&info, "size"), Value::Int(8));
+        assert_eq!(get(&info, "is_dir"), Value::Bool(false));
+        assert!(matches!(get(&info, "mtime"), Value::Int(_)));
+
+        let bin = format!("{}/a/b/data.bin", dir);
+        write_bytes(vec![Value::Str(bin.clone()), Value::list(vec![Value::Int(0), Value::Int(255)])], &mut context).unwrap();
+        assert_eq!(read_bytes(vec![Value::Str(bin.clone())], &mut context).unwrap().to_string(), "[0, 255]");
+        assert!(write_bytes(vec![Value::Str(bin.clone()), Value::list(vec![Value::Int(256)])], &mut context).is_err());
+
+        let copied = format!("{}/copy.txt", dir);
+        copy(vec![Value::Str(text.clone()), Value::Str(copied.clone())], &mut context).unwrap();
+        rename(vec![Value::Str(copied.clone()), Value::Str(format!("{}/moved.txt", dir))], &mut context).unwrap();
+
+        let found = glob(vec![Value::Str(format!("{}/**/*.txt", dir))], &mut context).unwrap();
+        assert_eq!(found.to_string(), format!("[{}/a/notes.txt, {}/moved.txt]", dir, dir));
+
+        remove(vec![Value::Str(dir.clone())], &mut context).unwrap();
+        assert_eq!(exists(vec![Value::Str(dir.clone())], &mut context).unwrap(), Value::Bool(false));
+    }
+
+    #[test]
+    fn missing_files() {
+        let mut context = Environment::default();
+
+        let result = read_lines(vec![Value::Str("/definitely/not/here.txt".to_string())], &mut context);
+        assert_eq!(result.map_err(|e| e.to_string()), Err("IOError: /definitely/not/here.txt: No such file or directory (os error 2)".to_string()));
+
+        assert!(remove(vec![Value::Str("/definitely/not/here.txt".to_string())], &mut context).is_err());
     }
 }
@@ -0,0 +1,355 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use crate::value::Value;
+use crate::environment::Environment;
+use crate::error::ModuError;
+use crate::packages::json;
+
+struct Request {
+    method: String,
+    url: String,
+    headers: Vec<(String, String)>,
+    body: Value,
+    timeout: Option<f64>,
+}
+
+fn string_arg(args: &[Value], index: usize, name: &str) -> Result<String, ModuError> {
+    match args.get(index) {
+        Some(Value::Str(s)) => Ok(s.clone()),
+        _ => Err(ModuError::type_error(format!("http.{}() expects a string as argument {}", name, index + 1))),
+    }
+}
+
+fn headers_of(value: Option<&Value>, name: &str) -> Result<Vec<(String, String)>, ModuError> {
+    match value {
+        None | Some(Value::Null) => Ok(vec![]),
+
+        Some(Value::Map(headers)) => {
+            Ok(headers.borrow().iter().map(|(key, value)| (key.clone(), value.to_string())).collect())
+        }
+
+        _ => Err(ModuError::type_error(format!("http.{}() headers must be an object", name))),
+    }
+}
+
+// reqwest's message leaves out why the request failed, like the connection being refused
+fn request_error(e: reqwest::Error) -> ModuError {
+    let mut message = e.to_string();
+    let mut source = std::error::Error::source(&e);
+
+    while let Some(cause) = source {
+        message = format!("{}: {}", message, cause);
+        source = cause.source();
+    }
+
+    ModuError::io(message)
+}
+
+// the response as an object with status, headers, body and a json() method
+fn send(request: Request) -> Result<Value, ModuError> {
+    let method = reqwest::Method::from_bytes(request.method.to_uppercase().as_bytes())
+        .map_err(|_| ModuError::type_error(format!("{} is not an http method", request.method)))?;
+
+    let mut builder = reqwest::blocking::Client::new().request(method, &request.url);
+
+    if let Some(timeout) = request.timeout {
+        builder = builder.timeout(Duration::from_secs_f64(timeout));
+    }
+
+    let has_content_type = request.headers.iter().any(|(key, _)| key.eq_ignore_ascii_case("content-type"));
+
+    for (key, value) in request.headers {
+        builder = builder.header(key, value);
+    }
+
+    // arrays and objects are sent as json
+    builder = match request.body {
+        Value::Null => builder,
+
+        Value::Str(body) => builder.body(body),
+
+        body @ (Value::List(_) | Value::Map(_)) => {
+            if !has_content_type {
+                builder = builder.header("content-type", "application/json");
+            }
+
+            builder.body(json::make_stuff_string(&body)?)
+        }
+
+        body => builder.body(body.to_string()),
+    };
+
+    let response = builder.send().map_err(request_error)?;
+
+    let mut headers: HashMap<String, Value> = HashMap::new();
+
+    for (key, value) in response.headers() {
+        let value = String::from_utf8_lossy(value.as_bytes()).to_string();
+
+        // a header sent more than once is joined like browsers do
+        let value = match headers.remove(key.as_str()) {
+            Some(Value::Str(previous)) => format!("{}, {}", previous, value),
+            _ => value,
+        };
+
+        headers.insert(key.as_str().to_string(), Value::Str(value));
+    }
+
+    let status = response.status().as_u16() as i64;
+    let body = response.text().map_err(request_error)?;
+
+    let mut object = HashMap::new();
+
+    object.insert("status".to_string(), Value::Int(status));
+    object.insert("headers".to_string(), Value::map(headers));
+    object.insert("body".to_string(), Value::Str(body));
+
+    object.insert(
+        "json".to_string(),
+        Value::NativeFunction {
+            name: "json".to_string(),
+            args: vec!["self".to_string()],
+            call_fn: body_json,
+        }
+    );
+
+    Ok(Value::map(object))
+}
+
+// response.json() and an httpserver request's json(), parses the body
+pub fn body_json(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let body = match &args[0] {
+        Value::Map(properties) => properties.borrow().get("body").cloned(),
+        _ => None,
+    };
+
+    match body {
+        Some(Value::Str(body)) => {
+            let json: serde_json::Value = serde_json::from_str(&body)
+                .map_err(|e| ModuError::runtime(format!("json() got invalid JSON: {}", e)))?;
+
+            Ok(json::from_json(json))
+        }
+
+        _ => Err(ModuError::type_error("json() expects an object with a string body")),
+    }
+}
+
+// get(url) or get(url, headers), delete is the same
+fn without_body(method: &str, args: Vec<Value>) -> Result<Value, ModuError> {
+    if args.len() != 1 && args.len() != 2 {
+        return Err(ModuError::type_error(format!("http.{}() takes a url and optional headers", method)));
+    }
+
+    send(Request {
+        method: method.to_string(),
+        url: string_arg(&args, 0, method)?,
+        headers: headers_of(args.get(1), method)?,
+        body: Value::Null,
+        timeout: None,
+    })
+}
+
+// post(url, body) or post(url, body, headers), put is the same
+fn with_body(method: &str, args: Vec<Value>) -> Result<Value, ModuError> {
+    if args.len() != 2 && args.len() != 3 {
+        return Err(ModuError::type_error(format!("http.{}() takes a url, a body and optional headers", method)));
+    }
+
+    send(Request {
+        method: method.to_string(),
+        url: string_arg(&args, 0, method)?,
+        headers: headers_of(args.get(2), method)?,
+        body: args[1].clone(),
+        timeout: None,
+    })
+}
+
+pub fn get(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    without_body("get", args)
+}
+
+pub fn delete(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    without_body("delete", args)
+}
+
+pub fn post(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    with_body("post", args)
+}
+
+pub fn put(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    with_body("put", args)
+}
+
+// request({ "url": ..., "method": ..., "headers": ..., "body": ..., "timeout": seconds }), only the url is needed
+pub fn request(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let options = match &args[0] {
+        Value::Map(options) => options.borrow().clone(),
+        _ => return Err(ModuError::type_error("http.request() expects an object")),
+    };
+
+    let url = match options.get("url") {
+        Some(Value::Str(url)) => url.clone(),
+        _ => return Err(ModuError::type_error("http.request() needs a url string")),
+    };
+
+    let method = match options.get("method") {
+        None => "GET".to_string(),
+        Some(Value::Str(method)) => method.clone(),
+        _ => return Err(ModuError::type_error("http.request() method must be a string")),
+    };
+
+    let timeout = match options.get("timeout") {
+        None | Some(Value::Null) => None,
+        Some(Value::Int(seconds)) if *seconds > 0 => Some(*seconds as f64),
+        Some(Value::Float(seconds)) if *seconds > 0.0 => Some(*seconds),
+        _ => return Err(ModuError::type_error("http.request() timeout must be a positive number of seconds")),
+    };
+
+    send(Request {
+        method,
+        url,
+        headers: headers_of(options.get("headers"), "request")?,
+        body: options.get("body").cloned().unwrap_or(Value::Null),
+        timeout,
+    })
+}
+
+pub fn get_object() -> HashMap<String, Value> {
+    let mut object = HashMap::new();
+
+    object.insert(
+        "get".to_string(),
+        Value::NativeFunction {
+            name: "get".to_string(),
+            args: vec!["__args__".to_string()],
+            call_fn: get,
+        }
+    );
+
+    object.insert(
+        "post".to_string(),
+        Value::NativeFunction {
+            name: "post".to_string(),
+            args: vec!["__args__".to_string()],
+            call_fn: post,
+        }
+    );
+
+    object.insert(
+        "put".to_string(),
+        Value::NativeFunction {
+            name: "put".to_string(),
+            args: vec!["__args__".to_string()],
+            call_fn: put,
+        }
+    );
+
+    object.insert(
+        "delete".to_string(),
+        Value::NativeFunction {
+            name: "delete".to_string(),
+            args: vec!["__args__".to_string()],
+            call_fn: delete,
+        }
+    );
+
+    object.insert(
+        "request".to_string(),
+        Value::NativeFunction {
+            name: "request".to_string(),
+            args: vec!["options".to_string()],
+            call_fn: request,
+        }
+    );
+
+    object
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    // a local server that answers every request with what it got
+    fn echo_server() -> (String, rouille::Server<impl Fn(&rouille::Request) -> rouille::Response + Send + Sync>) {
+        let server = rouille::Server::new("127.0.0.1:0", |request| {
+            let mut body = String::new();
+            request.data().unwrap().read_to_string(&mut body).unwrap();
+
+            let echo = serde_json::json!({
+                "method": request.method(),
+                "path": request.raw_url(),
+                "token": request.header("x-token"),
+                "type": request.header("content-type"),
+                "body": body,
+            });
+
+            rouille::Response::json(&echo).with_status_code(201).with_additional_header("x-echo", "yes")
+        }).unwrap();
+
+        (format!("http://{}", server.server_addr()), server)
+    }
+
+    fn property(object: &Value, key: &str) -> Value {
+        match object {
+            Value::Map(properties) => properties.borrow().get(key).cloned().unwrap_or(Value::Null),
+            _ => panic!("Expected an object"),
+        }
+    }
+
+    #[test]
+    fn get_object_test() {
+        let object = get_object();
+
+        assert_eq!(object.len(), 5);
+    }
+
+    #[test]
+    fn requests() {
+        let (url, server) = echo_server();
+        let (handle, stop) = server.stoppable();
+        let mut context = Environment::default();
+
+        let headers = Value::map(HashMap::from([("x-token".to_string(), Value::Str("abc".to_string()))]));
+        let response = get(vec![Value::Str(format!("{}/todos?id=1", url)), headers], &mut context).unwrap();
+
+        assert_eq!(property(&response, "status"), Value::Int(201));
+        assert_eq!(property(&property(&response, "headers"), "x-echo"), Value::Str("yes".to_string()));
+
+        let echo = body_json(vec![response], &mut context).unwrap();
+        assert_eq!(property(&echo, "method"), Value::Str("GET".to_string()));
+        assert_eq!(property(&echo, "path"), Value::Str("/todos?id=1".to_string()));
+        assert_eq!(property(&echo, "token"), Value::Str("abc".to_string()));
+
+        let body = Value::map(HashMap::from([("done".to_string(), Value::Bool(true))]));
+        let response = post(vec![Value::Str(url.clone()), body], &mut context).unwrap();
+
+        let echo = body_json(vec![response], &mut context).unwrap();
+        assert_eq!(property(&echo, "type"), Value::Str("application/json".to_string()));
+        assert_eq!(property(&echo, "body"), Value::Str("{\"done\":true}".to_string()));
+
+        let options = Value::map(HashMap::from([
+            ("url".to_string(), Value::Str(url.clone())),
+            ("method".to_string(), Value::Str("patch".to_string())),
+            ("body".to_string(), Value::Str("plain".to_string())),
+        ]));
+
+        let response = request(vec![options], &mut context).unwrap();
+
+        let echo = body_json(vec![response], &mut context).unwrap();
+        assert_eq!(property(&echo, "method"), Value::Str("PATCH".to_string()));
+        assert_eq!(property(&echo, "body"), Value::Str("plain".to_string()));
+
+        stop.send(()).unwrap();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn connection_errors() {
+        let mut context = Environment::default();
+
+        let result = get(vec![Value::Str("http://127.0.0.1:1".to_string())], &mut context);
+        assert!(result.map_err(|e| e.to_string()).unwrap_err().starts_with("IOError"));
+    }
+}
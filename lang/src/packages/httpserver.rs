@@ -0,0 +1,471 @@
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::mpsc;
+use crate::ast::Span;
+use crate::value::Value;
+use crate::environment::Environment;
+use crate::error::ModuError;
+use crate::eval::call_function;
+use crate::packages::{http, json};
+
+// modu values can't leave the thread they were made on, so rouille's threads hand requests
+// to the thread that called listen() and wait for it to answer
+
+struct Request {
+    method: String,
+    path: String,
+    query: String,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+struct Response {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl Response {
+    fn text(status: u16, body: &str) -> Response {
+        Response {
+            status,
+            headers: vec![("Content-Type".to_string(), "text/plain; charset=utf-8".to_string())],
+            body: body.to_string(),
+        }
+    }
+}
+
+fn app_arg(args: &[Value]) -> Result<std::rc::Rc<std::cell::RefCell<HashMap<String, Value>>>, ModuError> {
+    match &args[0] {
+        Value::Map(properties) => Ok(properties.clone()),
+        _ => Err(ModuError::runtime("uh oh, why is self not an app? this is a bug, please report it")),
+    }
+}
+
+// app.get("/users/:id", handler) and the other methods add a route
+fn add_route(method: &str, args: Vec<Value>) -> Result<Value, ModuError> {
+    let path = match &args[1] {
+        Value::Str(path) => path.clone(),
+        _ => return Err(ModuError::type_error(format!("app.{}() expects a path string", method.to_lowercase()))),
+    };
+
+    if !matches!(args[2], Value::Function(_) | Value::NativeFunction { .. }) {
+        return Err(ModuError::type_error(format!("app.{}() expects a handler function", method.to_lowercase())));
+    }
+
+    let route = Value::map(HashMap::from([
+        ("method".to_string(), Value::Str(method.to_string())),
+        ("path".to_string(), Value::Str(path)),
+        ("handler".to_string(), args[2].clone()),
+    ]));
+
+    let app = app_arg(&args)?;
+
+    match app.borrow().get("routes") {
+        Some(Value::List(routes)) => routes.borrow_mut().push(route),
+        _ => return Err(ModuError::type_error("app.routes must be an array")),
+    }
+
+    Ok(Value::Null)
+}
+
+pub fn get(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    add_route("GET", args)
+}
+
+pub fn post(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    add_route("POST", args)
+}
+
+pub fn put(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    add_route("PUT", args)
+}
+
+pub fn patch(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    add_route("PATCH", args)
+}
+
+pub fn delete(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    add_route("DELETE", args)
+}
+
+// the params of `path` if it matches `pattern`, segments starting with ":" match anything
+fn match_route(pattern: &str, path: &str) -> Option<HashMap<String, Value>> {
+    let pattern = pattern.trim_matches('/').split('/').collect::<Vec<&str>>();
+    let path = path.trim_matches('/').split('/').collect::<Vec<&str>>();
+
+    if pattern.len() != path.len() {
+        return None;
+    }
+
+    let mut params = HashMap::new();
+
+    for (expected, actual) in pattern.iter().zip(path) {
+        match expected.strip_prefix(':') {
+            Some(name) if !actual.is_empty() => {
+                params.insert(name.to_string(), Value::Str(actual.to_string()));
+            }
+
+            Some(_) => return None,
+
+            None if *expected != actual => return None,
+
+            None => {}
+        }
+    }
+
+    Some(params)
+}
+
+fn string_map(pairs: impl Iterator<Item = (String, String)>) -> Value {
+    Value::map(pairs.map(|(key, value)| (key, Value::Str(value))).collect())
+}
+
+// what a handler returns: an object with status, headers and a json or body, a string, or null for an empty response
+fn to_response(value: Value) -> Result<Response, ModuError> {
+    let properties = match value {
+        Value::Null => return Ok(Response { status: 204, headers: vec![], body: String::new() }),
+        Value::Map(properties) => properties.borrow().clone(),
+        value => return Ok(Response::text(200, &value.to_string())),
+    };
+
+    let status = match properties.get("status") {
+        None => 200,
+        Some(Value::Int(status)) if (100..600).contains(status) => *status as u16,
+        Some(status) => return Err(ModuError::type_error(format!("{} is not a valid status code", status))),
+    };
+
+    let mut headers = match properties.get("headers") {
+        None => vec![],
+        Some(Value::Map(headers)) => headers.borrow().iter().map(|(key, value)| (key.clone(), value.to_string())).collect(),
+        Some(_) => return Err(ModuError::type_error("Response headers must be an object")),
+    };
+
+    let has_content_type = headers.iter().any(|(key, _)| key.eq_ignore_ascii_case("content-type"));
+
+    let (body, content_type) = match (properties.get("json"), properties.get("body")) {
+        (Some(value), _) => (json::make_stuff_string(value)?, "application/json"),
+        (None, Some(body)) => (body.to_string(), "text/plain; charset=utf-8"),
+        (None, None) => (String::new(), "text/plain; charset=utf-8"),
+    };
+
+    if !has_content_type {
+        headers.push(("Content-Type".to_string(), content_type.to_string()));
+    }
+
+    Ok(Response { status, headers, body })
+}
+
+// runs the handler of the first route that matches, errors are printed and become a 500
+fn handle(routes: &[Value], request: Request, context: &mut Environment) -> Response {
+    for route in routes {
+        let (method, pattern, handler) = match route {
+            Value::Map(route) => {
+                let route = route.borrow();
+
+                match (route.get("method"), route.get("path"), route.get("handler")) {
+                    (Some(Value::Str(method)), Some(Value::Str(path)), Some(handler)) => (method.clone(), path.clone(), handler.clone()),
+                    _ => continue,
+                }
+            }
+
+            _ => continue,
+        };
+
+        if method != request.method {
+            continue;
+        }
+
+        let params = match match_route(&pattern, &request.path) {
+            Some(params) => params,
+            None => continue,
+        };
+
+        let query = form_urlencoded::parse(request.query.as_bytes()).map(|(key, value)| (key.to_string(), value.to_string()));
+
+        let req = Value::map(HashMap::from([
+            ("method".to_string(), Value::Str(request.method)),
+            ("path".to_string(), Value::Str(request.path)),
+            ("params".to_string(), Value::map(params)),
+            ("query".to_string(), string_map(query)),
+            ("headers".to_string(), string_map(request.headers.into_iter())),
+            ("body".to_string(), Value::Str(request.body)),
+            ("json".to_string(), Value::NativeFunction {
+                name: "json".to_string(),
+                args: vec!["self".to_string()],
+                call_fn: http::body_json,
+            }),
+        ]));
+
+        let result = call_function(&handler, vec![req], Span::default(), context).and_then(to_response);
+
+        return match result {
+            Ok(response) => response,
+
+            Err(e) => {
+                eprintln!("\n⚠️  {} {} | {}", method, pattern, e);
+
+                Response::text(500, "Internal Server Error")
+            }
+        };
+    }
+
+    Response::text(404, "Not Found")
+}
+
+fn to_request(request: &rouille::Request) -> Request {
+    let mut body = String::new();
+
+    if let Some(mut data) = request.data() {
+        let _ = data.read_to_string(&mut body);
+    }
+
+    Request {
+        method: request.method().to_string(),
+        path: request.url(),
+        query: request.raw_query_string().to_string(),
+        headers: request.headers().map(|(key, value)| (key.to_lowercase(), value.to_string())).collect(),
+        body,
+    }
+}
+
+// app.listen(port), serves requests until the program is stopped
+pub fn listen(args: Vec<Value>, context: &mut Environment) -> Result<Value, ModuError> {
+    let port = match &args[1] {
+        Value::Int(port) if (0..=65535).contains(port) => *port,
+        _ => return Err(ModuError::type_error("app.listen() expects a port number")),
+    };
+
+    let app = app_arg(&args)?;
+
+    let (sender, receiver) = mpsc::channel::<(Request, mpsc::Sender<Response>)>();
+    let sender = std::sync::Mutex::new(sender);
+
+    let server = rouille::Server::new(format!("0.0.0.0:{}", port), move |request| {
+        let (reply, answer) = mpsc::channel();
+
+        let sent = sender.lock().unwrap().send((to_request(request), reply));
+
+        let response = match sent.ok().and_then(|_| answer.recv().ok()) {
+            Some(response) => response,
+            None => Response::text(503, "Service Unavailable"),
+        };
+
+        rouille::Response {
+            status_code: response.status,
+            headers: response.headers.into_iter().map(|(key, value)| (key.into(), value.into())).collect(),
+            data: rouille::ResponseBody::from_string(response.body),
+            upgrade: None,
+        }
+    }).map_err(|e| ModuError::io(format!("Could not listen on port {}: {}", port, e)))?;
+
+    let (_thread, _stop) = server.stoppable();
+
+    for (request, reply) in receiver {
+        // routes are read for every request, so ones added by a handler work right away
+        let routes = match app.borrow().get("routes") {
+            Some(Value::List(routes)) => routes.borrow().clone(),
+            _ => vec![],
+        };
+
+        let _ = reply.send(handle(&routes, request, context));
+    }
+
+    Ok(Value::Null)
+}
+
+pub fn new(_: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let mut app = HashMap::new();
+
+    app.insert("routes".to_string(), Value::list(vec![]));
+
+    app.insert(
+        "get".to_string(),
+        Value::NativeFunction {
+            name: "get".to_string(),
+            args: vec!["self".to_string(), "path".to_string(), "handler".to_string()],
+            call_fn: get,
+        }
+    );
+
+    app.insert(
+        "post".to_string(),
+        Value::NativeFunction {
+            name: "post".to_string(),
+            args: vec!["self".to_string(), "path".to_string(), "handler".to_string()],
+            call_fn: post,
+        }
+    );
+
+    app.insert(
+        "put".to_string(),
+        Value::NativeFunction {
+            name: "put".to_string(),
+            args: vec!["self".to_string(), "path".to_string(), "handler".to_string()],
+            call_fn: put,
+        }
+    );
+
+    app.insert(
+        "patch".to_string(),
+        Value::NativeFunction {
+            name: "patch".to_string(),
+            args: vec!["self".to_string(), "path".to_string(), "handler".to_string()],
+            call_fn: patch,
+        }
+    );
+
+    app.insert(
+        "delete".to_string(),
+        Value::NativeFunction {
+            name: "delete".to_string(),
+            args: vec!["self".to_string(), "path".to_string(), "handler".to_string()],
+            call_fn: delete,
+        }
+    );
+
+    app.insert(
+        "listen".to_string(),
+        Value::NativeFunction {
+            name: "listen".to_string(),
+            args: vec!["self".to_string(), "port".to_string()],
+            call_fn: listen,
+        }
+    );
+
+    Ok(Value::map(app))
+}
+
+pub fn get_object() -> HashMap<String, Value> {
+    let mut object = HashMap::new();
+
+    object.insert(
+        "new".to_string(),
+        Value::NativeFunction {
+            name: "new".to_string(),
+            args: vec![],
+            call_fn: new,
+        }
+    );
+
+    object
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: &str, path: &str, query: &str, body: &str) -> Request {
+        Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            query: query.to_string(),
+            headers: vec![("x-token".to_string(), "abc".to_string())],
+            body: body.to_string(),
+        }
+    }
+
+    // answers with what it got as json
+    fn echo(args: Vec<Value>, context: &mut Environment) -> Result<Value, ModuError> {
+        let req = match &args[0] {
+            Value::Map(req) => req.borrow().clone(),
+            _ => panic!("Expected a request object"),
+        };
+
+        let parsed = http::body_json(args, context).unwrap_or(Value::Null);
+
+        let json = Value::map(HashMap::from([
+            ("params".to_string(), req["params"].clone()),
+            ("query".to_string(), req["query"].clone()),
+            ("headers".to_string(), req["headers"].clone()),
+            ("parsed".to_string(), parsed),
+        ]));
+
+        Ok(Value::map(HashMap::from([
+            ("status".to_string(), Value::Int(201)),
+            ("json".to_string(), json),
+        ])))
+    }
+
+    fn fail(_: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+        Err(ModuError::runtime("oops"))
+    }
+
+    fn app_with(routes: &[(&str, &str, crate::value::NativeFn)]) -> Vec<Value> {
+        let mut context = Environment::default();
+        let app = new(vec![], &mut context).unwrap();
+
+        for (method, path, handler) in routes {
+            let handler = Value::NativeFunction { name: "handler".to_string(), args: vec!["req".to_string()], call_fn: *handler };
+            add_route(method, vec![app.clone(), Value::Str(path.to_string()), handler]).unwrap();
+        }
+
+        match &app {
+            Value::Map(app) => match app.borrow().get("routes") {
+                Some(Value::List(routes)) => routes.borrow().clone(),
+                _ => panic!("Expected routes"),
+            },
+
+            _ => panic!("Expected an app"),
+        }
+    }
+
+    #[test]
+    fn get_object_test() {
+        let object = get_object();
+
+        assert_eq!(object.len(), 1);
+    }
+
+    #[test]
+    fn matching_routes() {
+        assert_eq!(match_route("/", "/"), Some(HashMap::new()));
+        assert_eq!(match_route("/users", "/users/"), Some(HashMap::new()));
+        assert_eq!(match_route("/users/:id", "/users/7"), Some(HashMap::from([("id".to_string(), Value::Str("7".to_string()))])));
+        assert_eq!(match_route("/users/:id", "/users/7/posts"), None);
+        assert_eq!(match_route("/users/:id", "/users/"), None);
+        assert_eq!(match_route("/users", "/posts"), None);
+    }
+
+    #[test]
+    fn handling_requests() {
+        let mut context = Environment::default();
+        let routes = app_with(&[("GET", "/users/:id", echo), ("POST", "/users", echo), ("GET", "/fail", fail)]);
+
+        let response = handle(&routes, request("GET", "/users/7", "a=1&b=x%20y", ""), &mut context);
+        assert_eq!(response.status, 201);
+        assert!(response.headers.contains(&("Content-Type".to_string(), "application/json".to_string())));
+
+        let body: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+        assert_eq!(body["params"]["id"], "7");
+        assert_eq!(body["query"]["b"], "x y");
+        assert_eq!(body["headers"]["x-token"], "abc");
+
+        let response = handle(&routes, request("POST", "/users", "", "{\"name\":\"a\"}"), &mut context);
+        let body: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+        assert_eq!(body["parsed"]["name"], "a");
+
+        assert_eq!(handle(&routes, request("DELETE", "/users/7", "", ""), &mut context).status, 404);
+        assert_eq!(handle(&routes, request("GET", "/fail", "", ""), &mut context).status, 500);
+    }
+
+    #[test]
+    fn responses() {
+        let response = to_response(Value::Str("hi".to_string())).unwrap();
+        assert_eq!((response.status, response.body.as_str()), (200, "hi"));
+
+        assert_eq!(to_response(Value::Null).unwrap().status, 204);
+
+        let value = Value::map(HashMap::from([
+            ("status".to_string(), Value::Int(302)),
+            ("headers".to_string(), Value::map(HashMap::from([("Location".to_string(), Value::Str("/".to_string()))]))),
+        ]));
+
+        let response = to_response(value).unwrap();
+        assert_eq!(response.status, 302);
+        assert!(response.headers.contains(&("Location".to_string(), "/".to_string())));
+
+        let value = Value::map(HashMap::from([("status".to_string(), Value::Int(42))]));
+        assert!(to_response(value).is_err());
+    }
+}
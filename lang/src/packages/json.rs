@@ -1,310 +1,215 @@
-use std::collections::HashMap;
-
-use crate::ast::AST;
-use crate::eval::eval;
-
-fn insert_functions(properties: &mut HashMap<String, AST>) -> HashMap<String, AST> {
-	properties.insert(
-		"set".to_string(),
-		AST::InternalFunction {
-			name: "set".to_string(),
-			args: vec!["self".to_string(), "key".to_string(), "value".to_string()],
-			call_fn: set,
-		}
-	);
-
-	properties.insert(
-		"get".to_string(),
-		AST::InternalFunction {
-			name: "get".to_string(),
-			args: vec!["self".to_string(), "key".to_string()],
-			call_fn: get,
-		}
-	);
-
-	properties.insert(
-		"has".to_string(),
-		AST::InternalFunction {
-			name: "has".to_string(),
-			args: vec!["self".to_string(), "key".to_string()],
-			call_fn: has,
-		}
-	);
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
-	properties.insert(
-		"delete".to_string(),
-		AST::InternalFunction {
-			name: "delete".to_string(),
-			args: vec!["self".to_string(), "key".to_string()],
-			call_fn: delete,
-		}
-	);
+use crate::value::{NativeFn, Value};
+use crate::environment::Environment;
+use crate::error::ModuError;
 
-	properties.clone()
+pub fn new(_: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+	Ok(Value::map(HashMap::new()))
 }
 
-pub fn new(_: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-	let mut properties = HashMap::new();
-	properties = insert_functions(&mut properties);
-
-    Ok((AST::Object {
-		properties,
-		line: 0,
-	}, AST::Null))
+// any value as json, used by stringify and for http request bodies
+pub fn make_stuff_string(value: &Value) -> Result<String, ModuError> {
+	to_json(value, &mut Vec::new())
 }
 
-fn make_stuff_string(value: AST) -> String {
+// `parents` are the arrays and objects `value` is inside of, json can't hold one that contains itself
+fn to_json(value: &Value, parents: &mut Vec<*const ()>) -> Result<String, ModuError> {
 	match value {
-		AST::String(string) => format!("\"{}\"", string),
+		Value::Str(string) => Ok(serde_json::Value::String(string.clone()).to_string()),
 
-		AST::Object { properties, .. } => {
-			let mut string = "{".to_string();
+		Value::Int(_) | Value::Bool(_) | Value::Null => Ok(value.to_string()),
 
-			for (key, value) in properties {
-				if BUILTINS.contains(&&key[..]) {
-					continue;
-				}
+		// json has no NaN or infinity
+		Value::Float(number) if !number.is_finite() => Ok("null".to_string()),
 
-				string.push_str(&format!("\"{}\":{},", key, make_stuff_string(value)));
+		Value::Float(_) => Ok(value.to_string()),
+
+		Value::List(items) => {
+			let pointer = Rc::as_ptr(items) as *const ();
+
+			if parents.contains(&pointer) {
+				return Err(ModuError::type_error("An array that contains itself can't be turned into JSON"));
 			}
 
-			if string.len() > 1 {
-				string.pop();
+			parents.push(pointer);
+			let items = items.borrow().iter().map(|item| to_json(item, parents)).collect::<Result<Vec<String>, ModuError>>()?;
+			parents.pop();
+
+			Ok(format!("[{}]", items.join(",")))
+		}
+
+		Value::Map(properties) => {
+			let pointer = Rc::as_ptr(properties) as *const ();
+
+			if parents.contains(&pointer) {
+				return Err(ModuError::type_error("An object that contains itself can't be turned into JSON"));
 			}
 
-			string.push_str("}");
+			parents.push(pointer);
+			let properties = properties.borrow().iter()
+				.map(|(key, value)| Ok(format!("{}:{}", serde_json::Value::String(key.clone()), to_json(value, parents)?)))
+				.collect::<Result<Vec<String>, ModuError>>()?;
+			parents.pop();
 
-			string
+			Ok(format!("{{{}}}", properties.join(",")))
 		}
 
-		_ => value.to_string(),
+		Value::Function(_) | Value::NativeFunction { .. } | Value::Module { .. } | Value::Regex(_) => {
+			Err(ModuError::type_error(format!("{} can't be turned into JSON", value)))
+		}
 	}
 }
 
-pub fn stringify(args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
+pub fn stringify(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
 	if args.len() != 1 {
-		return Err("json.stringify requires exactly one argument".to_string());
+		return Err(ModuError::type_error("json.stringify requires exactly one argument"));
 	}
 
-	let value = eval(args[0].clone(), context)?;
-
-	match value {
-		AST::Object { properties, .. } => {
-			let mut string = "{".to_string();
-
-			for (key, value) in properties {
-				if BUILTINS.contains(&&key[..]) {
-					continue;
-				}
-
-				string.push_str(&format!("\"{}\":{},", key, make_stuff_string(value)));
-			}
-
-			if string.len() > 1 {
-				string.pop();
-			}
+	match &args[0] {
+		value @ Value::Map(_) => Ok(Value::Str(make_stuff_string(value)?)),
 
-			string.push_str("}");
-
-			Ok((AST::String(string), AST::Null))
-		},
-
-		_ => Err("json.stringify argument must be an object".to_string()),
+		_ => Err(ModuError::type_error("json.stringify argument must be an object")),
 	}
 }
 
-fn parse_obj(obj: &mut HashMap<String, serde_json::Value>) -> HashMap<String, AST> {
-	let mut map = HashMap::new();
-
-	for (key, value) in obj.iter_mut() {
-		match value {
-			serde_json::Value::String(string) => {
-				map.insert(key.clone(), AST::String(string.clone()));
-			}
+// turns parsed json into modu values, arrays become arrays and objects become objects
+pub fn from_json(value: serde_json::Value) -> Value {
+	match value {
+		serde_json::Value::String(string) => Value::Str(string),
 
-			serde_json::Value::Number(number) => {
-				if number.as_i64().is_none() {
-					map.insert(key.clone(), AST::Float(number.as_f64().unwrap()));
-				} else {
-					map.insert(key.clone(), AST::Number(number.as_i64().unwrap()));
-				}
+		serde_json::Value::Number(number) => {
+			match number.as_i64() {
+				Some(number) => Value::Int(number),
+				None => Value::Float(number.as_f64().unwrap()),
 			}
+		}
 
-			serde_json::Value::Bool(boolean) => {
-				map.insert(key.clone(), AST::Boolean(*boolean));
-			}
+		serde_json::Value::Bool(boolean) => Value::Bool(boolean),
 
-			serde_json::Value::Null => {
-				map.insert(key.clone(), AST::Null);
-			}
+		serde_json::Value::Null => Value::Null,
 
-			serde_json::Value::Object(obj) => {
-				let mut hashmap: HashMap<String, serde_json::Value> = obj
-					.into_iter()
-					.map(|(k, v)| (k.clone(), v.clone()))
-					.collect();
-				
-				let parsed = parse_obj(&mut hashmap);
-
-				map.insert(key.clone(), AST::Object {
-					properties: parsed,
-					line: 0,
-				});
-			}
+		serde_json::Value::Array(items) => {
+			Value::list(items.into_iter().map(from_json).collect())
+		}
 
-			_ => {}
+		serde_json::Value::Object(obj) => {
+			Value::map(obj.into_iter().map(|(key, value)| (key, from_json(value))).collect())
 		}
 	}
-
-	map
 }
 
-pub fn parse(args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
+pub fn parse(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
 	if args.len() != 1 {
-		return Err("json.parse requires exactly one argument".to_string());
+		return Err(ModuError::type_error("json.parse requires exactly one argument"));
 	}
 
-	let value = match &args[0] {
-		AST::String(string) => AST::String(string.clone()),
-
-		_ => match eval(args[0].clone(), context) {
-			Ok(AST::String(string)) => AST::String(string),
-			Ok(_) => return Err("json.parse argument must be a string".to_string()),
-			Err(e) => return Err(e),
-		}
-	};
-
-	match value {
-		AST::String(string) => {			
-			let mut json: HashMap<String, serde_json::Value> = serde_json::from_str(&string).unwrap();
-
-			let mut properties = parse_obj(&mut json);
-			properties = insert_functions(&mut properties);
+	match &args[0] {
+		Value::Str(string) => {
+			let json: serde_json::Value = serde_json::from_str(string)
+				.map_err(|e| ModuError::runtime(format!("json.parse got invalid JSON: {}", e)))?;
 
-			Ok((AST::Object {
-				properties,
-				line: 0,
-			}, AST::Null))
+			Ok(from_json(json))
 		}
 
-		_ => Err("json.parse argument must be a string".to_string()),
+		_ => Err(ModuError::type_error("json.parse argument must be a string")),
 	}
 }
 
 // Self-functions
 
-pub static BUILTINS: [&str; 4] = ["set", "get", "has", "delete"];
-
-pub fn set(args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-	if args.len() != 3 {
-		return Err("json.set requires exactly two arguments".to_string());
+fn key_arg(args: &[Value], name: &str) -> Result<String, ModuError> {
+	match &args[1] {
+		Value::Str(key) => Ok(key.clone()),
+		_ => Err(ModuError::type_error(format!("json.{} second argument must be a string", name))),
 	}
+}
 
-	let key = match eval(args[1].clone(), context) {
-		Ok(AST::String(value)) => value,
-		Ok(_) => return Err("json.set second argument must be a string".to_string()),
-		Err(e) => return Err(e),
-	};
+fn self_properties(args: &[Value]) -> Result<Rc<RefCell<HashMap<String, Value>>>, ModuError> {
+	match &args[0] {
+		Value::Map(properties) => Ok(properties.clone()),
+		_ => Err(ModuError::runtime("uh oh, why is self not an object? this is a bug, please report it")),
+	}
+}
 
-	let value = eval(args[2].clone(), context)?;
+pub fn set(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+	if args.len() != 3 {
+		return Err(ModuError::type_error("json.set requires exactly two arguments"));
+	}
 
-	let mut properties = match &args[0] {
-		AST::Object { properties, .. } => properties.clone(),
-		_ => return Err("uh oh, why is self not an object? this is a bug, please report it".to_string()),
-	};
+	let key = key_arg(&args, "set")?;
+	let properties = self_properties(&args)?;
 
-	properties.insert(key, value);
+	properties.borrow_mut().insert(key, args[2].clone());
 
-	Ok((AST::Null, AST::Object {
-		properties,
-		line: 0,
-	}))
+	Ok(Value::Null)
 }
 
-pub fn get(args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
+pub fn get(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
 	if args.len() != 2 {
-		return Err("json.get requires exactly two arguments".to_string());
+		return Err(ModuError::type_error("json.get requires exactly two arguments"));
 	}
 
-	let key = match eval(args[1].clone(), context) {
-		Ok(AST::String(value)) => value,
-		Ok(_) => return Err("json.get second argument must be a string".to_string()),
-		Err(e) => return Err(e),
-	};
-
-	let properties = match &args[0] {
-		AST::Object { properties, .. } => properties.clone(),
-		_ => return Err("uh oh, why is self not an object? this is a bug, please report it".to_string()),
-	};
+	let key = key_arg(&args, "get")?;
+	let properties = self_properties(&args)?;
 
-	let value = match properties.get(&key) {
+	let value = match properties.borrow().get(&key) {
 		Some(value) => value.clone(),
-		None => AST::Null,
+		None => Value::Null,
 	};
 
-	Ok((value, AST::Object {
-		properties,
-		line: 0,
-	}))
+	Ok(value)
 }
 
-pub fn has(args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
+pub fn has(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
 	if args.len() != 2 {
-		return Err("json.get requires exactly two arguments".to_string());
+		return Err(ModuError::type_error("json.get requires exactly two arguments"));
 	}
 
-	let key = match eval(args[1].clone(), context) {
-		Ok(AST::String(value)) => value,
-		Ok(_) => return Err("json.get second argument must be a string".to_string()),
-		Err(e) => return Err(e),
-	};
-
-	let properties = match &args[0] {
-		AST::Object { properties, .. } => properties.clone(),
-		_ => return Err("uh oh, why is self not an object? this is a bug, please report it".to_string()),
-	};
+	let key = key_arg(&args, "get")?;
+	let properties = self_properties(&args)?;
 
-	let value = match properties.get(&key) {
-		Some(_) => AST::Boolean(true),
-		None => AST::Boolean(false),
-	};
+	let has = properties.borrow().contains_key(&key);
 
-	Ok((value, AST::Object {
-		properties,
-		line: 0,
-	}))
+	Ok(Value::Bool(has))
 }
 
-pub fn delete(args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
+pub fn delete(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
 	if args.len() != 2 {
-		return Err("json.delete requires exactly two arguments".to_string());
+		return Err(ModuError::type_error("json.delete requires exactly two arguments"));
 	}
 
-	let key = match eval(args[1].clone(), context) {
-		Ok(AST::String(value)) => value,
-		Ok(_) => return Err("json.delete second argument must be a string".to_string()),
-		Err(e) => return Err(e),
-	};
+	let key = key_arg(&args, "delete")?;
+	let properties = self_properties(&args)?;
 
-	let mut properties = match &args[0] {
-		AST::Object { properties, .. } => properties.clone(),
-		_ => return Err("uh oh, why is self not an object? this is a bug, please report it".to_string()),
-	};
+	properties.borrow_mut().remove(&key);
 
-	properties.remove(&key);
+	Ok(Value::Null)
+}
+
+// methods every object has, a property with the same name hides them
+pub fn get_method(name: &str) -> Option<Value> {
+	let (args, call_fn): (&[&str], NativeFn) = match name {
+		"set" => (&["self", "key", "value"], set),
+		"get" => (&["self", "key"], get),
+		"has" => (&["self", "key"], has),
+		"delete" => (&["self", "key"], delete),
+		_ => return None,
+	};
 
-	Ok((AST::Null, AST::Object {
-		properties,
-		line: 0,
-	}))
+	Some(Value::NativeFunction {
+		name: name.to_string(),
+		args: args.iter().map(|arg| arg.to_string()).collect(),
+		call_fn,
+	})
 }
 
-pub fn get_object() -> HashMap<String, AST> {
+pub fn get_object() -> HashMap<String, Value> {
 	let mut object = HashMap::new();
 
 	object.insert(
-		"new".to_string(), 
-		AST::InternalFunction {
+		"new".to_string(),
+		Value::NativeFunction {
 			name: "new".to_string(),
 			args: vec![],
 			call_fn: new,
@@ -313,7 +218,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
 	object.insert(
 		"stringify".to_string(),
-		AST::InternalFunction {
+		Value::NativeFunction {
 			name: "stringify".to_string(),
 			args: vec!["object".to_string()],
 			call_fn: stringify,
@@ -322,7 +227,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
 	object.insert(
 		"parse".to_string(),
-		AST::InternalFunction {
+		Value::NativeFunction {
 			name: "parse".to_string(),
 			args: vec!["string".to_string()],
 			call_fn: parse,
@@ -339,24 +244,21 @@ mod tests {
 
 	#[test]
 	fn test_stringify() {
-		let mut context = HashMap::new();
+		let mut context = Environment::default();
 
-		let object = AST::Object {
-			properties: vec![
-				("key".to_string(), AST::String("value".to_string())),
-				("key2".to_string(), AST::Number(1)),
-			].iter().cloned().collect(),
-			line: 0,
-		};
+		let object = Value::map(vec![
+			("key".to_string(), Value::Str("value".to_string())),
+			("key2".to_string(), Value::Int(1)),
+		].iter().cloned().collect());
 
 		let result = stringify(vec![object], &mut context).unwrap();
 
-		match result.0 {
-			AST::String(string) => {
+		match result {
+			Value::Str(string) => {
 				let mut equals = string == "{\"key\":\"value\",\"key2\":1}";
 
 				if !equals {
-					equals = string == "{\"key2\":1,\"key\":\"value\"}";			
+					equals = string == "{\"key2\":1,\"key\":\"value\"}";
 				}
 
 				assert_eq!(equals, true);
@@ -368,15 +270,17 @@ mod tests {
 
 	#[test]
 	fn test_parse() {
-		let mut context = HashMap::new();
+		let mut context = Environment::default();
 
-		let string = AST::String("{\"key\":\"value\",\"key2\":1}".to_string());
+		let string = Value::Str("{\"key\":\"value\",\"key2\":1}".to_string());
 
 		let result = parse(vec![string], &mut context).unwrap();
 
-		match result.0 {
-			AST::Object { properties, .. } => {
-				assert_eq!(properties.len(), 2 + BUILTINS.len());
+		match result {
+			Value::Map(properties) => {
+				let properties = properties.borrow();
+
+				assert_eq!(properties.len(), 2);
 				assert_eq!(properties.contains_key("key"), true);
 				assert_eq!(properties.contains_key("key2"), true);
 			}
@@ -384,4 +288,62 @@ mod tests {
 			_ => panic!("json.parse did not return an object"),
 		}
 	}
-}
\ No newline at end of file
+
+	#[test]
+	fn nested_arrays() {
+		let mut context = Environment::default();
+
+		let string = Value::Str("{\"list\":[1,[2.5,\"a\"],{\"b\":null}]}".to_string());
+
+		let parsed = parse(vec![string], &mut context).unwrap();
+		let result = stringify(vec![parsed], &mut context).unwrap();
+
+		assert_eq!(result, Value::Str("{\"list\":[1,[2.5,\"a\"],{\"b\":null}]}".to_string()));
+	}
+
+	#[test]
+	fn escapes_strings() {
+		let mut context = Environment::default();
+
+		let object = Value::map(HashMap::from([("say \"hi\"".to_string(), Value::Str("a\nb\\".to_string()))]));
+		let string = stringify(vec![object.clone()], &mut context).unwrap();
+
+		assert_eq!(string, Value::Str("{\"say \\\"hi\\\"\":\"a\\nb\\\\\"}".to_string()));
+		assert_eq!(parse(vec![string], &mut context).unwrap(), object);
+	}
+
+	#[test]
+	fn values_without_json() {
+		let mut context = Environment::default();
+
+		let object = Value::map(HashMap::from([("n".to_string(), Value::list(vec![Value::Float(f64::NAN), Value::Float(f64::INFINITY)]))]));
+		let string = stringify(vec![object], &mut context).unwrap();
+
+		assert_eq!(string, Value::Str("{\"n\":[null,null]}".to_string()));
+
+		let object = Value::map(HashMap::from([("f".to_string(), Value::NativeFunction { name: "parse".to_string(), args: vec![], call_fn: parse })]));
+		let result = stringify(vec![object], &mut context);
+
+		assert_eq!(result.map_err(|e| e.to_string()), Err("TypeError: <function parse> can't be turned into JSON".to_string()));
+	}
+
+	#[test]
+	fn contains_itself() {
+		let mut context = Environment::default();
+
+		let object = Value::map(HashMap::new());
+
+		if let Value::Map(properties) = &object {
+			properties.borrow_mut().insert("me".to_string(), object.clone());
+		}
+
+		let result = stringify(vec![object], &mut context);
+		assert_eq!(result.map_err(|e| e.to_string()), Err("TypeError: An object that contains itself can't be turned into JSON".to_string()));
+
+		// the same array twice is fine, it is only inside of itself that it can't be
+		let list = Value::list(vec![Value::Int(1)]);
+		let object = Value::map(HashMap::from([("a".to_string(), list.clone()), ("b".to_string(), list)]));
+
+		assert_eq!(stringify(vec![object], &mut context).map(|s| s.to_string().len()), Ok(17));
+	}
+}
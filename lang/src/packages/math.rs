@@ -1,549 +1,550 @@
 use std::collections::HashMap;
 use rand;
 
-use crate::ast::AST;
-use crate::eval::eval;
+use crate::value::Value;
+use crate::environment::Environment;
+use crate::error::ModuError;
 
-pub fn div(args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match (eval(args[0].clone(), context), eval(args[1].clone(), context)) {
-        (Ok(AST::Number(a)), Ok(AST::Number(b))) => {
+pub fn div(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match (args[0].clone(), args[1].clone()) {
+        (Value::Int(a), Value::Int(b)) => {
             if b == 0 {
-                return Err("cannot divide by zero".to_string());
+                return Err(ModuError::runtime("cannot divide by zero"));
             }
 
             let result = a as f64 / b as f64;
 
             if result.fract() == 0.0 {
-                return Ok((AST::Number(result as i64), AST::Null));
+                return Ok(Value::Int(result as i64));
             }
 
-            return Ok((AST::Float(result), AST::Null));
+            return Ok(Value::Float(result));
         }
 
-        (Ok(AST::Float(a)), Ok(AST::Float(b))) => {
+        (Value::Float(a), Value::Float(b)) => {
             if b == 0.0 {
-                return Err("cannot divide by zero".to_string());
+                return Err(ModuError::runtime("cannot divide by zero"));
             }
 
-            return Ok((AST::Float(a / b), AST::Null));
+            return Ok(Value::Float(a / b));
         }
 
-        (Ok(AST::Float(a)), Ok(AST::Number(b))) => {
+        (Value::Float(a), Value::Int(b)) => {
             if b == 0 {
-                return Err("cannot divide by zero".to_string());
+                return Err(ModuError::runtime("cannot divide by zero"));
             }
 
-            return Ok((AST::Float(a / b as f64), AST::Null));
+            return Ok(Value::Float(a / b as f64));
         }
 
-        (Ok(AST::Number(a)), Ok(AST::Float(b))) => {
+        (Value::Int(a), Value::Float(b)) => {
             if b == 0.0 {
-                return Err("cannot divide by zero".to_string());
+                return Err(ModuError::runtime("cannot divide by zero"));
             }
 
-            return Ok((AST::Float(a as f64 / b), AST::Null));
+            return Ok(Value::Float(a as f64 / b));
         }
 
         _ => {
-            return Err(format!("div requires 2 numbers, got {} and {}", args[0], args[1]));
+            return Err(ModuError::type_error(format!("div requires 2 numbers, got {} and {}", args[0], args[1])));
         }
     }
 }
 
-pub fn mul(args: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match (eval(args[0].clone(), &mut HashMap::new()), eval(args[1].clone(), &mut HashMap::new())) {
-        (Ok(AST::Number(a)), Ok(AST::Number(b))) => {
-            return Ok((AST::Number(a * b), AST::Null));
+pub fn mul(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match (args[0].clone(), args[1].clone()) {
+        (Value::Int(a), Value::Int(b)) => {
+            return Ok(Value::Int(a * b));
         }
 
-        (Ok(AST::Float(a)), Ok(AST::Float(b))) => {
-            return Ok((AST::Float(a * b), AST::Null));
+        (Value::Float(a), Value::Float(b)) => {
+            return Ok(Value::Float(a * b));
         }
 
-        (Ok(AST::Float(a)), Ok(AST::Number(b))) => {
-            return Ok((AST::Float(a * b as f64), AST::Null));
+        (Value::Float(a), Value::Int(b)) => {
+            return Ok(Value::Float(a * b as f64));
         }
 
-        (Ok(AST::Number(a)), Ok(AST::Float(b))) => {
-            return Ok((AST::Float(a as f64 * b), AST::Null));
+        (Value::Int(a), Value::Float(b)) => {
+            return Ok(Value::Float(a as f64 * b));
         }
 
         _ => {
-            return Err("mul requires 2 numbers".to_string());
+            return Err(ModuError::type_error("mul requires 2 numbers"));
         }
     }
 }
 
-pub fn abs(args: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), &mut HashMap::new()) {
-        Ok(AST::Number(a)) => {
-            return Ok((AST::Number(a.abs()), AST::Null));
+pub fn abs(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Int(a) => {
+            return Ok(Value::Int(a.abs()));
         }
 
-        Ok(AST::Float(a)) => {
-            return Ok((AST::Float(a.abs()), AST::Null));
+        Value::Float(a) => {
+            return Ok(Value::Float(a.abs()));
         }
 
         _ => {
-            return Err("abs requires a number".to_string());
+            return Err(ModuError::type_error("abs requires a number"));
         }
     }
 }
 
-pub fn sqrt(args: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), &mut HashMap::new()) {
-        Ok(AST::Number(a)) => {
+pub fn sqrt(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Int(a) => {
             if a < 0 {
-                return Err("sqrt requires a positive number".to_string());
+                return Err(ModuError::runtime("sqrt requires a positive number"));
             }
 
-            return Ok((AST::Float((a as f64).sqrt()), AST::Null));
+            return Ok(Value::Float((a as f64).sqrt()));
         }
 
-        Ok(AST::Float(a)) => {
+        Value::Float(a) => {
             if a < 0.0 {
-                return Err("sqrt requires a positive number".to_string());
+                return Err(ModuError::runtime("sqrt requires a positive number"));
             }
 
-            return Ok((AST::Float(a.sqrt()), AST::Null));
+            return Ok(Value::Float(a.sqrt()));
         }
 
         _ => {
-            return Err("sqrt requires a number".to_string());
+            return Err(ModuError::type_error("sqrt requires a number"));
         }
     }
 }
 
-pub fn pow(args: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), &mut HashMap::new()) {
-        Ok(AST::Number(a)) => {
-            match eval(args[1].clone(), &mut HashMap::new()) {
-                Ok(AST::Number(b)) => {
+pub fn pow(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Int(a) => {
+            match args[1].clone() {
+                Value::Int(b) => {
                     if b < 0 {
-                        return Err("pow requires a positive number".to_string());
+                        return Err(ModuError::runtime("pow requires a positive number"));
                     }
 
-                    return Ok((AST::Number(a.pow(b as u32)), AST::Null));
+                    return Ok(Value::Int(a.pow(b as u32)));
                 }
 
-                Ok(AST::Float(b)) => {
+                Value::Float(b) => {
                     if b < 0.0 {
-                        return Err("pow requires a positive number".to_string());
+                        return Err(ModuError::runtime("pow requires a positive number"));
                     }
 
-                    return Ok((AST::Float((a as f64).powf(b)), AST::Null));
+                    return Ok(Value::Float((a as f64).powf(b)));
                 }
 
                 _ => {
-                    return Err("pow requires a number".to_string());
+                    return Err(ModuError::type_error("pow requires a number"));
                 }
             }
         }
 
-        Ok(AST::Float(a)) => {
-            match eval(args[1].clone(), &mut HashMap::new()) {
-                Ok(AST::Number(b)) => {
-                    return Ok((AST::Float(a.powf(b as f64)), AST::Null));
+        Value::Float(a) => {
+            match args[1].clone() {
+                Value::Int(b) => {
+                    return Ok(Value::Float(a.powf(b as f64)));
                 }
 
-                Ok(AST::Float(b)) => {
-                    return Ok((AST::Float(a.powf(b)), AST::Null));
+                Value::Float(b) => {
+                    return Ok(Value::Float(a.powf(b)));
                 }
 
                 _ => {
-                    return Err("pow requires a number".to_string());
+                    return Err(ModuError::type_error("pow requires a number"));
                 }
             }
         }
 
         _ => {
-            return Err("pow requires a number".to_string());
+            return Err(ModuError::type_error("pow requires a number"));
         }
     }
 }
 
-pub fn floor(args: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), &mut HashMap::new()) {
-        Ok(AST::Number(a)) => {
-            return Ok((AST::Number(a), AST::Null));
+pub fn floor(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Int(a) => {
+            return Ok(Value::Int(a));
         }
 
-        Ok(AST::Float(a)) => {
-            return Ok((AST::Number(a.floor() as i64), AST::Null));
+        Value::Float(a) => {
+            return Ok(Value::Int(a.floor() as i64));
         }
 
         _ => {
-            return Err("floor requires a number".to_string());
+            return Err(ModuError::type_error("floor requires a number"));
         }
     }
 }
 
-pub fn ceil(args: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), &mut HashMap::new()) {
-        Ok(AST::Number(a)) => {
-            return Ok((AST::Number(a), AST::Null));
+pub fn ceil(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Int(a) => {
+            return Ok(Value::Int(a));
         }
 
-        Ok(AST::Float(a)) => {
-            return Ok((AST::Number(a.ceil() as i64), AST::Null));
+        Value::Float(a) => {
+            return Ok(Value::Int(a.ceil() as i64));
         }
 
         _ => {
-            return Err("ceil requires a number".to_string());
+            return Err(ModuError::type_error("ceil requires a number"));
         }
     }
 }
 
-pub fn random(_: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    return Ok((AST::Float(rand::random()), AST::Null));
+pub fn random(_: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    return Ok(Value::Float(rand::random()));
 }
 
-pub fn random_int(_: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    return Ok((AST::Number(rand::random()), AST::Null));
+pub fn random_int(_: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    return Ok(Value::Int(rand::random()));
 }
 
-pub fn cbrt(args: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), &mut HashMap::new()) {
-        Ok(AST::Number(a)) => {
+pub fn cbrt(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Int(a) => {
             if a < 0 {
-                return Err("cbrt requires a positive number".to_string());
+                return Err(ModuError::runtime("cbrt requires a positive number"));
             }
 
-            return Ok((AST::Float((a as f64).cbrt()), AST::Null));
+            return Ok(Value::Float((a as f64).cbrt()));
         }
 
-        Ok(AST::Float(a)) => {
+        Value::Float(a) => {
             if a < 0.0 {
-                return Err("cbrt requires a positive number".to_string());
+                return Err(ModuError::runtime("cbrt requires a positive number"));
             }
 
-            return Ok((AST::Float(a.cbrt()), AST::Null));
+            return Ok(Value::Float(a.cbrt()));
         }
 
         _ => {
-            return Err("cbrt requires a number".to_string());
+            return Err(ModuError::type_error("cbrt requires a number"));
         }
     }
 }
 
-pub fn acos(args: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), &mut HashMap::new()) {
-        Ok(AST::Number(a)) => {
+pub fn acos(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Int(a) => {
             if a < -1 || a > 1 {
-                return Err("acos requires a number in the interval [-1, 1]".to_string());
+                return Err(ModuError::runtime("acos requires a number in the interval [-1, 1]"));
             }
 
-            return Ok((AST::Float((a as f64).acos()), AST::Null));
+            return Ok(Value::Float((a as f64).acos()));
         },
 
-        Ok(AST::Float(a)) => {
+        Value::Float(a) => {
             if a < -1.0 || a > 1.0 {
-                return Err("acos requires a number in the interval [-1, 1]".to_string());
+                return Err(ModuError::runtime("acos requires a number in the interval [-1, 1]"));
             }
 
-            return Ok((AST::Float(a.acos()), AST::Null));
+            return Ok(Value::Float(a.acos()));
         },
 
-        _ => Err("acos requires a number".to_string())
+        _ => Err(ModuError::type_error("acos requires a number"))
     }
 }
 
-pub fn acosh(args: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), &mut HashMap::new()) {
-        Ok(AST::Number(a)) => {
+pub fn acosh(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Int(a) => {
             if a < 1 {
-                return Err("acosh requires a number greater than or equal to 1".to_string());
+                return Err(ModuError::runtime("acosh requires a number greater than or equal to 1"));
             }
 
-            return Ok((AST::Float((a as f64).acosh()), AST::Null));
+            return Ok(Value::Float((a as f64).acosh()));
         },
 
-        Ok(AST::Float(a)) => {
+        Value::Float(a) => {
             if a < 1.0 {
-                return Err("acosh requires a number greater than or equal to 1".to_string());
+                return Err(ModuError::runtime("acosh requires a number greater than or equal to 1"));
             }
 
-            return Ok((AST::Float(a.acosh()), AST::Null));
+            return Ok(Value::Float(a.acosh()));
         },
 
-        _ => Err("acosh requires a number".to_string())
+        _ => Err(ModuError::type_error("acosh requires a number"))
     }
 }
 
-pub fn asin(args: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), &mut HashMap::new()) {
-        Ok(AST::Number(a)) => {
+pub fn asin(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Int(a) => {
             if a < -1 || a > 1 {
-                return Err("asin requires a number in the interval [-1, 1]".to_string());
+                return Err(ModuError::runtime("asin requires a number in the interval [-1, 1]"));
             }
 
-            return Ok((AST::Float((a as f64).asin()), AST::Null));
+            return Ok(Value::Float((a as f64).asin()));
         },
 
-        Ok(AST::Float(a)) => {
+        Value::Float(a) => {
             if a < -1.0 || a > 1.0 {
-                return Err("asin requires a number in the interval [-1, 1]".to_string());
+                return Err(ModuError::runtime("asin requires a number in the interval [-1, 1]"));
             }
 
-            return Ok((AST::Float(a.asin()), AST::Null));
+            return Ok(Value::Float(a.asin()));
         },
 
-        _ => Err("asin requires a number".to_string())
+        _ => Err(ModuError::type_error("asin requires a number"))
     }
 }
 
-pub fn asinh(args: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), &mut HashMap::new()) {
-        Ok(AST::Number(a)) => Ok((AST::Float((a as f64).asinh()), AST::Null)),
-        Ok(AST::Float(a)) => Ok((AST::Float(a.asinh()), AST::Null)),
+pub fn asinh(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Int(a) => Ok(Value::Float((a as f64).asinh())),
+        Value::Float(a) => Ok(Value::Float(a.asinh())),
 
-        _ => Err("asinh requires a number".to_string())
+        _ => Err(ModuError::type_error("asinh requires a number"))
     }
 }
 
-pub fn atan(args: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), &mut HashMap::new()) {
-        Ok(AST::Number(a)) => Ok((AST::Float((a as f64).atan()), AST::Null)),
-        Ok(AST::Float(a)) => Ok((AST::Float(a.atan()), AST::Null)),
+pub fn atan(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Int(a) => Ok(Value::Float((a as f64).atan())),
+        Value::Float(a) => Ok(Value::Float(a.atan())),
 
-        _ => Err("atan requires a number".to_string())
+        _ => Err(ModuError::type_error("atan requires a number"))
     }
 }
 
-pub fn atanh(args: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), &mut HashMap::new()) {
-        Ok(AST::Number(a)) => {
+pub fn atanh(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Int(a) => {
             if a < -1 || a > 1 {
-                return Err("atanh requires a number in the interval [-1, 1]".to_string());
+                return Err(ModuError::runtime("atanh requires a number in the interval [-1, 1]"));
             }
 
-            return Ok((AST::Float((a as f64).atanh()), AST::Null));
+            return Ok(Value::Float((a as f64).atanh()));
         },
 
-        Ok(AST::Float(a)) => {
+        Value::Float(a) => {
             if a < -1.0 || a > 1.0 {
-                return Err("atanh requires a number in the interval [-1, 1]".to_string());
+                return Err(ModuError::runtime("atanh requires a number in the interval [-1, 1]"));
             }
 
-            return Ok((AST::Float(a.atanh()), AST::Null));
+            return Ok(Value::Float(a.atanh()));
         },
 
-        _ => Err("atanh requires a number".to_string())
+        _ => Err(ModuError::type_error("atanh requires a number"))
     }
 }
 
-pub fn cos(args: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), &mut HashMap::new()) {
-        Ok(AST::Number(a)) => {
+pub fn cos(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Int(a) => {
             if a < -1 || a > 1 {
-                return Err("cos requires a number in the interval [-1, 1]".to_string());
+                return Err(ModuError::runtime("cos requires a number in the interval [-1, 1]"));
             }
 
-            return Ok((AST::Float((a as f64).cos()), AST::Null));
+            return Ok(Value::Float((a as f64).cos()));
         },
 
-        Ok(AST::Float(a)) => {
+        Value::Float(a) => {
             if a < -1.0 || a > 1.0 {
-                return Err("cos requires a number in the interval [-1, 1]".to_string());
+                return Err(ModuError::runtime("cos requires a number in the interval [-1, 1]"));
             }
 
-            return Ok((AST::Float(a.cos()), AST::Null));
+            return Ok(Value::Float(a.cos()));
         },
 
-        _ => Err("cos requires a number".to_string())
+        _ => Err(ModuError::type_error("cos requires a number"))
     }
 }
 
-pub fn cosh(args: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), &mut HashMap::new()) {
-        Ok(AST::Number(a)) => Ok((AST::Float((a as f64).cosh()), AST::Null)),
-        Ok(AST::Float(a)) => Ok((AST::Float(a.cosh()), AST::Null)),
+pub fn cosh(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Int(a) => Ok(Value::Float((a as f64).cosh())),
+        Value::Float(a) => Ok(Value::Float(a.cosh())),
 
-        _ => Err("cosh requires a number".to_string())
+        _ => Err(ModuError::type_error("cosh requires a number"))
     }
 }
 
-pub fn exp(args: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), &mut HashMap::new()) {
-        Ok(AST::Number(a)) => Ok((AST::Float((a as f64).exp()), AST::Null)),
-        Ok(AST::Float(a)) => Ok((AST::Float(a.exp()), AST::Null)),
+pub fn exp(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Int(a) => Ok(Value::Float((a as f64).exp())),
+        Value::Float(a) => Ok(Value::Float(a.exp())),
 
-        _ => Err("exp requires a number".to_string())
+        _ => Err(ModuError::type_error("exp requires a number"))
     }
 }
 
-pub fn exp2(args: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), &mut HashMap::new()) {
-        Ok(AST::Number(a)) => Ok((AST::Float((a as f64).exp2()), AST::Null)),
-        Ok(AST::Float(a)) => Ok((AST::Float(a.exp2()), AST::Null)),
+pub fn exp2(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Int(a) => Ok(Value::Float((a as f64).exp2())),
+        Value::Float(a) => Ok(Value::Float(a.exp2())),
 
-        _ => Err("exp2 requires a number".to_string())
+        _ => Err(ModuError::type_error("exp2 requires a number"))
     }
 }
 
-pub fn expm1(args: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), &mut HashMap::new()) {
-        Ok(AST::Number(a)) => Ok((AST::Float((a as f64).exp_m1()), AST::Null)),
-        Ok(AST::Float(a)) => Ok((AST::Float(a.exp_m1()), AST::Null)),
+pub fn expm1(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Int(a) => Ok(Value::Float((a as f64).exp_m1())),
+        Value::Float(a) => Ok(Value::Float(a.exp_m1())),
 
-        _ => Err("expm1 requires a number".to_string())
+        _ => Err(ModuError::type_error("expm1 requires a number"))
     }
 }
 
-pub fn fract(args: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), &mut HashMap::new()) {
-        Ok(AST::Number(a)) => Ok((AST::Float((a as f64).fract()), AST::Null)),
-        Ok(AST::Float(a)) => Ok((AST::Float(a.fract()), AST::Null)),
+pub fn fract(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Int(a) => Ok(Value::Float((a as f64).fract())),
+        Value::Float(a) => Ok(Value::Float(a.fract())),
 
-        _ => Err("fract requires a number".to_string())
+        _ => Err(ModuError::type_error("fract requires a number"))
     }
 }
 
-pub fn ln(args: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), &mut HashMap::new()) {
-        Ok(AST::Number(a)) => {
+pub fn ln(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Int(a) => {
             if a <= 0 {
-                return Err("ln requires a number greater than 0".to_string());
+                return Err(ModuError::runtime("ln requires a number greater than 0"));
             }
 
-            return Ok((AST::Float((a as f64).ln()), AST::Null));
+            return Ok(Value::Float((a as f64).ln()));
         },
 
-        Ok(AST::Float(a)) => {
+        Value::Float(a) => {
             if a <= 0.0 {
-                return Err("ln requires a number greater than 0".to_string());
+                return Err(ModuError::runtime("ln requires a number greater than 0"));
             }
 
-            return Ok((AST::Float(a.ln()), AST::Null));
+            return Ok(Value::Float(a.ln()));
         },
 
-        _ => Err("ln requires a number".to_string())
+        _ => Err(ModuError::type_error("ln requires a number"))
     }
 }
 
-pub fn ln1p(args: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), &mut HashMap::new()) {
-        Ok(AST::Number(a)) => {
+pub fn ln1p(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Int(a) => {
             if a <= -1 {
-                return Err("ln1p requires a number greater than -1".to_string());
+                return Err(ModuError::runtime("ln1p requires a number greater than -1"));
             }
 
-            return Ok((AST::Float((a as f64).ln_1p()), AST::Null));
+            return Ok(Value::Float((a as f64).ln_1p()));
         }
 
-        Ok(AST::Float(a)) => {
+        Value::Float(a) => {
             if a <= -1.0 {
-                return Err("ln1p requires a number greater than -1".to_string());
+                return Err(ModuError::runtime("ln1p requires a number greater than -1"));
             }
 
-            return Ok((AST::Float(a.ln_1p()), AST::Null));
+            return Ok(Value::Float(a.ln_1p()));
         }
 
-        _ => Err("ln1p requires a number".to_string())
+        _ => Err(ModuError::type_error("ln1p requires a number"))
     }
 }
 
-pub fn log10(args: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), &mut HashMap::new()) {
-        Ok(AST::Number(a)) => {
+pub fn log10(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Int(a) => {
             if a <= 0 {
-                return Err("log10 requires a number greater than 0".to_string());
+                return Err(ModuError::runtime("log10 requires a number greater than 0"));
             }
 
-            return Ok((AST::Float((a as f64).log10()), AST::Null));
+            return Ok(Value::Float((a as f64).log10()));
         },
 
-        Ok(AST::Float(a)) => {
+        Value::Float(a) => {
             if a <= 0.0 {
-                return Err("log10 requires a number greater than 0".to_string());
+                return Err(ModuError::runtime("log10 requires a number greater than 0"));
             }
 
-            return Ok((AST::Float(a.log10()), AST::Null));
+            return Ok(Value::Float(a.log10()));
         },
 
-        _ => Err("log10 requires a number".to_string())
+        _ => Err(ModuError::type_error("log10 requires a number"))
     }
 }
 
-pub fn log2(args: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), &mut HashMap::new()) {
-        Ok(AST::Number(a)) => {
+pub fn log2(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Int(a) => {
             if a <= 0 {
-                return Err("log2 requires a number greater than 0".to_string());
+                return Err(ModuError::runtime("log2 requires a number greater than 0"));
             }
 
-            return Ok((AST::Float((a as f64).log2()), AST::Null));
+            return Ok(Value::Float((a as f64).log2()));
         },
 
-        Ok(AST::Float(a)) => {
+        Value::Float(a) => {
             if a <= 0.0 {
-                return Err("log2 requires a number greater than 0".to_string());
+                return Err(ModuError::runtime("log2 requires a number greater than 0"));
             }
 
-            return Ok((AST::Float(a.log2()), AST::Null));
+            return Ok(Value::Float(a.log2()));
         },
 
-        _ => Err("log2 requires a number".to_string())
+        _ => Err(ModuError::type_error("log2 requires a number"))
     }
 }
 
-pub fn sin(args: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), &mut HashMap::new()) {
-        Ok(AST::Number(a)) => Ok((AST::Float((a as f64).sin()), AST::Null)),
-        Ok(AST::Float(a)) => Ok((AST::Float(a.sin()), AST::Null)),
+pub fn sin(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Int(a) => Ok(Value::Float((a as f64).sin())),
+        Value::Float(a) => Ok(Value::Float(a.sin())),
 
-        _ => Err("sin requires a number".to_string())
+        _ => Err(ModuError::type_error("sin requires a number"))
     }
 }
 
-pub fn sinh(args: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), &mut HashMap::new()) {
-        Ok(AST::Number(a)) => Ok((AST::Float((a as f64).sinh()), AST::Null)),
-        Ok(AST::Float(a)) => Ok((AST::Float(a.sinh()), AST::Null)),
+pub fn sinh(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Int(a) => Ok(Value::Float((a as f64).sinh())),
+        Value::Float(a) => Ok(Value::Float(a.sinh())),
 
-        _ => Err("sinh requires a number".to_string())
+        _ => Err(ModuError::type_error("sinh requires a number"))
     }
 }
 
-pub fn tan(args: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), &mut HashMap::new()) {
-        Ok(AST::Number(a)) => Ok((AST::Float((a as f64).tan()), AST::Null)),
-        Ok(AST::Float(a)) => {
+pub fn tan(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Int(a) => Ok(Value::Float((a as f64).tan())),
+        Value::Float(a) => {
             let b = a.tan();
             if b.is_nan() {
-                return Err("tan requires a real number that is not an odd multiple of pi/2".to_string());
+                return Err(ModuError::runtime("tan requires a real number that is not an odd multiple of pi/2"));
             }
-            Ok((AST::Float(b), AST::Null))
+            Ok(Value::Float(b))
         },
 
-        _ => Err("tan requires a number".to_string())
+        _ => Err(ModuError::type_error("tan requires a number"))
     }
 }
 
-pub fn tanh(args: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), &mut HashMap::new()) {
-        Ok(AST::Number(a)) => Ok((AST::Float((a as f64).tanh()), AST::Null)),
-        Ok(AST::Float(a)) => Ok((AST::Float(a.tanh()), AST::Null)),
+pub fn tanh(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Int(a) => Ok(Value::Float((a as f64).tanh())),
+        Value::Float(a) => Ok(Value::Float(a.tanh())),
 
-        _ => Err("tanh requires a number".to_string())
+        _ => Err(ModuError::type_error("tanh requires a number"))
     }
 }
 
-pub fn trunc(args: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    match eval(args[0].clone(), &mut HashMap::new()) {
-        Ok(AST::Number(a)) => Ok((AST::Float((a as f64).trunc()), AST::Null)),
-        Ok(AST::Float(a)) => Ok((AST::Float(a.trunc()), AST::Null)),
+pub fn trunc(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    match args[0].clone() {
+        Value::Int(a) => Ok(Value::Float((a as f64).trunc())),
+        Value::Float(a) => Ok(Value::Float(a.trunc())),
 
-        _ => Err("trunc requires a number".to_string())
+        _ => Err(ModuError::type_error("trunc requires a number"))
     }
 }
 
-pub fn get_object() -> HashMap<String, AST> {
+pub fn get_object() -> HashMap<String, Value> {
     let mut objects = HashMap::new();
 
     objects.insert(
         "div".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "div".to_string(),
             args: vec!["a".to_string(), "b".to_string()],
             call_fn: div,
@@ -552,7 +553,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "mul".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "mul".to_string(),
             args: vec!["a".to_string(), "b".to_string()],
             call_fn: mul,
@@ -561,7 +562,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "abs".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "abs".to_string(),
             args: vec!["a".to_string()],
             call_fn: abs,
@@ -570,7 +571,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "sqrt".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "sqrt".to_string(),
             args: vec!["a".to_string()],
             call_fn: sqrt,
@@ -579,7 +580,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "pow".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "pow".to_string(),
             args: vec!["a".to_string(), "b".to_string()],
             call_fn: pow,
@@ -588,7 +589,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "floor".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "floor".to_string(),
             args: vec!["a".to_string()],
             call_fn: floor,
@@ -597,7 +598,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "ceil".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "ceil".to_string(),
             args: vec!["a".to_string()],
             call_fn: ceil,
@@ -606,7 +607,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "random".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "random".to_string(),
             args: vec![],
             call_fn: random,
@@ -615,7 +616,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "random_int".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "random_int".to_string(),
             args: vec![],
             call_fn: random_int,
@@ -624,7 +625,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "cbrt".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "cbrt".to_string(),
             args: vec!["a".to_string()],
             call_fn: cbrt,
@@ -633,7 +634,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "acos".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "acos".to_string(),
             args: vec!["a".to_string()],
             call_fn: acos,
@@ -642,7 +643,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "acosh".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "acosh".to_string(),
             args: vec!["a".to_string()],
             call_fn: acosh,
@@ -651,7 +652,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "asin".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "asin".to_string(),
             args: vec!["a".to_string()],
             call_fn: asin,
@@ -660,7 +661,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "asinh".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "asinh".to_string(),
             args: vec!["a".to_string()],
             call_fn: asinh,
@@ -669,7 +670,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "atan".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "atan".to_string(),
             args: vec!["a".to_string()],
             call_fn: atan,
@@ -678,7 +679,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "atanh".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "atanh".to_string(),
             args: vec!["a".to_string()],
             call_fn: atanh,
@@ -687,7 +688,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "cos".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "cos".to_string(),
             args: vec!["a".to_string()],
             call_fn: cos,
@@ -696,7 +697,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "cosh".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "cosh".to_string(),
             args: vec!["a".to_string()],
             call_fn: cosh,
@@ -705,7 +706,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "exp".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "exp".to_string(),
             args: vec!["a".to_string()],
             call_fn: exp,
@@ -714,7 +715,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "exp2".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "exp2".to_string(),
             args: vec!["a".to_string()],
             call_fn: exp2,
@@ -723,7 +724,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "expm1".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "expm1".to_string(),
             args: vec!["a".to_string()],
             call_fn: expm1,
@@ -732,7 +733,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "fract".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "fract".to_string(),
             args: vec!["a".to_string()],
             call_fn: fract,
@@ -741,7 +742,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "ln".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "ln".to_string(),
             args: vec!["a".to_string()],
             call_fn: ln,
@@ -750,7 +751,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "ln1p".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "ln1p".to_string(),
             args: vec!["a".to_string()],
             call_fn: ln1p,
@@ -759,7 +760,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "log10".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "log10".to_string(),
             args: vec!["a".to_string()],
             call_fn: log10,
@@ -768,7 +769,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "log2".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "log2".to_string(),
             args: vec!["a".to_string()],
             call_fn: log2,
@@ -777,7 +778,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "sin".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "sin".to_string(),
             args: vec!["a".to_string()],
             call_fn: sin,
@@ -786,7 +787,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "sinh".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "sinh".to_string(),
             args: vec!["a".to_string()],
             call_fn: sinh,
@@ -795,7 +796,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "tan".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "tan".to_string(),
             args: vec!["a".to_string()],
             call_fn: tan,
@@ -804,7 +805,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "tanh".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "tanh".to_string(),
             args: vec!["a".to_string()],
             call_fn: tanh,
@@ -813,7 +814,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "trunc".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "trunc".to_string(),
             args: vec!["a".to_string()],
             call_fn: trunc,
@@ -822,167 +823,167 @@ pub fn get_object() -> HashMap<String, AST> {
 
     objects.insert(
         "CBRT_2".to_string(),
-        AST::Float(1.25992104989487316476721060727822835_f64)
+        Value::Float(1.25992104989487316476721060727822835_f64)
     );
 
     objects.insert(
         "CBRT_3".to_string(),
-        AST::Float(1.44224957030740838232163831078010958_f64)
+        Value::Float(1.44224957030740838232163831078010958_f64)
     );
 
     objects.insert(
         "E".to_string(),
-        AST::Float(2.71828182845904523536028747135266250_f64)
+        Value::Float(2.71828182845904523536028747135266250_f64)
     );
 
     objects.insert(
         "EGAMMA".to_string(),
-        AST::Float(0.57721566490153286060651209008240243_f64)
+        Value::Float(0.57721566490153286060651209008240243_f64)
     );
 
     objects.insert(
         "FRAC_1_PI".to_string(),
-        AST::Float(0.31830988618379067153776752674502872_f64)
+        Value::Float(0.31830988618379067153776752674502872_f64)
     );
 
     objects.insert(
         "FRAC_1_SQRT_2".to_string(),
-        AST::Float(0.70710678118654752440084436210484903_f64)
+        Value::Float(0.70710678118654752440084436210484903_f64)
     );
 
     objects.insert(
         "FRAC_1_SQRT_2PI".to_string(),
-        AST::Float(0.39894228040143267793994605993438186_f64)
+        Value::Float(0.39894228040143267793994605993438186_f64)
     );
 
     objects.insert(
         "FRAC_1_SQRT_3".to_string(),
-        AST::Float(0.57735026918962576450914878050195745_f64)
+        Value::Float(0.57735026918962576450914878050195745_f64)
     );
 
     objects.insert(
         "FRAC_1_SQRT_PI".to_string(),
-        AST::Float(0.56418958354775628694807945156077258_f64)
+        Value::Float(0.56418958354775628694807945156077258_f64)
     );
 
     objects.insert(
         "FRAC_2_PI".to_string(),
-        AST::Float(0.63661977236758134307553505349005744_f64)
+        Value::Float(0.63661977236758134307553505349005744_f64)
     );
 
     objects.insert(
         "FRAC_2_SQRT_PI".to_string(),
-        AST::Float(1.12837916709551257389615890312154517_f64)
+        Value::Float(1.12837916709551257389615890312154517_f64)
     );
 
     objects.insert(
         "FRAC_PI_2".to_string(),
-        AST::Float(1.57079632679489661923132169163975144_f64)
+        Value::Float(1.57079632679489661923132169163975144_f64)
     );
 
     objects.insert(
         "FRAC_PI_3".to_string(),
-        AST::Float(1.04719755119659774615421446109316763_f64)
+        Value::Float(1.04719755119659774615421446109316763_f64)
     );
 
     objects.insert(
         "FRAC_PI_4".to_string(),
-        AST::Float(0.78539816339744830961566084581987572_f64)
+        Value::Float(0.78539816339744830961566084581987572_f64)
     );
 
     objects.insert(
         "FRAC_PI_5".to_string(),
-        AST::Float(0.62831853071795864769252867665590057_f64)
+        Value::Float(0.62831853071795864769252867665590057_f64)
     );
 
     objects.insert(
         "FRAC_PI_6".to_string(),
-        AST::Float(0.52359877559829887307710723054658381_f64)
+        Value::Float(0.52359877559829887307710723054658381_f64)
     );
 
     objects.insert(
         "FRAC_PI_7".to_string(),
-        AST::Float(0.44879895051282760549466334046850041_f64)
+        Value::Float(0.44879895051282760549466334046850041_f64)
     );
 
     objects.insert(
         "FRAC_PI_8".to_string(),
-        AST::Float(0.39269908169872415480783042290993786_f64)
+        Value::Float(0.39269908169872415480783042290993786_f64)
     );
 
     objects.insert(
         "LN_2".to_string(),
-        AST::Float(0.69314718055994530941723212145817656_f64)
+        Value::Float(0.69314718055994530941723212145817656_f64)
     );
 
     objects.insert(
         "LN_10".to_string(),
-        AST::Float(2.30258509299404568401799145468436421_f64)
+        Value::Float(2.30258509299404568401799145468436421_f64)
     );
 
     objects.insert(
         "LOG2_10".to_string(),
-        AST::Float(3.32192809488736234787031942948939018_f64)
+        Value::Float(3.32192809488736234787031942948939018_f64)
     );
 
     objects.insert(
         "LOG2_E".to_string(),
-        AST::Float(1.44269504088896340735992468100189214_f64)
+        Value::Float(1.44269504088896340735992468100189214_f64)
     );
 
     objects.insert(
         "LOG10_2".to_string(),
-        AST::Float(0.30102999566398119521373889472449302_f64)
+        Value::Float(0.30102999566398119521373889472449302_f64)
     );
 
     objects.insert(
         "LOG10_E".to_string(),
-        AST::Float(0.43429448190325182765112891891660508_f64)
+        Value::Float(0.43429448190325182765112891891660508_f64)
     );
 
     objects.insert(
         "PHI".to_string(),
-        AST::Float(1.61803398874989484820458683436563811_f64)
+        Value::Float(1.61803398874989484820458683436563811_f64)
     );
 
     objects.insert(
         "PI".to_string(),
-        AST::Float(3.14159265358979323846264338327950288_f64)
+        Value::Float(3.14159265358979323846264338327950288_f64)
     );
 
     objects.insert(
         "RT12_2".to_string(),
-        AST::Float(1.05946309435929526456182529494634170_f64)
+        Value::Float(1.05946309435929526456182529494634170_f64)
     );
 
     objects.insert(
         "SILVER".to_string(),
-        AST::Float(2.41421356237309504880168872420969808_f64)
+        Value::Float(2.41421356237309504880168872420969808_f64)
     );
 
     objects.insert(
         "SUPERPHI".to_string(),
-        AST::Float(1.46557123187676802665673122521993910_f64)
+        Value::Float(1.46557123187676802665673122521993910_f64)
     );
 
     objects.insert(
         "SQRT_2".to_string(),
-        AST::Float(1.41421356237309504880168872420969808_f64)
+        Value::Float(1.41421356237309504880168872420969808_f64)
     );
 
     objects.insert(
         "SQRT_3".to_string(),
-        AST::Float(1.73205080756887729352744634150587236_f64)
+        Value::Float(1.73205080756887729352744634150587236_f64)
     );
 
     objects.insert(
         "SQRT_5".to_string(),
-        AST::Float(2.23606797749978969640917366873127623_f64)
+        Value::Float(2.23606797749978969640917366873127623_f64)
     );
 
     objects.insert(
         "TAU".to_string(),
-        AST::Float(6.28318530717958647692528676655900577_f64)
+        Value::Float(6.28318530717958647692528676655900577_f64)
     );
 
     objects
@@ -1002,84 +1003,84 @@ mod tests {
 
     #[test]
     fn div_test() {
-        let mut context = HashMap::new();
-        let args = vec![AST::Number(10), AST::Number(2)];
-        let result = div(args, &mut context).unwrap().0;
+        let mut context = Environment::default();
+        let args = vec![Value::Int(10), Value::Int(2)];
+        let result = div(args, &mut context).unwrap();
 
         match result {
-            AST::Number(a) => {
+            Value::Int(a) => {
                 assert_eq!(a, 5);
             }
-            _ => panic!("Expected AST::Number")
+            _ => panic!("Expected Value::Int")
         }
     }
 
     #[test]
     fn abs_test() {
-        let mut context = HashMap::new();
-        let args = vec![AST::Number(-10)];
+        let mut context = Environment::default();
+        let args = vec![Value::Int(-10)];
 
-        match abs(args, &mut context).unwrap().0 {
-            AST::Number(a) => {
+        match abs(args, &mut context).unwrap() {
+            Value::Int(a) => {
                 assert_eq!(a, 10);
             }
-            _ => panic!("Expected AST::Number")
+            _ => panic!("Expected Value::Int")
         }
     }
 
     #[test]
     fn sqrt_test() {
-        let mut context = HashMap::new();
-        let args = vec![AST::Number(9)];
+        let mut context = Environment::default();
+        let args = vec![Value::Int(9)];
 
-        match sqrt(args, &mut context).unwrap().0 {
-            AST::Float(a) => {
+        match sqrt(args, &mut context).unwrap() {
+            Value::Float(a) => {
                 assert_eq!(a, 3.0);
             }
 
-            _ => panic!("Expected AST::Float")
+            _ => panic!("Expected Value::Float")
         }
     }
 
     #[test]
     fn pow_test() {
-        let mut context = HashMap::new();
-        let args = vec![AST::Number(2), AST::Number(3)];
+        let mut context = Environment::default();
+        let args = vec![Value::Int(2), Value::Int(3)];
 
-        match pow(args, &mut context).unwrap().0 {
-            AST::Number(a) => {
+        match pow(args, &mut context).unwrap() {
+            Value::Int(a) => {
                 assert_eq!(a, 8);
             }
 
-            _ => panic!("Expected AST::Number")
+            _ => panic!("Expected Value::Int")
         }
     }
 
     #[test]
     fn floor_test() {
-        let mut context = HashMap::new();
-        let args = vec![AST::Float(3.14)];
+        let mut context = Environment::default();
+        let args = vec![Value::Float(3.14)];
 
-        match floor(args, &mut context).unwrap().0 {
-            AST::Number(a) => {
+        match floor(args, &mut context).unwrap() {
+            Value::Int(a) => {
                 assert_eq!(a, 3);
             }
 
-            _ => panic!("Expected AST::Number")
+            _ => panic!("Expected Value::Int")
         }
     }
 
     #[test]
     fn ceil_test() {
-        let mut context = HashMap::new();
-        let args = vec![AST::Float(3.14)];
+        let mut context = Environment::default();
+        let args = vec![Value::Float(3.14)];
 
-        match ceil(args, &mut context).unwrap().0 {
-            AST::Number(a) => {
+        match ceil(args, &mut context).unwrap() {
+            Value::Int(a) => {
                 assert_eq!(a, 4);
             }
 
-            _ => panic!("Expected AST::Number")
+            _ => panic!("Expected Value::Int")
         }
     }
 
@@ -1088,22 +1089,22 @@ mod tests {
         let object = get_object();
 
         match object.get("PI").unwrap() {
-            AST::Float(a) => {
+            Value::Float(a) => {
                 assert_eq!(a, &std::f64::consts::PI);
             }
 
-            _ => panic!("Expected AST::Float")
+            _ => panic!("Expected Value::Float")
         }
     }
 
     #[test]
     fn div_by_zero() {
-        let mut context = HashMap::new();
-        let args = vec![AST::Number(10), AST::Number(0)];
+        let mut context = Environment::default();
+        let args = vec![Value::Int(10), Value::Int(0)];
 
         match div(args, &mut context) {
             Err(e) => {
-                assert_eq!(e, "cannot divide by zero");
+                assert_eq!(e.to_string(), "RuntimeError: cannot divide by zero");
             }
 
             _ => panic!("Expected Err")
@@ -6,23 +6,31 @@ mod ffi;
 pub mod json;
 pub mod array;
 mod uuid;
+mod string;
+pub mod regex;
+pub mod http;
+mod httpserver;
+mod path;
 
-use crate::ast::AST;
+use std::{collections::HashMap, rc::Rc};
 
-pub fn get_package(name: &str) -> Option<AST> {
+use crate::value::Value;
+
+fn module(name: &str, members: HashMap<String, Value>) -> Value {
+	Value::Module {
+		name: name.to_string(),
+		members: Rc::new(members),
+	}
+}
+
+pub fn get_package(name: &str) -> Option<Value> {
 	match name {
 		"math" => {
-			Some(AST::Object {
-				properties: math::get_object(),
-				line: 0,
-			})
+			Some(module("math", math::get_object()))
 		}
 
 		"time" => {
-			Some(AST::Object {
-				properties: time::get_object(),
-				line: 0,
-			})
+			Some(module("time", time::get_object()))
 		}
 
 		"file" => {
@@ -32,36 +40,28 @@ pub fn get_package(name: &str) -> Option<AST> {
 				return None;
 			}
 
-			Some(AST::Object {
-				properties: file::get_object(),
-				line: 0,
-			})
+			Some(module("file", file::get_object()))
 		}
 
-		"os" => Some(AST::Object {
-			properties: os::get_object(),
-			line: 0
-		}),
+		"os" => Some(module("os", os::get_object())),
+
+		"ffi" => Some(module("ffi", ffi::get_object())),
+
+		"json" => Some(module("json", json::get_object())),
+
+		"array" => Some(module("array", array::get_object())),
+
+		"uuid" => Some(module("uuid", uuid::get_object())),
+
+		"string" => Some(module("string", string::get_object())),
 
-		"ffi" => Some(AST::Object {
-			properties: ffi::get_object(),
-			line: 0
-		}),
+		"regex" => Some(module("regex", regex::get_object())),
 
-		"json" => Some(AST::Object {
-			properties: json::get_object(),
-			line: 0
-		}),
+		"http" => Some(module("http", http::get_object())),
 
-		"array" => Some(AST::Object {
-			properties: array::get_object(),
-			line: 0
-		}),
+		"httpserver" => Some(module("httpserver", httpserver::get_object())),
 
-		"uuid" => Some(AST::Object {
-			properties: uuid::get_object(),
-			line: 0
-		}),
+		"path" => Some(module("path", path::get_object())),
 
 		_ => None
 	}
@@ -75,11 +75,11 @@ mod tests {
 	fn get_math_package() {
 		let math = get_package("math").unwrap();
 		match math {
-			AST::Object { properties, line: _ } => {
+			Value::Module { members: properties, .. } => {
 				assert_eq!(properties.len(), 64);
 				assert_eq!(properties.contains_key("div"), true);
 			}
-			_ => panic!("Expected AST::Object")
+			_ => panic!("Expected a module")
 		}
 	}
 
@@ -88,12 +88,12 @@ mod tests {
 		let file = get_package("file").unwrap();
 
 		match file {
-			AST::Object { properties, line: _ } => {
-				assert_eq!(properties.len(), 3);
+			Value::Module { members: properties, .. } => {
+				assert_eq!(properties.len(), 16);
 				assert_eq!(properties.contains_key("write_append"), true);
 			}
 
-			_ => panic!("Expected AST::Object")
+			_ => panic!("Expected a module")
 		}
 	}
 
@@ -107,12 +107,12 @@ mod tests {
 	fn get_os_package() {
 		let os = get_package("os").unwrap();
 		match os {
-			AST::Object { properties, line: _ } => {
+			Value::Module { members: properties, .. } => {
 				assert_eq!(properties.len(), 2);
 				assert_eq!(properties.contains_key("exec"), true);
 				assert_eq!(properties.contains_key("name"), true);
 			}
-			_ => panic!("Expected AST::Object")
+			_ => panic!("Expected a module")
 		}
 	}
 
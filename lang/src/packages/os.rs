@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 use std::process::Command;
-use crate::ast::AST;
-use crate::eval::eval;
+use crate::value::Value;
+use crate::environment::Environment;
+use crate::error::ModuError;
 
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
@@ -15,17 +16,15 @@ fn clean_command(cmd: String) -> String {
 	return clean;
 }
 
-pub fn exec(args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
+pub fn exec(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
 	if args.len() != 1 {
-		return Err("os.exec requires exactly one argument".to_string());
+		return Err(ModuError::type_error("os.exec requires exactly one argument"));
 	}
 
-	let command = match eval(args[0].clone(), context) {
-		Ok(AST::String(value))=> value,
+	let command = match args[0].clone() {
+		Value::Str(value)=> value,
 
-		Ok(_) => return Err("os.exec argument must be a string".to_string()),
-
-		Err(e) => return Err(e),
+		_ => return Err(ModuError::type_error("os.exec argument must be a string")),
 	};
 
 	let cleaned = clean_command(command);
@@ -50,22 +49,22 @@ pub fn exec(args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(AST,
 		Ok(output) => {
 			let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
 			if output.status.success() {
-				Ok((AST::String(stdout), AST::Null))
+				Ok(Value::Str(stdout))
 			} else {
 				let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-				Err(stderr)
+				Err(ModuError::runtime(stderr))
 			}
 		},
-		Err(e) => Err(format!("Command execution failed: {}", e))
+		Err(e) => Err(ModuError::runtime(format!("Command execution failed: {}", e)))
 	}
 }
 
-pub fn get_object() -> HashMap<String, AST> {
+pub fn get_object() -> HashMap<String, Value> {
 	let mut object = HashMap::new();
 
 	object.insert(
 		"exec".to_string(),
-		AST::InternalFunction {
+		Value::NativeFunction {
 			name: "exec".to_string(),
 			args: vec!["command".to_string()],
 			call_fn: exec,
@@ -81,7 +80,7 @@ pub fn get_object() -> HashMap<String, AST> {
 
 	object.insert(
 		"name".to_string(),
-		AST::String(os_name.to_string())
+		Value::Str(os_name.to_string())
 	);
 
 	object
@@ -93,10 +92,10 @@ mod tests {
 
 	#[test]
 	fn test_exec_echo() {
-		let args = vec![AST::String("echo hello".to_string())];
-		let result = exec(args, &mut HashMap::new()).unwrap();
-		match result.0 {
-			AST::String(value) => {
+		let args = vec![Value::Str("echo hello".to_string())];
+		let result = exec(args, &mut Environment::default()).unwrap();
+		match result {
+			Value::Str(value) => {
 				assert!(value.contains("hello"));
 			},
 			_ => panic!("Expected string output")
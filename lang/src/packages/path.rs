@@ -0,0 +1,309 @@
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use crate::value::Value;
+use crate::environment::Environment;
+use crate::error::ModuError;
+
+// everything here only looks at the path strings, nothing is read from the disk,
+// so it also works on the server
+
+fn path_arg(args: &[Value], index: usize, name: &str) -> Result<PathBuf, ModuError> {
+    match args.get(index) {
+        Some(Value::Str(path)) => Ok(PathBuf::from(path)),
+        _ => Err(ModuError::type_error(format!("{}() expects a path string as argument {}", name, index + 1))),
+    }
+}
+
+fn path_string(path: &Path) -> Value {
+    Value::Str(path.to_string_lossy().to_string())
+}
+
+fn os_string(part: Option<&std::ffi::OsStr>) -> Value {
+    Value::Str(part.map(|part| part.to_string_lossy().to_string()).unwrap_or_default())
+}
+
+// removes "." and resolves ".." without looking at the disk, ".." can't go above the root
+fn normalized(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+
+            Component::ParentDir => {
+                match result.components().next_back() {
+                    Some(Component::Normal(_)) => {
+                        result.pop();
+                    }
+
+                    Some(Component::RootDir | Component::Prefix(_)) => {}
+
+                    _ => result.push(".."),
+                }
+            }
+
+            component => result.push(component),
+        }
+    }
+
+    if result.as_os_str().is_empty() {
+        return PathBuf::from(".");
+    }
+
+    result
+}
+
+fn absolute_path(path: &Path) -> Result<PathBuf, ModuError> {
+    if path.is_absolute() {
+        return Ok(normalized(path));
+    }
+
+    let current = std::env::current_dir().map_err(|e| ModuError::io(e.to_string()))?;
+
+    Ok(normalized(&current.join(path)))
+}
+
+// join("a", "b", "c.txt"), a part that is absolute starts the path over
+pub fn join(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    if args.is_empty() {
+        return Err(ModuError::type_error("join() expects at least one path"));
+    }
+
+    let mut path = PathBuf::new();
+
+    for index in 0..args.len() {
+        path.push(path_arg(&args, index, "join")?);
+    }
+
+    Ok(path_string(&path))
+}
+
+// the path without its last part, "" if there is none
+pub fn dirname(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let path = path_arg(&args, 0, "dirname")?;
+
+    match path.parent() {
+        Some(parent) => Ok(path_string(parent)),
+        None if path.has_root() => Ok(path_string(&path)),
+        None => Ok(Value::Str(String::new())),
+    }
+}
+
+pub fn basename(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let path = path_arg(&args, 0, "basename")?;
+
+    Ok(os_string(path.file_name()))
+}
+
+// the extension without the dot, "" if there is none
+pub fn extension(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let path = path_arg(&args, 0, "extension")?;
+
+    Ok(os_string(path.extension()))
+}
+
+// the last part without its extension
+pub fn stem(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let path = path_arg(&args, 0, "stem")?;
+
+    Ok(os_string(path.file_stem()))
+}
+
+pub fn normalize(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let path = path_arg(&args, 0, "normalize")?;
+
+    Ok(path_string(&normalized(&path)))
+}
+
+// a relative path is taken from the current directory
+pub fn absolute(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let path = path_arg(&args, 0, "absolute")?;
+
+    Ok(path_string(&absolute_path(&path)?))
+}
+
+// the path that leads from `from` to `to`
+pub fn relative(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let mut from = normalized(&path_arg(&args, 0, "relative")?);
+    let mut to = normalized(&path_arg(&args, 1, "relative")?);
+
+    // ".." can only be left at the start, where it is not known which directory it leaves
+    if from.is_absolute() != to.is_absolute() || from.starts_with("..") {
+        from = absolute_path(&from)?;
+        to = absolute_path(&to)?;
+    }
+
+    let from = from.components().filter(|c| *c != Component::CurDir).collect::<Vec<Component>>();
+    let to = to.components().filter(|c| *c != Component::CurDir).collect::<Vec<Component>>();
+
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+
+    let mut result = PathBuf::new();
+
+    for _ in common..from.len() {
+        result.push("..");
+    }
+
+    for component in &to[common..] {
+        result.push(component);
+    }
+
+    if result.as_os_str().is_empty() {
+        return Ok(Value::Str(".".to_string()));
+    }
+
+    Ok(path_string(&result))
+}
+
+pub fn is_absolute(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let path = path_arg(&args, 0, "is_absolute")?;
+
+    Ok(Value::Bool(path.is_absolute()))
+}
+
+pub fn get_object() -> HashMap<String, Value> {
+    let mut object = HashMap::new();
+
+    object.insert(
+        "join".to_string(),
+        Value::NativeFunction {
+            name: "join".to_string(),
+            args: vec!["__args__".to_string()],
+            call_fn: join,
+        }
+    );
+
+    object.insert(
+        "dirname".to_string(),
+        Value::NativeFunction {
+            name: "dirname".to_string(),
+            args: vec!["path".to_string()],
+            call_fn: dirname,
+        }
+    );
+
+    object.insert(
+        "basename".to_string(),
+        Value::NativeFunction {
+            name: "basename".to_string(),
+            args: vec!["path".to_string()],
+            call_fn: basename,
+        }
+    );
+
+    object.insert(
+        "extension".to_string(),
+        Value::NativeFunction {
+            name: "extension".to_string(),
+            args: vec!["path".to_string()],
+            call_fn: extension,
+        }
+    );
+
+    object.insert(
+        "stem".to_string(),
+        Value::NativeFunction {
+            name: "stem".to_string(),
+            args: vec!["path".to_string()],
+            call_fn: stem,
+        }
+    );
+
+    object.insert(
+        "normalize".to_string(),
+        Value::NativeFunction {
+            name: "normalize".to_string(),
+            args: vec!["path".to_string()],
+            call_fn: normalize,
+        }
+    );
+
+    object.insert(
+        "absolute".to_string(),
+        Value::NativeFunction {
+            name: "absolute".to_string(),
+            args: vec!["path".to_string()],
+            call_fn: absolute,
+        }
+    );
+
+    object.insert(
+        "relative".to_string(),
+        Value::NativeFunction {
+            name: "relative".to_string(),
+            args: vec!["from".to_string(), "to".to_string()],
+            call_fn: relative,
+        }
+    );
+
+    object.insert(
+        "is_absolute".to_string(),
+        Value::NativeFunction {
+            name: "is_absolute".to_string(),
+            args: vec!["path".to_string()],
+            call_fn: is_absolute,
+        }
+    );
+
+    object
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_object_test() {
+        let object = get_object();
+
+        assert_eq!(object.len(), 9);
+    }
+
+    #[test]
+    fn parts() {
+        let mut context = Environment::default();
+
+        assert_eq!(join(vec![Value::Str("a".to_string()), Value::Str("b".to_string()), Value::Str("c.txt".to_string())], &mut context).unwrap(), Value::Str("a/b/c.txt".to_string()));
+        assert_eq!(join(vec![Value::Str("a".to_string()), Value::Str("/b".to_string())], &mut context).unwrap(), Value::Str("/b".to_string()));
+
+        assert_eq!(dirname(vec![Value::Str("a/b/c.txt".to_string())], &mut context).unwrap(), Value::Str("a/b".to_string()));
+        assert_eq!(dirname(vec![Value::Str("c.txt".to_string())], &mut context).unwrap(), Value::Str("".to_string()));
+        assert_eq!(dirname(vec![Value::Str("/".to_string())], &mut context).unwrap(), Value::Str("/".to_string()));
+
+        assert_eq!(basename(vec![Value::Str("a/b/c.tar.gz".to_string())], &mut context).unwrap(), Value::Str("c.tar.gz".to_string()));
+        assert_eq!(extension(vec![Value::Str("a/b/c.tar.gz".to_string())], &mut context).unwrap(), Value::Str("gz".to_string()));
+        assert_eq!(extension(vec![Value::Str("a/b/README".to_string())], &mut context).unwrap(), Value::Str("".to_string()));
+        assert_eq!(stem(vec![Value::Str("a/b/c.tar.gz".to_string())], &mut context).unwrap(), Value::Str("c.tar".to_string()));
+
+        assert_eq!(is_absolute(vec![Value::Str("/a".to_string())], &mut context).unwrap(), Value::Bool(true));
+        assert_eq!(is_absolute(vec![Value::Str("a".to_string())], &mut context).unwrap(), Value::Bool(false));
+    }
+
+    #[test]
+    fn normalizing() {
+        let mut context = Environment::default();
+
+        assert_eq!(normalize(vec![Value::Str("a/./b/../c/".to_string())], &mut context).unwrap(), Value::Str("a/c".to_string()));
+        assert_eq!(normalize(vec![Value::Str("../a/../../b".to_string())], &mut context).unwrap(), Value::Str("../../b".to_string()));
+        assert_eq!(normalize(vec![Value::Str("/../a".to_string())], &mut context).unwrap(), Value::Str("/a".to_string()));
+        assert_eq!(normalize(vec![Value::Str("a/..".to_string())], &mut context).unwrap(), Value::Str(".".to_string()));
+
+        let current = std::env::current_dir().unwrap();
+        assert_eq!(absolute(vec![Value::Str("a/../b".to_string())], &mut context).unwrap(), path_string(&current.join("b")));
+        assert_eq!(absolute(vec![Value::Str("/x/./y".to_string())], &mut context).unwrap(), Value::Str("/x/y".to_string()));
+    }
+
+    #[test]
+    fn relative_paths() {
+        let mut context = Environment::default();
+
+        assert_eq!(relative(vec![Value::Str("/a/b".to_string()), Value::Str("/a/c/d".to_string())], &mut context).unwrap(), Value::Str("../c/d".to_string()));
+        assert_eq!(relative(vec![Value::Str("a".to_string()), Value::Str("a/b".to_string())], &mut context).unwrap(), Value::Str("b".to_string()));
+        assert_eq!(relative(vec![Value::Str("/a/b".to_string()), Value::Str("/a/b".to_string())], &mut context).unwrap(), Value::Str(".".to_string()));
+        assert_eq!(relative(vec![Value::Str("./src".to_string()), Value::Str("tests/x.modu".to_string())], &mut context).unwrap(), Value::Str("../tests/x.modu".to_string()));
+
+        let current = std::env::current_dir().unwrap();
+        let expected = Path::new("..").join(current.file_name().unwrap()).join("b");
+        assert_eq!(relative(vec![Value::Str("../a".to_string()), Value::Str("b".to_string())], &mut context).unwrap(), path_string(&expected));
+    }
+}
@@ -0,0 +1,262 @@
+use std::{collections::HashMap, rc::Rc};
+use crate::value::{NativeFn, Pattern, Value};
+use crate::environment::Environment;
+use crate::error::ModuError;
+
+// every function takes a pattern first, either a string or one made with regex.compile()
+// a compiled pattern has the same functions as methods, without the pattern argument
+
+fn compile_pattern(source: &str) -> Result<Rc<Pattern>, ModuError> {
+    match ::regex::Regex::new(source) {
+        Ok(regex) => Ok(Rc::new(Pattern(regex))),
+        Err(e) => Err(ModuError::runtime(format!("Invalid regex {}: {}", source, e))),
+    }
+}
+
+fn pattern_arg(args: &[Value], name: &str) -> Result<Rc<Pattern>, ModuError> {
+    match args.first() {
+        Some(Value::Regex(pattern)) => Ok(pattern.clone()),
+        Some(Value::Str(source)) => compile_pattern(source),
+        _ => Err(ModuError::type_error(format!("regex.{}() expects a pattern as argument 1", name))),
+    }
+}
+
+fn string_arg(args: &[Value], index: usize, name: &str) -> Result<String, ModuError> {
+    match args.get(index) {
+        Some(Value::Str(s)) => Ok(s.clone()),
+        _ => Err(ModuError::type_error(format!("regex.{}() expects a string as argument {}", name, index + 1))),
+    }
+}
+
+pub fn compile(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let source = string_arg(&args, 0, "compile")?;
+
+    Ok(Value::Regex(compile_pattern(&source)?))
+}
+
+pub fn test(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let pattern = pattern_arg(&args, "test")?;
+    let s = string_arg(&args, 1, "test")?;
+
+    Ok(Value::Bool(pattern.0.is_match(&s)))
+}
+
+// the first match, or null
+pub fn find(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let pattern = pattern_arg(&args, "find")?;
+    let s = string_arg(&args, 1, "find")?;
+
+    match pattern.0.find(&s) {
+        Some(found) => Ok(Value::Str(found.as_str().to_string())),
+        None => Ok(Value::Null),
+    }
+}
+
+pub fn find_all(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let pattern = pattern_arg(&args, "find_all")?;
+    let s = string_arg(&args, 1, "find_all")?;
+
+    let found = pattern.0.find_iter(&s)
+        .map(|found| Value::Str(found.as_str().to_string()))
+        .collect();
+
+    Ok(Value::list(found))
+}
+
+// the named groups of the first match as an object, groups that did not take part are null
+// null if there is no match
+pub fn captures(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let pattern = pattern_arg(&args, "captures")?;
+    let s = string_arg(&args, 1, "captures")?;
+
+    let captures = match pattern.0.captures(&s) {
+        Some(captures) => captures,
+        None => return Ok(Value::Null),
+    };
+
+    let mut groups = HashMap::new();
+
+    for name in pattern.0.capture_names().flatten() {
+        let value = match captures.name(name) {
+            Some(group) => Value::Str(group.as_str().to_string()),
+            None => Value::Null,
+        };
+
+        groups.insert(name.to_string(), value);
+    }
+
+    Ok(Value::map(groups))
+}
+
+// replaces every match, $1 and $name in the replacement are groups
+pub fn replace(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let pattern = pattern_arg(&args, "replace")?;
+    let s = string_arg(&args, 1, "replace")?;
+    let replacement = string_arg(&args, 2, "replace")?;
+
+    Ok(Value::Str(pattern.0.replace_all(&s, replacement.as_str()).to_string()))
+}
+
+pub fn split(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let pattern = pattern_arg(&args, "split")?;
+    let s = string_arg(&args, 1, "split")?;
+
+    let parts = pattern.0.split(&s)
+        .map(|part| Value::Str(part.to_string()))
+        .collect();
+
+    Ok(Value::list(parts))
+}
+
+// methods of a compiled pattern, the pattern is passed as self
+pub fn get_method(name: &str) -> Option<Value> {
+    let (args, call_fn): (&[&str], NativeFn) = match name {
+        "test" => (&["self", "string"], test),
+        "find" => (&["self", "string"], find),
+        "find_all" => (&["self", "string"], find_all),
+        "captures" => (&["self", "string"], captures),
+        "replace" => (&["self", "string", "replacement"], replace),
+        "split" => (&["self", "string"], split),
+        _ => return None,
+    };
+
+    Some(Value::NativeFunction {
+        name: name.to_string(),
+        args: args.iter().map(|arg| arg.to_string()).collect(),
+        call_fn,
+    })
+}
+
+pub fn get_object() -> HashMap<String, Value> {
+    let mut object = HashMap::new();
+
+    object.insert(
+        "compile".to_string(),
+        Value::NativeFunction {
+            name: "compile".to_string(),
+            args: vec!["pattern".to_string()],
+            call_fn: compile,
+        }
+    );
+
+    object.insert(
+        "test".to_string(),
+        Value::NativeFunction {
+            name: "test".to_string(),
+            args: vec!["pattern".to_string(), "string".to_string()],
+            call_fn: test,
+        }
+    );
+
+    object.insert(
+        "find".to_string(),
+        Value::NativeFunction {
+            name: "find".to_string(),
+            args: vec!["pattern".to_string(), "string".to_string()],
+            call_fn: find,
+        }
+    );
+
+    object.insert(
+        "find_all".to_string(),
+        Value::NativeFunction {
+            name: "find_all".to_string(),
+            args: vec!["pattern".to_string(), "string".to_string()],
+            call_fn: find_all,
+        }
+    );
+
+    object.insert(
+        "captures".to_string(),
+        Value::NativeFunction {
+            name: "captures".to_string(),
+            args: vec!["pattern".to_string(), "string".to_string()],
+            call_fn: captures,
+        }
+    );
+
+    object.insert(
+        "replace".to_string(),
+        Value::NativeFunction {
+            name: "replace".to_string(),
+            args: vec!["pattern".to_string(), "string".to_string(), "replacement".to_string()],
+            call_fn: replace,
+        }
+    );
+
+    object.insert(
+        "split".to_string(),
+        Value::NativeFunction {
+            name: "split".to_string(),
+            args: vec!["pattern".to_string(), "string".to_string()],
+            call_fn: split,
+        }
+    );
+
+    object
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_object_test() {
+        let object = get_object();
+
+        assert_eq!(object.len(), 7);
+        assert_eq!(get_method("compile"), None);
+    }
+
+    #[test]
+    fn strings_and_compiled_patterns() {
+        let mut context = Environment::default();
+
+        let compiled = compile(vec![Value::Str("[0-9]+".to_string())], &mut context).unwrap();
+
+        for pattern in [Value::Str("[0-9]+".to_string()), compiled] {
+            assert_eq!(test(vec![pattern.clone(), Value::Str("a1".to_string())], &mut context).unwrap(), Value::Bool(true));
+            assert_eq!(find(vec![pattern.clone(), Value::Str("a12b3".to_string())], &mut context).unwrap(), Value::Str("12".to_string()));
+            assert_eq!(find(vec![pattern.clone(), Value::Str("ab".to_string())], &mut context).unwrap(), Value::Null);
+            assert_eq!(find_all(vec![pattern.clone(), Value::Str("a12b3".to_string())], &mut context).unwrap().to_string(), "[12, 3]");
+            assert_eq!(split(vec![pattern.clone(), Value::Str("a12b3c".to_string())], &mut context).unwrap().to_string(), "[a, b, c]");
+        }
+    }
+
+    #[test]
+    fn named_captures() {
+        let mut context = Environment::default();
+
+        let pattern = Value::Str("(?P<level>[A-Z]+)(: (?P<message>.*))?".to_string());
+
+        let result = captures(vec![pattern.clone(), Value::Str("WARN: disk full".to_string())], &mut context).unwrap();
+        assert_eq!(result, Value::map(HashMap::from([
+            ("level".to_string(), Value::Str("WARN".to_string())),
+            ("message".to_string(), Value::Str("disk full".to_string())),
+        ])));
+
+        let result = captures(vec![pattern.clone(), Value::Str("INFO".to_string())], &mut context).unwrap();
+        assert_eq!(result, Value::map(HashMap::from([
+            ("level".to_string(), Value::Str("INFO".to_string())),
+            ("message".to_string(), Value::Null),
+        ])));
+
+        assert_eq!(captures(vec![pattern, Value::Str("lower".to_string())], &mut context).unwrap(), Value::Null);
+    }
+
+    #[test]
+    fn replace_groups() {
+        let mut context = Environment::default();
+
+        let result = replace(vec![Value::Str("(?P<k>\\w+)=(\\w+)".to_string()), Value::Str("a=1 b=2".to_string()), Value::Str("$2:$k".to_string())], &mut context).unwrap();
+        assert_eq!(result, Value::Str("1:a 2:b".to_string()));
+    }
+
+    #[test]
+    fn invalid_pattern() {
+        let mut context = Environment::default();
+
+        let result = test(vec![Value::Str("(".to_string()), Value::Str("a".to_string())], &mut context);
+        assert!(result.map_err(|e| e.to_string()).unwrap_err().starts_with("RuntimeError: Invalid regex ("));
+    }
+}
@@ -0,0 +1,422 @@
+use std::collections::HashMap;
+use crate::value::Value;
+use crate::environment::Environment;
+use crate::error::ModuError;
+
+// indexes and lengths count characters, not bytes
+
+// repeat() and padding can't build strings longer than this, a huge count is an error instead of running out of memory
+const MAX_LENGTH: usize = 64 * 1024 * 1024;
+
+fn string_arg(args: &[Value], index: usize, name: &str) -> Result<String, ModuError> {
+    match args.get(index) {
+        Some(Value::Str(s)) => Ok(s.clone()),
+        _ => Err(ModuError::type_error(format!("{}() expects a string as argument {}", name, index + 1))),
+    }
+}
+
+fn int_arg(args: &[Value], index: usize, name: &str) -> Result<i64, ModuError> {
+    match args.get(index) {
+        Some(Value::Int(n)) => Ok(*n),
+        _ => Err(ModuError::type_error(format!("{}() expects a number as argument {}", name, index + 1))),
+    }
+}
+
+fn strings(items: Vec<String>) -> Value {
+    Value::list(items.into_iter().map(Value::Str).collect())
+}
+
+pub fn len(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let s = string_arg(&args, 0, "len")?;
+
+    Ok(Value::Int(s.chars().count() as i64))
+}
+
+// an empty separator splits into characters
+pub fn split(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let s = string_arg(&args, 0, "split")?;
+    let separator = string_arg(&args, 1, "split")?;
+
+    if separator.is_empty() {
+        return Ok(strings(s.chars().map(|c| c.to_string()).collect()));
+    }
+
+    Ok(strings(s.split(separator.as_str()).map(String::from).collect()))
+}
+
+pub fn join(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let separator = string_arg(&args, 1, "join")?;
+
+    match &args[0] {
+        Value::List(items) => {
+            let items = items.borrow().iter().map(|item| item.to_string()).collect::<Vec<String>>();
+
+            Ok(Value::Str(items.join(&separator)))
+        }
+
+        _ => Err(ModuError::type_error("join() expects an array as argument 1")),
+    }
+}
+
+pub fn trim(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let s = string_arg(&args, 0, "trim")?;
+
+    Ok(Value::Str(s.trim().to_string()))
+}
+
+pub fn upper(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let s = string_arg(&args, 0, "upper")?;
+
+    Ok(Value::Str(s.to_uppercase()))
+}
+
+pub fn lower(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let s = string_arg(&args, 0, "lower")?;
+
+    Ok(Value::Str(s.to_lowercase()))
+}
+
+// replaces every occurrence
+pub fn replace(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let s = string_arg(&args, 0, "replace")?;
+    let from = string_arg(&args, 1, "replace")?;
+    let to = string_arg(&args, 2, "replace")?;
+
+    Ok(Value::Str(s.replace(&from, &to)))
+}
+
+pub fn contains(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let s = string_arg(&args, 0, "contains")?;
+    let part = string_arg(&args, 1, "contains")?;
+
+    Ok(Value::Bool(s.contains(&part)))
+}
+
+pub fn starts_with(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let s = string_arg(&args, 0, "starts_with")?;
+    let part = string_arg(&args, 1, "starts_with")?;
+
+    Ok(Value::Bool(s.starts_with(&part)))
+}
+
+pub fn ends_with(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let s = string_arg(&args, 0, "ends_with")?;
+    let part = string_arg(&args, 1, "ends_with")?;
+
+    Ok(Value::Bool(s.ends_with(&part)))
+}
+
+// the index of the first occurrence, or -1
+pub fn index_of(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let s = string_arg(&args, 0, "index_of")?;
+    let part = string_arg(&args, 1, "index_of")?;
+
+    match s.find(&part) {
+        Some(byte) => Ok(Value::Int(s[..byte].chars().count() as i64)),
+        None => Ok(Value::Int(-1)),
+    }
+}
+
+// substring(s, start) or substring(s, start, end), negative indexes count from the end
+pub fn substring(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    if args.len() != 2 && args.len() != 3 {
+        return Err(ModuError::type_error("substring() takes a string, a start and an optional end index"));
+    }
+
+    let chars = string_arg(&args, 0, "substring")?.chars().collect::<Vec<char>>();
+    let length = chars.len() as i64;
+
+    let resolve = |index: i64| -> usize {
+        if index < 0 {
+            (length + index).max(0) as usize
+        } else {
+            index.min(length) as usize
+        }
+    };
+
+    let start = resolve(int_arg(&args, 1, "substring")?);
+
+    let end = if args.len() == 3 {
+        resolve(int_arg(&args, 2, "substring")?)
+    } else {
+        length as usize
+    };
+
+    Ok(Value::Str(chars[start..end.max(start)].iter().collect()))
+}
+
+pub fn repeat(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let s = string_arg(&args, 0, "repeat")?;
+
+    let count = usize::try_from(int_arg(&args, 1, "repeat")?)
+        .map_err(|_| ModuError::runtime("repeat() count can't be negative"))?;
+
+    match s.len().checked_mul(count) {
+        Some(length) if length <= MAX_LENGTH => Ok(Value::Str(s.repeat(count))),
+        _ => Err(ModuError::runtime(format!("repeat() result would be longer than {} bytes", MAX_LENGTH))),
+    }
+}
+
+// the padding to get `s` to `length` characters, made of `pad` which defaults to a space
+fn padding(args: &[Value], name: &str) -> Result<(String, String), ModuError> {
+    if args.len() != 2 && args.len() != 3 {
+        return Err(ModuError::type_error(format!("{}() takes a string, a length and an optional padding string", name)));
+    }
+
+    let s = string_arg(args, 0, name)?;
+    let length = int_arg(args, 1, name)?;
+
+    let pad = if args.len() == 3 {
+        string_arg(args, 2, name)?
+    } else {
+        " ".to_string()
+    };
+
+    let length = usize::try_from(length)
+        .map_err(|_| ModuError::runtime(format!("{}() length can't be negative", name)))?;
+
+    let missing = length.saturating_sub(s.chars().count());
+
+    // a character is at most 4 bytes
+    if missing.saturating_mul(4) > MAX_LENGTH {
+        return Err(ModuError::runtime(format!("{}() result would be longer than {} bytes", name, MAX_LENGTH)));
+    }
+
+    if pad.is_empty() || missing == 0 {
+        return Ok((s, String::new()));
+    }
+
+    Ok((s, pad.chars().cycle().take(missing).collect()))
+}
+
+pub fn pad_start(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let (s, padding) = padding(&args, "pad_start")?;
+
+    Ok(Value::Str(padding + &s))
+}
+
+pub fn pad_end(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let (s, padding) = padding(&args, "pad_end")?;
+
+    Ok(Value::Str(s + &padding))
+}
+
+pub fn chars(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let s = string_arg(&args, 0, "chars")?;
+
+    Ok(strings(s.chars().map(|c| c.to_string()).collect()))
+}
+
+pub fn get_object() -> HashMap<String, Value> {
+    let mut object = HashMap::new();
+
+    object.insert(
+        "len".to_string(),
+        Value::NativeFunction {
+            name: "len".to_string(),
+            args: vec!["string".to_string()],
+            call_fn: len,
+        }
+    );
+
+    object.insert(
+        "split".to_string(),
+        Value::NativeFunction {
+            name: "split".to_string(),
+            args: vec!["string".to_string(), "separator".to_string()],
+            call_fn: split,
+        }
+    );
+
+    object.insert(
+        "join".to_string(),
+        Value::NativeFunction {
+            name: "join".to_string(),
+            args: vec!["array".to_string(), "separator".to_string()],
+            call_fn: join,
+        }
+    );
+
+    object.insert(
+        "trim".to_string(),
+        Value::NativeFunction {
+            name: "trim".to_string(),
+            args: vec!["string".to_string()],
+            call_fn: trim,
+        }
+    );
+
+    object.insert(
+        "upper".to_string(),
+        Value::NativeFunction {
+            name: "upper".to_string(),
+            args: vec!["string".to_string()],
+            call_fn: upper,
+        }
+    );
+
+    object.insert(
+        "lower".to_string(),
+        Value::NativeFunction {
+            name: "lower".to_string(),
+            args: vec!["string".to_string()],
+            call_fn: lower,
+        }
+    );
+
+    object.insert(
+        "replace".to_string(),
+        Value::NativeFunction {
+            name: "replace".to_string(),
+            args: vec!["string".to_string(), "from".to_string(), "to".to_string()],
+            call_fn: replace,
+        }
+    );
+
+    object.insert(
+        "contains".to_string(),
+        Value::NativeFunction {
+            name: "contains".to_string(),
+            args: vec!["string".to_string(), "part".to_string()],
+            call_fn: contains,
+        }
+    );
+
+    object.insert(
+        "starts_with".to_string(),
+        Value::NativeFunction {
+            name: "starts_with".to_string(),
+            args: vec!["string".to_string(), "part".to_string()],
+            call_fn: starts_with,
+        }
+    );
+
+    object.insert(
+        "ends_with".to_string(),
+        Value::NativeFunction {
+            name: "ends_with".to_string(),
+            args: vec!["string".to_string(), "part".to_string()],
+            call_fn: ends_with,
+        }
+    );
+
+    object.insert(
+        "index_of".to_string(),
+        Value::NativeFunction {
+            name: "index_of".to_string(),
+            args: vec!["string".to_string(), "part".to_string()],
+            call_fn: index_of,
+        }
+    );
+
+    object.insert(
+        "substring".to_string(),
+        Value::NativeFunction {
+            name: "substring".to_string(),
+            args: vec!["__args__".to_string()],
+            call_fn: substring,
+        }
+    );
+
+    object.insert(
+        "repeat".to_string(),
+        Value::NativeFunction {
+            name: "repeat".to_string(),
+            args: vec!["string".to_string(), "count".to_string()],
+            call_fn: repeat,
+        }
+    );
+
+    object.insert(
+        "pad_start".to_string(),
+        Value::NativeFunction {
+            name: "pad_start".to_string(),
+            args: vec!["__args__".to_string()],
+            call_fn: pad_start,
+        }
+    );
+
+    object.insert(
+        "pad_end".to_string(),
+        Value::NativeFunction {
+            name: "pad_end".to_string(),
+            args: vec!["__args__".to_string()],
+            call_fn: pad_end,
+        }
+    );
+
+    object.insert(
+        "chars".to_string(),
+        Value::NativeFunction {
+            name: "chars".to_string(),
+            args: vec!["string".to_string()],
+            call_fn: chars,
+        }
+    );
+
+    object
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_object_test() {
+        let object = get_object();
+
+        assert_eq!(object.len(), 16);
+    }
+
+    #[test]
+    fn split_and_join() {
+        let mut context = Environment::default();
+
+        let parts = split(vec![Value::Str("a,b,,c".to_string()), Value::Str(",".to_string())], &mut context).unwrap();
+        assert_eq!(parts.to_string(), "[a, b, , c]");
+
+        assert_eq!(split(vec![Value::Str("hé".to_string()), Value::Str("".to_string())], &mut context).unwrap().to_string(), "[h, é]");
+        assert_eq!(join(vec![parts, Value::Str("-".to_string())], &mut context).unwrap(), Value::Str("a-b--c".to_string()));
+    }
+
+    #[test]
+    fn counts_characters() {
+        let mut context = Environment::default();
+
+        assert_eq!(len(vec![Value::Str("héllo".to_string())], &mut context).unwrap(), Value::Int(5));
+        assert_eq!(index_of(vec![Value::Str("héllo".to_string()), Value::Str("llo".to_string())], &mut context).unwrap(), Value::Int(2));
+        assert_eq!(index_of(vec![Value::Str("héllo".to_string()), Value::Str("x".to_string())], &mut context).unwrap(), Value::Int(-1));
+        assert_eq!(substring(vec![Value::Str("héllo".to_string()), Value::Int(1), Value::Int(-1)], &mut context).unwrap(), Value::Str("éll".to_string()));
+        assert_eq!(substring(vec![Value::Str("héllo".to_string()), Value::Int(3)], &mut context).unwrap(), Value::Str("lo".to_string()));
+    }
+
+    #[test]
+    fn padding_and_repeat() {
+        let mut context = Environment::default();
+
+        assert_eq!(pad_start(vec![Value::Str("7".to_string()), Value::Int(3), Value::Str("0".to_string())], &mut context).unwrap(), Value::Str("007".to_string()));
+        assert_eq!(pad_end(vec![Value::Str("ab".to_string()), Value::Int(5), Value::Str("xy".to_string())], &mut context).unwrap(), Value::Str("abxyx".to_string()));
+        assert_eq!(pad_start(vec![Value::Str("long".to_string()), Value::Int(2)], &mut context).unwrap(), Value::Str("long".to_string()));
+        assert_eq!(repeat(vec![Value::Str("ab".to_string()), Value::Int(3)], &mut context).unwrap(), Value::Str("ababab".to_string()));
+
+        let result = repeat(vec![Value::Str("ab".to_string()), Value::Int(-1)], &mut context);
+        assert_eq!(result.map_err(|e| e.to_string()), Err("RuntimeError: repeat() count can't be negative".to_string()));
+
+        let result = repeat(vec![Value::Str("ab".to_string()), Value::Int(i64::MAX)], &mut context);
+        assert_eq!(result.map_err(|e| e.to_string()), Err("RuntimeError: repeat() result would be longer than 67108864 bytes".to_string()));
+
+        let result = pad_end(vec![Value::Str("ab".to_string()), Value::Int(-1)], &mut context);
+        assert_eq!(result.map_err(|e| e.to_string()), Err("RuntimeError: pad_end() length can't be negative".to_string()));
+
+        let result = pad_start(vec![Value::Str("ab".to_string()), Value::Int(1 << 40)], &mut context);
+        assert_eq!(result.map_err(|e| e.to_string()), Err("RuntimeError: pad_start() result would be longer than 67108864 bytes".to_string()));
+    }
+
+    #[test]
+    fn wrong_types() {
+        let mut context = Environment::default();
+
+        let result = upper(vec![Value::Int(1)], &mut context);
+        assert_eq!(result.map_err(|e| e.to_string()), Err("TypeError: upper() expects a string as argument 1".to_string()));
+    }
+}
@@ -2,26 +2,26 @@ use std::collections::HashMap;
 use std::time;
 use chrono::prelude::{DateTime, Local};
 
-use crate::ast::AST;
-use crate::eval::eval;
+use crate::value::Value;
+use crate::environment::Environment;
+use crate::error::ModuError;
 
 
-pub fn now(_: Vec<AST>,  _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
+pub fn now(_: Vec<Value>,  _: &mut Environment) -> Result<Value, ModuError> {
     let now = time::SystemTime::now()
         .duration_since(time::UNIX_EPOCH)
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| ModuError::runtime(e.to_string()))?;
 
-    Ok((AST::Number(now.as_secs() as i64), AST::Null))
+    Ok(Value::Int(now.as_secs() as i64))
 }
 
 
-pub fn to_iso_8601(args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    let time = match eval(args[0].clone(), context) {
-        Ok(AST::Number(time)) => time,
-        Ok(AST::Float(time)) => time as i64,
+pub fn to_iso_8601(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let time = match args[0].clone() {
+        Value::Int(time) => time,
+        Value::Float(time) => time as i64,
         
-        Ok(_) => return Err("to_iso_8601() expects a number".to_string()),
-        Err(e) => return Err(e),
+        _ => return Err(ModuError::type_error("to_iso_8601() expects a number")),
     };
 
     let time = time::UNIX_EPOCH + time::Duration::from_secs(time as u64);
@@ -29,16 +29,15 @@ pub fn to_iso_8601(args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result
     let time = time.format("%+").to_string();
 
 
-    Ok((AST::String(time), AST::Null))
+    Ok(Value::Str(time))
 }
 
-pub fn to_local_date_time(args: Vec<AST>, context: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    let time = match eval(args[0].clone(), context) {
-        Ok(AST::Number(time)) => time,
-        Ok(AST::Float(time)) => time as i64,
+pub fn to_local_date_time(args: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    let time = match args[0].clone() {
+        Value::Int(time) => time,
+        Value::Float(time) => time as i64,
         
-        Ok(_) => return Err("to_iso_8601() expects a number".to_string()),
-        Err(e) => return Err(e),
+        _ => return Err(ModuError::type_error("to_iso_8601() expects a number")),
     };
 
     let time = time::UNIX_EPOCH + time::Duration::from_secs(time as u64);
@@ -46,25 +45,25 @@ pub fn to_local_date_time(args: Vec<AST>, context: &mut HashMap<String, AST>) ->
     let time = time.format("%c").to_string();
 
 
-    Ok((AST::String(time), AST::Null))
+    Ok(Value::Str(time))
 }
 
-pub fn get_object() -> HashMap<String, AST> {
+pub fn get_object() -> HashMap<String, Value> {
     let mut object = HashMap::new();
 
     object.insert(
         "now".to_string(),
-        AST::InternalFunction { name: "now".to_string(), args: vec![], call_fn: now }
+        Value::NativeFunction { name: "now".to_string(), args: vec![], call_fn: now }
     );
 
     object.insert(
         "to_iso_8601".to_string(),
-        AST::InternalFunction { name: "to_iso_8601".to_string(), args: vec!["unix".to_string()], call_fn: to_iso_8601 }
+        Value::NativeFunction { name: "to_iso_8601".to_string(), args: vec!["unix".to_string()], call_fn: to_iso_8601 }
     );
 
     object.insert(
         "to_local_date_time".to_string(),
-        AST::InternalFunction { name: "to_local_date_time".to_string(), args: vec!["unix".to_string()], call_fn: to_local_date_time }
+        Value::NativeFunction { name: "to_local_date_time".to_string(), args: vec!["unix".to_string()], call_fn: to_local_date_time }
     );
 
     return object;
@@ -83,11 +82,11 @@ mod tests {
 
     #[test]
     fn get_current_time() {
-        let time = now(vec![], &mut HashMap::new()).unwrap().0;
+        let time = now(vec![], &mut Environment::default()).unwrap();
         
         assert_eq!(
             time,
-            AST::Number(
+            Value::Int(
                 time::SystemTime::now()
                     .duration_since(time::UNIX_EPOCH)
                     .unwrap()
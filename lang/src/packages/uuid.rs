@@ -1,17 +1,19 @@
 use std::collections::HashMap;
-use crate::ast::AST;
+use crate::value::Value;
+use crate::environment::Environment;
+use crate::error::ModuError;
 use uuid;
 
-pub fn v4(_: Vec<AST>, _: &mut HashMap<String, AST>) -> Result<(AST, AST), String> {
-    Ok((AST::String(uuid::Uuid::new_v4().to_string()), AST::Null))
+pub fn v4(_: Vec<Value>, _: &mut Environment) -> Result<Value, ModuError> {
+    Ok(Value::Str(uuid::Uuid::new_v4().to_string()))
 }
 
-pub fn get_object() -> HashMap<String, AST> {
+pub fn get_object() -> HashMap<String, Value> {
     let mut object = HashMap::new();
 
     object.insert(
         "v4".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "v4".to_string(),
             args: vec![],
             call_fn: v4
@@ -1,3178 +1,933 @@
-use crate::ast::AST;
-use crate::lexer::{Token, LexingError};
-use crate::eval::eval;
+use crate::ast::{AST, Program, Span};
+use crate::environment::Environment;
+use crate::lexer::{Token, LexingError, StringPart};
+use crate::error::ModuError;
+use crate::interpreter;
 
 use logos::Logos;
-use std::collections::HashMap;
-use std::vec;
-
-pub fn insert_right_bracket(obj: AST) -> AST {
-    match obj {
-        AST::Function { name, args, mut body, line } => {
-            match body.pop().unwrap_or(AST::Null) {
-                AST::IfStatement { condition, body: mut if_body, line: if_line } => {
-                    match if_body.pop().unwrap_or(AST::Null) {
-                        AST::RBracket => {
-                            if_body.push(AST::RBracket);
-
-                            body.push(AST::IfStatement {
-                                condition,
-                                body: if_body,
-                                line: if_line,
-                            });
-
-                            body.push(AST::RBracket);
-                        }
-
-                        val => {
-                            if_body.push(val);
-                            
-                            let new_body = insert_right_bracket(AST::IfStatement {
-                                condition,
-                                body: if_body,
-                                line,
-                            });
-        
-                            body.push(new_body);
-                        }
-                    }
-                }
 
-                AST::Null => {
-                    body.push(AST::RBracket);
-                }
+#[derive(Debug, Clone)]
+struct Lexeme {
+    token: Token,
+    text: String,
+    span: Span,
+    newline_before: bool, // statements end at a newline unless inside parentheses
+}
 
-                val => {
-                    body.push(val);
+fn lexing_error(err: LexingError, slice: &str) -> String {
+    match err {
+        LexingError::UnexpectedToken => format!("Unexpected token: {:?}", slice),
+        LexingError::InvalidInteger(str) => format!("Could not parse integer: {:?}", str),
+        LexingError::InvalidEscape(escape) => format!("Invalid escape sequence: {}", escape),
+    }
+}
 
-                    body.push(AST::RBracket);
-                }
-            }
+// `start` is the line and column the input begins at, the code in a `${}` starts somewhere inside a file
+fn tokenize(input: &str, start: (usize, usize)) -> Result<Vec<Lexeme>, ParseError> {
+    let line_starts = std::iter::once(0)
+        .chain(input.match_indices('\n').map(|(i, _)| i + 1))
+        .collect::<Vec<usize>>();
 
-            return AST::Function {
-                name,
-                args,
-                body,
-                line,
-            };
+    // turns a byte offset into a line and column
+    let position = |offset: usize| {
+        let line = line_starts.partition_point(|start| *start <= offset);
+        let column = input[line_starts[line - 1]..offset].chars().count() + 1;
+
+        if line == 1 {
+            (start.0, start.1 + column - 1)
+        } else {
+            (start.0 + line - 1, column)
         }
+    };
 
-        AST::IfStatement { condition, mut body, line } => {
-            match body.pop().unwrap_or(AST::Null) {
-                AST::IfStatement { condition: if_condition, body: mut if_body, line: if_line } => {
-                    match if_body.pop().unwrap_or(AST::Null) {
-                        AST::RBracket => {
-                            if_body.push(AST::RBracket);
-
-                            body.push(AST::IfStatement {
-                                condition: if_condition,
-                                body: if_body,
-                                line: if_line,
-                            });
-
-                            body.push(AST::RBracket);
-                        }
-
-                        val => {
-                            if_body.push(val);
-                            
-                            let new_body = insert_right_bracket(AST::IfStatement {
-                                condition: if_condition,
-                                body: if_body,
-                                line,
-                            });
-        
-                            body.push(new_body);
-                        }
-                    }
-                }
+    let mut tokens = Vec::new();
+    let mut last_line = start.0;
+    let mut lexer = Token::lexer(input);
 
-                AST::Null => {
-                    body.push(AST::RBracket);
-                }
+    while let Some(token) = lexer.next() {
+        let range = lexer.span();
+        let (line, column) = position(range.start);
+        let (end_line, end_column) = position(range.end);
 
-                val => {
-                    body.push(val);
+        match token {
+            Ok(Token::Comment) => {}
 
-                    body.push(AST::RBracket);
-                }
+            // an unterminated multi line comment runs until the end of the file
+            Ok(Token::MultiLineCommentStart) => {
+                break;
             }
 
-            return AST::IfStatement {
-                condition,
-                body,
-                line,
-            };
-        }
+            Ok(token) => {
+                tokens.push(Lexeme {
+                    token,
+                    text: lexer.slice().to_string(),
+                    span: Span { line, column, end_line, end_column },
+                    newline_before: line > last_line,
+                });
+
+                last_line = end_line;
+            }
 
-        _ => {
-            return obj;
+            Err(err) => {
+                return Err(ParseError {
+                    message: lexing_error(err, lexer.slice()),
+                    span: Span { line, column, end_line, end_column },
+                    unexpected_eof: false,
+                    invalid_token: true,
+                });
+            }
         }
     }
+
+    Ok(tokens)
 }
 
-pub fn handle_nested_ast(mut ast: Vec<AST>, temp_ast: Vec<AST>, current_line: usize) -> Result<Vec<AST>, (String, usize)> {
-    if ast.is_empty() {
-        return Ok(temp_ast);
-    }
-
-    let last = ast.pop().unwrap();
-        
-    match last {
-        AST::Function { name, args, mut body, line } => {
-            if let Some(last_body_expr) = body.pop() {
-                match last_body_expr {
-                    AST::IfStatement { condition, body: mut if_body, line: if_line } => {
-                        match if_body.pop().unwrap_or(AST::Null) {
-                            AST::RBracket => {
-                                if_body.push(AST::RBracket);
-
-                                body.push(AST::IfStatement {
-                                    condition,
-                                    body: if_body,
-                                    line: if_line,
-                                });
-
-                                body.extend(temp_ast);
-                            }
-
-                            AST::Null => {
-                                let updated_body = handle_nested_ast(if_body, temp_ast, current_line)?;
-
-                                body.push(AST::IfStatement {
-                                    condition,
-                                    body: updated_body,
-                                    line: if_line,
-                                });
-                            }
-
-                            val => {
-                                if_body.push(val);
-
-                                let updated_body = handle_nested_ast(if_body, temp_ast, current_line)?;
-
-                                body.push(AST::IfStatement {
-                                    condition,
-                                    body: updated_body,
-                                    line: if_line,
-                                });
-                            }
-                        }
-                        
-                        /*let updated_body = handle_nested_ast(if_body, temp_ast, current_line)?;
-
-                        body.push(AST::IfStatement {
-                            condition,
-                            body: updated_body,
-                            line: if_line,
-                        });*/
-                    }
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParseError {
+    pub message: String,
+    pub span: Span,
+    pub unexpected_eof: bool, // the input ended too early, more input could still make it valid
+    pub invalid_token: bool, // the lexer couldn't make sense of the input
+}
 
-                    AST::RBracket => {
-                        body.push(AST::RBracket);
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
 
-                        ast.push(AST::Function {
-                            name,
-                            args,
-                            body,
-                            line,
-                        });
+pub struct Parser {
+    tokens: Vec<Lexeme>,
+    pos: usize,
+    paren_depth: usize,
+    nesting: usize, // expressions and blocks we are inside of, see MAX_NESTING
+    loop_depth: usize,
+    function_depth: usize,
+}
 
-                        ast.extend(temp_ast);
+// expressions and blocks nested deeper than this are an error instead of a stack overflow
+const MAX_NESTING: usize = 200;
+
+impl Parser {
+    pub fn new(input: &str) -> Result<Self, ParseError> {
+        Ok(Parser {
+            tokens: tokenize(input, (1, 1))?,
+            pos: 0,
+            paren_depth: 0,
+            nesting: 0,
+            loop_depth: 0,
+            function_depth: 0,
+        })
+    }
 
-                        return Ok(ast);
-                    }
+    pub fn parse_program(&mut self) -> Result<Program, ParseError> {
+        let mut body = Vec::new();
 
-                    AST::Null => {
-                        body.extend(temp_ast);
-                    }
+        loop {
+            while self.eat(Token::Semicolon) {}
 
-                    other => {
-                        body.push(other);
-                        body.extend(temp_ast);
-                    }
-                }
-            } else {
-                body.extend(temp_ast);
+            if self.peek().is_none() {
+                return Ok(Program { body });
             }
 
-            ast.push(AST::Function {
-                name,
-                args,
-                body,
-                line,
-            });
-
-            Ok(ast)
+            body.push(self.parse_statement()?);
+            self.end_statement()?;
         }
+    }
 
-        AST::IfStatement { condition, mut body, line } => {
-            if let Some(last_body_expr) = body.pop() {
-                match last_body_expr {
-                    AST::IfStatement { condition, body: mut if_body, line: if_line } => {
-                        match if_body.pop().unwrap_or(AST::Null) {
-                            AST::RBracket => {
-                                if_body.push(AST::RBracket);
-
-                                body.push(AST::IfStatement {
-                                    condition,
-                                    body: if_body,
-                                    line: if_line,
-                                });
-
-                                body.extend(temp_ast);
-                            }
-
-                            AST::Null => {
-                                let updated_body = handle_nested_ast(if_body, temp_ast, current_line)?;
-
-                                body.push(AST::IfStatement {
-                                    condition,
-                                    body: updated_body,
-                                    line: if_line,
-                                });
-                            }
-
-                            val => {
-                                if_body.push(val);
-
-                                let updated_body = handle_nested_ast(if_body, temp_ast, current_line)?;
-
-                                body.push(AST::IfStatement {
-                                    condition,
-                                    body: updated_body,
-                                    line: if_line,
-                                });
-                            }
-                        }
-                        
-                        /*let updated_body = handle_nested_ast(if_body, temp_ast, current_line)?;
-
-                        body.push(AST::IfStatement {
-                            condition,
-                            body: updated_body,
-                            line: if_line,
-                        });*/
-                    }
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|lexeme| &lexeme.token)
+    }
 
-                    AST::RBracket => {
-                        body.push(AST::RBracket);
+    // true when the next token is on the same line as the previous one
+    fn on_same_line(&self) -> bool {
+        self.tokens.get(self.pos).is_some_and(|lexeme| !lexeme.newline_before)
+    }
 
-                        ast.push(AST::IfStatement {
-                            condition,
-                            body,
-                            line,
-                        });
+    fn next(&mut self) -> Option<Lexeme> {
+        let lexeme = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        lexeme
+    }
 
-                        ast.extend(temp_ast);
+    fn eat(&mut self, token: Token) -> bool {
+        if self.peek() == Some(&token) {
+            self.pos += 1;
+            return true;
+        }
 
-                        return Ok(ast);
-                    }
+        false
+    }
 
-                    AST::Null => {
-                        body.extend(temp_ast);
-                    }
+    // an error at the next token, or right after the last one at the end of the input
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        let (span, unexpected_eof) = match self.tokens.get(self.pos) {
+            Some(lexeme) => (lexeme.span, false),
 
-                    other => {
-                        body.push(other);
-                        body.extend(temp_ast);
-                    }
-                }
-            } else {
-                body.extend(temp_ast);
+            None => {
+                let end = self.tokens.last().map(|lexeme| lexeme.span).unwrap_or(Span { line: 1, column: 1, end_line: 1, end_column: 1 });
+
+                (Span { line: end.end_line, column: end.end_column, end_line: end.end_line, end_column: end.end_column + 1 }, true)
             }
+        };
 
-            ast.push(AST::IfStatement {
-                condition,
-                body,
-                line,
-            });
+        ParseError {
+            message: message.into(),
+            span,
+            unexpected_eof,
+            invalid_token: false,
+        }
+    }
 
-            Ok(ast)
+    fn error_at(message: impl Into<String>, span: Span) -> ParseError {
+        ParseError {
+            message: message.into(),
+            span,
+            unexpected_eof: false,
+            invalid_token: false,
         }
+    }
 
-        _ => {
-            ast.push(last);
-            ast.extend(temp_ast);
+    fn start(&self) -> Span {
+        self.tokens.get(self.pos)
+            .map(|lexeme| lexeme.span)
+            .unwrap_or_default()
+    }
+
+    // a span from `start` to the end of the last consumed token
+    fn span_from(&self, start: Span) -> Span {
+        match self.tokens.get(self.pos.wrapping_sub(1)) {
+            Some(last) => Span {
+                end_line: last.span.end_line,
+                end_column: last.span.end_column,
+                ..start
+            },
 
-            Ok(ast)
+            None => start,
         }
     }
-}
-
-pub fn handle_nested_arguments(last: AST, arg: AST) -> Result<AST, (String, usize)> {
-    let mut args: Vec<AST>;
-    let line: usize;
 
-    match last.clone() {
-        AST::Call { args: call_args, line: call_line, .. } => {
-            args = call_args;
-            line = call_line;
+    fn unexpected(&self, expected: &str) -> ParseError {
+        match self.tokens.get(self.pos) {
+            Some(lexeme) => self.error(format!("Expected {} but got {:?}", expected, lexeme.text)),
+            None => self.error(format!("Expected {} but reached the end of the file", expected)),
         }
+    }
 
-        AST::PropertyCall { args: property_args, line: property_line, .. } => {
-            args = property_args;
-            line = property_line;
+    fn expect(&mut self, token: Token, expected: &str) -> Result<Lexeme, ParseError> {
+        if self.peek() == Some(&token) {
+            return Ok(self.next().unwrap());
         }
 
-        _ => {
-            return Ok(last);
+        Err(self.unexpected(expected))
+    }
+
+    fn end_statement(&mut self) -> Result<(), ParseError> {
+        if self.eat(Token::Semicolon) || !self.on_same_line() || self.peek() == Some(&Token::RBracket) {
+            return Ok(());
         }
+
+        Err(self.error(format!("Unexpected {:?}", self.tokens[self.pos].text)))
     }
 
-    let last_arg = args.pop().unwrap_or(AST::Null);
-    match (last_arg.clone(), arg.clone()) {
-        (AST::Call { name: inner_name, args: mut inner_args, line: inner_line }, AST::Rparen) => {
-            let last_inner = inner_args.pop().unwrap_or(AST::Null);
+    // counts one more level of nesting for the time `parse` runs
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> Result<T, ParseError>) -> Result<T, ParseError> {
+        if self.nesting >= MAX_NESTING {
+            return Err(self.error("Nesting too deep"));
+        }
 
-            match last_inner {
-                AST::Rparen => {
-                    args.push(AST::Call {
-                        name: inner_name.clone(),
-                        args: inner_args,
-                        line: inner_line,
-                    });
+        self.nesting += 1;
+        let result = parse(self);
+        self.nesting -= 1;
 
-                    args.push(AST::Rparen);
-                }
+        result
+    }
 
-                AST::Call { name: inner_inner_name, args: inner_inner_args, line: inner_inner_line } => {
-                    let new_call = handle_nested_arguments(AST::Call {
-                        name: inner_inner_name,
-                        args: inner_inner_args,
-                        line: inner_inner_line,
-                    }, AST::Rparen)?;
+    fn parse_block(&mut self) -> Result<Vec<AST>, ParseError> {
+        self.nested(Self::parse_block_body)
+    }
 
-                    inner_args.push(new_call);
+    fn parse_block_body(&mut self) -> Result<Vec<AST>, ParseError> {
+        let open = self.expect(Token::LBracket, "'{'")?;
 
-                    args.push(AST::Call {
-                        name: inner_name,
-                        args: inner_args,
-                        line: inner_line,
-                    });
-                }
+        // newlines separate statements again inside a block, even if it is inside parentheses
+        let paren_depth = std::mem::replace(&mut self.paren_depth, 0);
+        let mut body = Vec::new();
 
-                AST::PropertyCall { object, property, args: inner_inner_args, line: inner_inner_line } => {
-                    let new_call = handle_nested_arguments(AST::PropertyCall {
-                        object,
-                        property,
-                        args: inner_inner_args,
-                        line: inner_inner_line,
-                    }, AST::Rparen)?;
+        loop {
+            while self.eat(Token::Semicolon) {}
 
-                    inner_args.push(new_call);
+            match self.peek() {
+                Some(Token::RBracket) => {
+                    self.pos += 1;
+                    self.paren_depth = paren_depth;
 
-                    args.push(AST::Call {
-                        name: inner_name,
-                        args: inner_args,
-                        line: inner_line,
-                    });
+                    return Ok(body);
                 }
 
-                _ => {
-                    inner_args.push(last_inner);
-
-                    let new_call = handle_nested_arguments(AST::Call {
-                        name: inner_name,
-                        args: inner_args,
-                        line: inner_line,
-                    }, AST::Rparen)?;
+                None => {
+                    return Err(self.error(format!("Expected '}}' to close the block opened on line {}", open.span.line)));
+                }
 
-                    args.push(new_call);
+                _ => {
+                    body.push(self.parse_statement()?);
+                    self.end_statement()?;
                 }
             }
         }
+    }
 
-        (AST::PropertyCall { object, property, args: mut inner_args, line: inner_line }, AST::Rparen) => {
-            let last_inner = inner_args.pop().unwrap_or(AST::Null);
+    fn parse_loop_body(&mut self) -> Result<Vec<AST>, ParseError> {
+        self.loop_depth += 1;
+        let body = self.parse_block();
+        self.loop_depth -= 1;
 
-            match last_inner {
-                AST::Rparen => {
-                    args.push(AST::PropertyCall {
-                        object,
-                        property,
-                        args: inner_args,
-                        line: inner_line,
-                    });
+        body
+    }
 
-                    args.push(AST::Rparen);
-                }
+    fn parse_statement(&mut self) -> Result<AST, ParseError> {
+        let start = self.start();
 
-                AST::Call { name: inner_inner_name, args: inner_inner_args, line: inner_inner_line } => {
-                    let new_call = handle_nested_arguments(AST::Call {
-                        name: inner_inner_name,
-                        args: inner_inner_args,
-                        line: inner_inner_line,
-                    }, AST::Rparen)?;
+        match self.peek() {
+            Some(Token::Let) => {
+                self.pos += 1;
 
-                    inner_args.push(new_call);
+                let name = self.expect(Token::Identifer, "a variable name after 'let'")?.text;
 
-                    args.push(AST::PropertyCall {
-                        object,
-                        property,
-                        args: inner_args,
-                        line: inner_line,
-                    });
-                }
+                let value = if self.eat(Token::Assign) {
+                    self.parse_expression()?
+                } else {
+                    AST::Null
+                };
+
+                Ok(AST::LetDeclaration {
+                    name: Some(name),
+                    value: Box::new(value),
+                    span: self.span_from(start),
+                })
+            }
 
-                AST::PropertyCall { object: inner_object, property: inner_property, args: inner_inner_args, line: inner_inner_line } => {
-                    let new_call = handle_nested_arguments(AST::PropertyCall {
-                        object: inner_object,
-                        property: inner_property,
-                        args: inner_inner_args,
-                        line: inner_inner_line,
-                    }, AST::Rparen)?;
+            // `fn (` starts an anonymous function, which is an expression
+            Some(Token::Fn) if self.tokens.get(self.pos + 1).map(|lexeme| &lexeme.token) != Some(&Token::LParen) => {
+                self.pos += 1;
 
-                    inner_args.push(new_call);
+                let name = self.expect(Token::Identifer, "a function name after 'fn'")?.text;
+                let args = self.parse_params()?;
+                let body = self.parse_function_body()?;
 
-                    args.push(AST::PropertyCall {
-                        object,
-                        property,
-                        args: inner_args,
-                        line: inner_line,
-                    });
-                }
+                Ok(AST::Function {
+                    name,
+                    args,
+                    body,
+                    span: self.span_from(start),
+                })
+            }
 
-                _ => {
-                    inner_args.push(last_inner);
+            Some(Token::If) => {
+                self.parse_if()
+            }
 
-                    let new_call = handle_nested_arguments(AST::PropertyCall {
-                        object,
-                        property,
-                        args: inner_args,
-                        line: inner_line,
-                    }, AST::Rparen)?;
+            Some(Token::Else) => {
+                Err(self.error("Expected an if statement before 'else'"))
+            }
 
-                    args.push(new_call);
-                }
+            Some(Token::While) => {
+                self.pos += 1;
+
+                let condition = self.parse_condition()?;
+                let body = self.parse_loop_body()?;
+
+                Ok(AST::WhileLoop {
+                    condition: Box::new(condition),
+                    body,
+                    span: self.span_from(start),
+                })
             }
-        }
 
-        (AST::Call { name: inner_name, args: inner_args, line: inner_line }, _) => {
-            match arg {
-                AST::Rparen => {
-                    args.push(AST::Call {
-                        name: inner_name.clone(),
-                        args: inner_args,
-                        line: inner_line,
-                    });
-                }
+            Some(Token::For) => {
+                self.pos += 1;
 
-                _ => {
-                    match inner_args.clone().pop().unwrap_or(AST::Null) {
-                        AST::Rparen => {
-                            match arg {
-                                AST::Plus => {
-                                    args.push(AST::Addition {
-                                        left: Box::new(AST::Call {
-                                            name: inner_name.clone(),
-                                            args: inner_args,
-                                            line: inner_line,
-                                        }),
-                                        right: Box::new(AST::Null),
-                                        line,
-                                    });
-                                }
-
-                                AST::Minus => {
-                                    args.push(AST::Subtraction {
-                                        left: Box::new(AST::Call {
-                                            name: inner_name.clone(),
-                                            args: inner_args,
-                                            line: inner_line,
-                                        }),
-                                        right: Box::new(AST::Null),
-                                        line,
-                                    });
-                                }
-
-                                _ => {
-                                    args.push(AST::Call {
-                                        name: inner_name.clone(),
-                                        args: inner_args,
-                                        line: inner_line,
-                                    });
-
-                                    args.push(arg);
-                                }
-                            }
-                        }
-
-                        _ => {
-                            let new_call = handle_nested_arguments(AST::Call {
-                                name: inner_name,
-                                args: inner_args,
-                                line: inner_line,
-                            }, arg)?;
-
-                            args.push(new_call);
-                        }
-                    }
-                }
+                let variable = self.expect(Token::Identifer, "a variable name after 'for'")?.text;
+                self.expect(Token::In, "'in' after the for loop variable")?;
+
+                let iterable = self.parse_expression()?;
+                let body = self.parse_loop_body()?;
+
+                Ok(AST::ForLoop {
+                    variable,
+                    iterable: Box::new(iterable),
+                    body,
+                    span: self.span_from(start),
+                })
             }
-        }
 
-        (AST::PropertyCall { object, property, args: inner_args, line: inner_line }, _) => {
-            match arg {
-                AST::Rparen => {
-                    match arg {
-                        AST::Plus => {
-                            args.push(AST::Addition {
-                                left: Box::new(AST::PropertyCall {
-                                    object,
-                                    property,
-                                    args: inner_args,
-                                    line: inner_line,
-                                }),
-                                right: Box::new(AST::Null),
-                                line,
-                            });
-                        }
-
-                        AST::Minus => {
-                            args.push(AST::Subtraction {
-                                left: Box::new(AST::PropertyCall {
-                                    object,
-                                    property,
-                                    args: inner_args,
-                                    line: inner_line,
-                                }),
-                                right: Box::new(AST::Null),
-                                line,
-                            });
-                        }
-
-                        _ => {
-                            args.push(AST::PropertyCall {
-                                object,
-                                property,
-                                args: inner_args,
-                                line: inner_line,
-                            });
-
-                            args.push(arg);
-                        }
-                    }
+            Some(Token::Break) | Some(Token::Continue) => {
+                let keyword = self.next().unwrap();
+
+                if self.loop_depth == 0 {
+                    return Err(Self::error_at(format!("'{}' outside of a loop", keyword.text), keyword.span));
                 }
 
-                _ => {
-                    match inner_args.clone().pop().unwrap_or(AST::Null) {
-                        AST::Rparen => {
-                            args.push(AST::PropertyCall {
-                                object,
-                                property,
-                                args: inner_args,
-                                line: inner_line,
-                            });
-
-                            args.push(arg);
-                        }
-
-                        _ => {
-                            let new_call = handle_nested_arguments(AST::PropertyCall {
-                                object,
-                                property,
-                                args: inner_args,
-                                line: inner_line,
-                            }, arg)?;
-
-                            args.push(new_call);
-                        }
-                    }
+                if keyword.token == Token::Break {
+                    Ok(AST::Break { span: keyword.span })
+                } else {
+                    Ok(AST::Continue { span: keyword.span })
                 }
             }
-        }
 
-        (AST::PropertyAccess { object, property: _, line }, AST::Identifer(name)) => {
-            args.push(AST::PropertyAccess {
-                object,
-                property: Some(name.clone()),
-                line,
-            });
-        }
+            Some(Token::Try) => {
+                self.pos += 1;
 
-        (AST::PropertyAccess { object, property, line }, AST::Lparen) => {
-            args.push(AST::PropertyCall {
-                object,
-                property,
-                args: vec![],
-                line,
-            });
-        }
+                let body = self.parse_block()?;
 
-        (AST::Identifer(ident), AST::Lparen) => {
-            args.push(AST::Call { name: ident.clone(), args: vec![], line });
-        }
+                self.expect(Token::Catch, "'catch' after the try block")?;
+                let error_name = self.expect(Token::Identifer, "a variable name after 'catch'")?.text;
+                let catch_body = self.parse_block()?;
 
-        (AST::Identifer(ident), AST::Dot) => {
-            args.push(AST::PropertyAccess {
-                object: Some(ident.clone()),
-                property: None,
-                line,
-            });
-        }
+                Ok(AST::TryCatch {
+                    body,
+                    error_name,
+                    catch_body,
+                    span: self.span_from(start),
+                })
+            }
 
-        (AST::Null, _) => {
-            args.push(arg);
-        }
+            Some(Token::Throw) => {
+                self.pos += 1;
 
-        (val, AST::Minus) => {
-            if val == AST::Comma {
-                args.push(val);
+                let value = self.parse_expression()?;
 
-                args.push(AST::Subtraction {
-                    left: Box::new(AST::Null),
-                    right: Box::new(AST::Null),
-                    line,
-                });
-            } else {
-                args.push(AST::Subtraction {
-                    left: Box::new(val),
-                    right: Box::new(AST::Null),
-                    line,
-                });
+                Ok(AST::Throw {
+                    value: Box::new(value),
+                    span: self.span_from(start),
+                })
             }
-        }
 
-        (AST::Subtraction { left, right, line }, val) => {
-            if *right == AST::Null && val != AST::Comma {
-                args.push(AST::Subtraction {
-                    left,
-                    right: Box::new(val),
-                    line,
-                });
-            } else {
-                if last_arg != AST::Null {
-                    args.push(last_arg);
+            Some(Token::Return) => {
+                if self.function_depth == 0 {
+                    return Err(self.error("Unexpected return statement"));
                 }
 
-                args.push(arg);
+                self.pos += 1;
+
+                let value = match self.peek() {
+                    Some(Token::Semicolon) | Some(Token::RBracket) | None => AST::Null,
+                    _ if !self.on_same_line() => AST::Null,
+                    _ => self.parse_expression()?,
+                };
+
+                Ok(AST::Return {
+                    value: Box::new(value),
+                    span: self.span_from(start),
+                })
             }
-        }
 
-        (AST::Minus, _) => {
-            args.push(AST::Subtraction {
-                left: Box::new(AST::Null),
-                right: Box::new(arg),
-                line,
-            });
-        }
+            Some(Token::Import) => {
+                self.pos += 1;
 
-        (val, AST::Plus) => {
-            match val {
-                AST::Comma => {
-                    return Err(("Unexpected ',' before '+'".to_string(), line));
-                }
+                let file = match self.next() {
+                    Some(Lexeme { token: Token::String(parts), .. }) if plain_string(&parts).is_some() => plain_string(&parts).unwrap(),
 
-                AST::Call { name: call_name, args: call_args, line: call_line } => {
-                    let new_call = handle_nested_arguments(AST::Call {
-                        name: call_name,
-                        args: call_args,
-                        line: call_line,
-                    }, AST::Plus)?;
+                    _ => {
+                        self.pos -= 1;
+                        return Err(self.unexpected("a path after 'import'"));
+                    }
+                };
 
-                    args.push(new_call);
-                }
+                self.expect(Token::As, "'as' after the import path")?;
 
-                AST::PropertyCall { object, property, args: call_args, line: call_line } => {
-                    let new_call = handle_nested_arguments(AST::PropertyCall {
-                        object,
-                        property,
-                        args: call_args,
-                        line: call_line,
-                    }, AST::Plus)?;
+                let as_ = match self.next() {
+                    Some(Lexeme { token: Token::Identifer, text, .. }) => text,
+                    Some(Lexeme { token: Token::Star, .. }) => "*".to_string(),
 
-                    args.push(new_call);
+                    _ => {
+                        self.pos -= 1;
+                        return Err(self.unexpected("a name or '*' after 'as'"));
+                    }
+                };
+
+                Ok(AST::Import {
+                    file: Some(file),
+                    as_: Some(as_),
+                    span: self.span_from(start),
+                })
+            }
+
+            _ => {
+                let target = self.parse_expression()?;
+
+                let operator = match self.peek() {
+                    Some(token @ (Token::Assign | Token::PlusAssign | Token::MinusAssign)) if self.on_same_line() => token.clone(),
+                    _ => return Ok(target),
+                };
+
+                if !matches!(target, AST::Identifer(..) | AST::PropertyAccess { .. } | AST::Index { .. }) {
+                    return Err(self.error("Cannot assign to this expression"));
                 }
 
-                _ => {
-                    args.push(AST::Addition {
-                        left: Box::new(val),
-                        right: Box::new(AST::Null),
-                        line,
-                    });
+                self.pos += 1;
+
+                let target = Box::new(target);
+                let value = Box::new(self.parse_expression()?);
+                let span = self.span_from(start);
+
+                match operator {
+                    Token::PlusAssign => Ok(AST::AddAssign { target, value, span }),
+                    Token::MinusAssign => Ok(AST::SubtractAssign { target, value, span }),
+                    _ => Ok(AST::Assign { target, value, span }),
                 }
             }
         }
+    }
 
-        
-        (AST::Addition { left, right, line }, val) => {
-            if *right == AST::Null && arg != AST::Comma {
-                args.push(AST::Addition {
-                    left,
-                    right: Box::new(val),
-                    line,
-                });
-            } else {
-                if last_arg != AST::Null {
-                    args.push(last_arg);
-                }
+    fn parse_params(&mut self) -> Result<Vec<String>, ParseError> {
+        self.expect(Token::LParen, "'(' after the function name")?;
+
+        let mut params = Vec::new();
 
-                args.push(arg);
+        while !self.eat(Token::RParen) {
+            params.push(self.expect(Token::Identifer, "a parameter name")?.text);
+
+            if !self.eat(Token::Comma) {
+                self.expect(Token::RParen, "',' or ')' after a parameter")?;
+                break;
             }
         }
 
-        (AST::Plus, _) => {
-            return Err(("Unexpected '+' before value".to_string(), line));
-        }
+        Ok(params)
+    }
+
+    fn parse_function_body(&mut self) -> Result<Vec<AST>, ParseError> {
+        // loops outside of the function can't be broken out of from inside it
+        let loop_depth = std::mem::replace(&mut self.loop_depth, 0);
+        self.function_depth += 1;
+
+        let body = self.parse_block();
+
+        self.function_depth -= 1;
+        self.loop_depth = loop_depth;
+
+        body
+    }
+
+    fn parse_if(&mut self) -> Result<AST, ParseError> {
+        let start = self.start();
+        self.expect(Token::If, "'if'")?;
+
+        let condition = self.parse_condition()?;
+        let body = self.parse_block()?;
 
-        _ => {
-            if last_arg != AST::Null {
-                args.push(last_arg);
+        let else_body = if self.eat(Token::Else) {
+            if self.peek() == Some(&Token::If) {
+                Some(vec![self.parse_if()?])
+            } else {
+                Some(self.parse_block()?)
             }
+        } else {
+            None
+        };
+
+        Ok(AST::IfStatement {
+            condition: Box::new(condition),
+            body,
+            else_body,
+            span: self.span_from(start),
+        })
+    }
+
+    // a bare value as a condition checks that it isn't null or false
+    fn parse_condition(&mut self) -> Result<AST, ParseError> {
+        let start = self.start();
+        let condition = self.parse_expression()?;
 
-            args.push(arg);
+        match condition {
+            AST::Identifer(..) | AST::PropertyAccess { .. } | AST::Index { .. } => Ok(AST::Exists {
+                value: Box::new(condition),
+                span: self.span_from(start),
+            }),
+
+            _ => Ok(condition),
         }
     }
 
-    match last.clone() {
-        AST::Call { name, .. } => {
-            Ok(AST::Call {
-                name: name.clone(),
-                args,
-                line: line,
-            })
+    // binding power of infix operators, higher binds tighter
+    fn precedence(token: &Token) -> Option<u8> {
+        match token {
+            Token::Or => Some(1),
+            Token::And => Some(2),
+            Token::IsEqual | Token::IsUnequal | Token::LessThan | Token::GreaterThan | Token::LessThanOrEqual | Token::GreaterThanOrEqual => Some(3),
+            Token::Plus | Token::Minus => Some(4),
+            Token::Star | Token::Slash | Token::Percent => Some(5),
+            Token::Power => Some(7),
+            _ => None,
         }
+    }
 
-        AST::PropertyCall { object, property, .. } => {
-            Ok(AST::PropertyCall {
-                object: object.clone(),
-                property: property.clone(),
-                args,
-                line,
-            })
-        }
+    // prefix operators bind looser than `**`, so `-2 ** 2` is `-(2 ** 2)`
+    const UNARY_PRECEDENCE: u8 = 6;
 
-        _ => {
-            Ok(last)
-        }
+    fn parse_expression(&mut self) -> Result<AST, ParseError> {
+        self.parse_binary(0)
     }
-}
 
-pub fn clean_args(obj: AST) -> AST {
-    match obj {
-        AST::Call { name, args, line } => {
-            let mut new_args = Vec::new();
+    fn parse_binary(&mut self, min_precedence: u8) -> Result<AST, ParseError> {
+        self.nested(|parser| parser.parse_operators(min_precedence))
+    }
 
-            for arg in args {
-                match arg {
-                    AST::Rparen
-                    | AST::Comma => {}
+    fn parse_operators(&mut self, min_precedence: u8) -> Result<AST, ParseError> {
+        let start = self.start();
+        let mut left = self.parse_unary()?;
 
-                    _ => {
-                        new_args.push(clean_args(arg));
-                    }
-                }
+        loop {
+            // an operator on the next line starts a new statement, unless we are inside parentheses
+            if self.paren_depth == 0 && !self.on_same_line() {
+                return Ok(left);
             }
 
-            AST::Call {
-                name,
-                args: new_args,
-                line,
-            }
+            let token = match self.peek() {
+                Some(token) => token.clone(),
+                None => return Ok(left),
+            };
+
+            let precedence = match Self::precedence(&token) {
+                Some(precedence) if precedence > min_precedence => precedence,
+                _ => return Ok(left),
+            };
+
+            self.pos += 1;
+
+            // `**` is right associative, everything else is left associative
+            let right = match token {
+                Token::Power => self.parse_binary(precedence - 1)?,
+                _ => self.parse_binary(precedence)?,
+            };
+
+            left = Self::binary(token, left, right, self.span_from(start));
         }
+    }
 
-        AST::PropertyCall { object, property, args, line } => {
-            let mut new_args = Vec::new();
+    fn binary(token: Token, left: AST, right: AST, span: Span) -> AST {
+        let left = Box::new(left);
+        let right = Box::new(right);
+
+        match token {
+            Token::Or => AST::Or { left, right, span },
+            Token::And => AST::And { left, right, span },
+            Token::IsEqual => AST::IsEqual { left, right, span },
+            Token::IsUnequal => AST::IsUnequal { left, right, span },
+            Token::LessThan => AST::LessThan { left, right, span },
+            Token::GreaterThan => AST::GreaterThan { left, right, span },
+            Token::LessThanOrEqual => AST::LessThanOrEqual { left, right, span },
+            Token::GreaterThanOrEqual => AST::GreaterThanOrEqual { left, right, span },
+            Token::Plus => AST::Addition { left, right, span },
+            Token::Minus => AST::Subtraction { left, right, span },
+            Token::Star => AST::Multiplication { left, right, span },
+            Token::Slash => AST::Division { left, right, span },
+            Token::Percent => AST::Modulo { left, right, span },
+            _ => AST::Exponent { left, right, span },
+        }
+    }
 
-            for arg in args {
-                match arg {
-                    AST::Rparen
-                    | AST::Comma => {}
+    fn parse_unary(&mut self) -> Result<AST, ParseError> {
+        let start = self.start();
 
-                    _ => {
-                        new_args.push(clean_args(arg));
-                    }
-                }
-            }
+        if self.eat(Token::Not) {
+            let value = self.parse_binary(Self::UNARY_PRECEDENCE)?;
 
-            AST::PropertyCall {
-                object,
-                property,
-                args: new_args,
-                line,
-            }
+            return Ok(AST::Not { value: Box::new(value), span: self.span_from(start) });
         }
 
-        AST::IfStatement { condition, body, line } => {
-            let mut new_body = vec![];
+        if self.eat(Token::Minus) {
+            let value = self.parse_binary(Self::UNARY_PRECEDENCE)?;
 
-            for expr in body {
-                match expr {
-                    AST::RBracket => {}
+            return Ok(AST::Subtraction { left: Box::new(AST::Null), right: Box::new(value), span: self.span_from(start) });
+        }
 
-                    _ => {
-                        new_body.push(clean_args(expr));
-                    }
-                }
+        self.parse_postfix()
+    }
+
+    // `[index]`, `.property`, `.method(args)` and `(args)` after a value, chained as far as they go
+    // `[` and `(` have to be on the same line, so an array or parenthesized expression on the next line starts a new statement
+    fn parse_postfix(&mut self) -> Result<AST, ParseError> {
+        let start = self.start();
+        let mut value = self.parse_primary()?;
+
+        loop {
+            if self.peek() == Some(&Token::LSquareBracket) && self.on_same_line() {
+                self.pos += 1;
+                self.paren_depth += 1;
+
+                let index = self.parse_expression()?;
+                self.expect(Token::RSquareBracket, "']' after the index")?;
+
+                self.paren_depth -= 1;
+
+                value = AST::Index {
+                    object: Box::new(value),
+                    index: Box::new(index),
+                    span: self.span_from(start),
+                };
+
+                continue;
             }
 
-            AST::IfStatement {
-                condition,
-                body: new_body,
-                line,
+            // whatever came before is called, like `fns[0]()` or `(fn (x) { ... })(1)`
+            if self.peek() == Some(&Token::LParen) && self.on_same_line() {
+                let args = self.parse_args()?;
+
+                value = AST::CallExpression {
+                    callee: Box::new(value),
+                    args,
+                    span: self.span_from(start),
+                };
+
+                continue;
             }
-        }
 
-        AST::Function { name, args, body, line } => {
-            let mut new_body = vec![];
+            // property access can continue on the next line
+            if self.eat(Token::Dot) {
+                let property = self.expect(Token::Identifer, "a property name after '.'")?.text;
 
-            for expr in body {
-                match expr {
-                    AST::RBracket => {}
+                if self.peek() == Some(&Token::LParen) && self.on_same_line() {
+                    let args = self.parse_args()?;
 
-                    _ => {
-                        new_body.push(clean_args(expr));
-                    }
+                    value = AST::PropertyCall {
+                        object: Box::new(value),
+                        property,
+                        args,
+                        span: self.span_from(start),
+                    };
+                } else {
+                    value = AST::PropertyAccess {
+                        object: Box::new(value),
+                        property,
+                        span: self.span_from(start),
+                    };
                 }
-            }
 
-            AST::Function {
-                name,
-                args,
-                body: new_body,
-                line,
+                continue;
             }
-        }
 
-        AST::Addition { left, right, line } => {
-            AST::Addition {
-                left: Box::new(clean_args(*left)),
-                right: Box::new(clean_args(*right)),
-                line,
-            }
+            return Ok(value);
         }
+    }
+
+    // arguments can span multiple lines and end with a trailing comma
+    fn parse_args(&mut self) -> Result<Vec<AST>, ParseError> {
+        self.expect(Token::LParen, "'('")?;
+        self.paren_depth += 1;
+
+        let mut args = Vec::new();
 
-        AST::Subtraction { left, right, line } => {
-            AST::Subtraction {
-                left: Box::new(clean_args(*left)),
-                right: Box::new(clean_args(*right)),
-                line,
+        while !self.eat(Token::RParen) {
+            args.push(self.parse_expression()?);
+
+            if !self.eat(Token::Comma) {
+                self.expect(Token::RParen, "',' or ')' after an argument")?;
+                break;
             }
         }
 
-        _ => obj
+        self.paren_depth -= 1;
+
+        Ok(args)
     }
-}
 
-pub fn parse(input: &str, context: &mut HashMap<String, AST>) -> Result<(), (String, usize)> {
-    let verbose = std::env::args().collect::<Vec<String>>()
-                            .iter().any(|arg| arg == "--verbose");
+    // `[a, b]`, items can span multiple lines and end with a trailing comma
+    fn parse_array(&mut self) -> Result<Vec<AST>, ParseError> {
+        self.paren_depth += 1;
 
-    let mut ast = Vec::new();
-    let mut line_map = HashMap::new();
-    let mut current_line = 0;
-    let mut bodies_deep = 0;
-    let mut inside_multiline_comment = false;
+        let mut items = Vec::new();
 
-    for line in input.split("\n") {
-        current_line += 1;
+        while !self.eat(Token::RSquareBracket) {
+            items.push(self.parse_expression()?);
 
-        let mut lexer = Token::lexer(line);
+            if !self.eat(Token::Comma) {
+                self.expect(Token::RSquareBracket, "',' or ']' after an item")?;
+                break;
+            }
+        }
 
-        let mut temp_ast = Vec::new();
-        let mut body_starts = false;
+        self.paren_depth -= 1;
 
-        while let Some(token) = lexer.next() {
-            if inside_multiline_comment {
-                if token == Ok(Token::MultiLineCommentEnd) {
-                    inside_multiline_comment = false;
-                } else {
-                    continue;
-                }
-            }
+        Ok(items)
+    }
 
-            match token {
-                Ok(Token::MultiLineCommentStart) => {
-                    inside_multiline_comment = true;
-                }
+    // `{ key: value }`, keys are names or strings
+    fn parse_object(&mut self) -> Result<Vec<(String, AST)>, ParseError> {
+        self.paren_depth += 1;
 
-                Ok(Token::Import) => {
-                    temp_ast.push(AST::Import {
-                        file: None,
-                        as_: None,
-                        line: current_line,
-                    });
-                }
+        let mut properties = Vec::new();
 
-                Ok(Token::As) => {
-                    let value = temp_ast.pop().unwrap_or(AST::Null);
-
-                    match value {
-                        AST::Import { file, line, .. } => {
-                            if file.is_none() {
-                                return Err(("Expected a file before 'as'".to_string(), current_line));
-                            }
-
-                            temp_ast.push(AST::Import {
-                                file,
-                                as_: None,
-                                line,
-                            });
-                        }
-
-                        _ => {
-                            return Err(("Expected an import before 'as'".to_string(), current_line));
-                        }
-                    }
-                }
+        while !self.eat(Token::RBracket) {
+            let key = match self.next() {
+                Some(Lexeme { token: Token::Identifer, text, .. }) => text,
+                Some(Lexeme { token: Token::String(parts), .. }) if plain_string(&parts).is_some() => plain_string(&parts).unwrap(),
 
-                Ok(Token::Star) => {
-                    let value = temp_ast.pop().unwrap_or(AST::Null);
-
-                    match value {
-                        AST::Import { file, as_: _, line } => {
-                            if file.is_none() {
-                                return Err(("Expected a file before '*'".to_string(), current_line));
-                            }
-
-                            temp_ast.push(AST::Import {
-                                file,
-                                as_: Some("*".to_string()),
-                                line,
-                            });
-                        }
-
-                        _ => {
-                            return Err(("Expected an import before '*'".to_string(), current_line));
-                        }
-                    }
+                _ => {
+                    self.pos -= 1;
+                    return Err(self.unexpected("a property name"));
                 }
+            };
 
-                Ok(Token::Let) => {
-                    temp_ast.push(AST::LetDeclaration {
-                        name: None,
-                        value: Box::new(AST::Null),
-                        line: current_line,
-                    });
-                }
+            self.expect(Token::Colon, "':' after the property name")?;
+            properties.push((key, self.parse_expression()?));
 
-                Ok(Token::Fn) => {
-                    temp_ast.push(AST::Function {
-                        name: String::new(),
-                        args: Vec::new(),
-                        body: Vec::new(),
-                        line: current_line,
-                    });
-                }
+            if !self.eat(Token::Comma) {
+                self.expect(Token::RBracket, "',' or '}' after a property")?;
+                break;
+            }
+        }
 
-                Ok(Token::If) => {
-                    temp_ast.push(AST::IfStatement {
-                        condition: Box::new(AST::Null),
-                        body: Vec::new(),
-                        line: current_line,
-                    });
-                }
+        self.paren_depth -= 1;
 
-                Ok(Token::IsEqual) | Ok(Token::IsUnequal) | Ok(Token::LessThan) | Ok(Token::GreaterThan) | Ok(Token::LessThanOrEqual) | Ok(Token::GreaterThanOrEqual) => {
-                    let value = temp_ast.pop().unwrap_or(AST::Null);
-
-                    match value {
-                        AST::Null => {
-                            return Err(("Expected an value before comparison".to_string(), current_line));
-                        }
-
-                        _ => {
-                            match temp_ast.pop().unwrap_or(AST::Null) {
-                                AST::Null => {
-                                    return Err(("Expected an value before comparison".to_string(), current_line));
-                                }
-
-                                AST::IfStatement { mut condition, body, line } => {
-                                    condition = match token {
-                                        Ok(Token::IsEqual) => {
-                                            Box::new(AST::IsEqual {
-                                                left: Box::new(value),
-                                                right: Box::new(AST::Null),
-                                                line,
-                                            })
-                                        }
-
-                                        Ok(Token::IsUnequal) => {
-                                            Box::new(AST::IsUnequal {
-                                                left: Box::new(value),
-                                                right: Box::new(AST::Null),
-                                                line,
-                                            })
-                                        }
-
-                                        Ok(Token::LessThan) => {
-                                            Box::new(AST::LessThan { 
-                                                left: Box::new(value),
-                                                right: Box::new(AST::Null),
-                                                line,
-                                            })
-                                        }
-
-                                        Ok(Token::GreaterThan) => {
-                                            Box::new(AST::GreaterThan { 
-                                                left: Box::new(value),
-                                                right: Box::new(AST::Null),
-                                                line,
-                                            })
-                                        }
-
-                                        Ok(Token::LessThanOrEqual) => {
-                                            Box::new(AST::LessThanOrEqual { 
-                                                left: Box::new(value),
-                                                right: Box::new(AST::Null),
-                                                line,
-                                            })
-                                        }
-
-                                        Ok(Token::GreaterThanOrEqual) => {
-                                            Box::new(AST::GreaterThanOrEqual { 
-                                                left: Box::new(value),
-                                                right: Box::new(AST::Null),
-                                                line,
-                                            })
-                                        }
-
-                                        Ok(_) => {
-                                            return Err(("Expected a comparison operator".to_string(), current_line));
-                                        }
-
-                                        Err(_) => {
-                                            return Err(("Expected a comparison operator".to_string(), current_line));
-                                        }
-                                    };
-
-                                    temp_ast.push(AST::IfStatement {
-                                        condition,
-                                        body,
-                                        line,
-                                    });
-                                }
-
-                                _ => {
-                                    return Err(("Expected an if statement before comparison".to_string(), current_line));
-                                }
-                            }
-                        }
-                    }
-                }
+        Ok(properties)
+    }
 
-                Ok(Token::Dot) => {
-                    let value = temp_ast.pop().unwrap_or(AST::Null);
-
-                    match value {
-                        AST::Identifer(name) => {
-                            temp_ast.push(AST::PropertyAccess {
-                                object: Some(name),
-                                property: None,
-                                line: current_line,
-                            });
-                        }
-
-                        AST::Function { name, args, body, line } => {
-                            temp_ast.push(AST::Function {
-                                name,
-                                args,
-                                body,
-                                line,
-                            });
-                        }
-
-                        AST::Call { name, args, line } => {
-                            if args.is_empty() {
-                                return Err(("Expected a property before '.' in function call".to_string(), current_line));
-                            } else {
-                                let new_call = handle_nested_arguments(AST::Call {
-                                    name,
-                                    args,
-                                    line,
-                                }, AST::Dot)?;
-
-                                temp_ast.push(new_call);
-                            }
-                        }
-
-                        AST::PropertyCall { object, property, args, line } => {
-                            if args.is_empty() {
-                                return Err(("Expected a property before '.' in property call".to_string(), current_line));
-                            } else {
-                                let new_call = handle_nested_arguments(AST::PropertyCall {
-                                    object,
-                                    property,
-                                    args,
-                                    line,
-                                }, AST::Dot)?;
-
-                                temp_ast.push(new_call);
-                            }
-                        }
-
-                        AST::LetDeclaration { name, value, line } => {
-                            let value = *value;
-
-                            match value  {
-                                AST::Identifer(ident) => {
-                                    temp_ast.push(AST::LetDeclaration {
-                                        name,
-                                        value: Box::new(AST::PropertyAccess {
-                                            object: Some(ident),
-                                            property: None,
-                                            line,
-                                        }),
-                                        line,
-                                    });
-                                }
-
-                                AST::Call { name: call_name, args: call_args, line: call_line } => {
-                                    let new_call = handle_nested_arguments(AST::Call {
-                                        name: call_name,
-                                        args: call_args,
-                                        line: call_line,
-                                    }, AST::Dot)?;
-
-                                    temp_ast.push(AST::LetDeclaration {
-                                        name: Some(lexer.slice().to_string()),
-                                        value: Box::new(new_call),
-                                        line,
-                                    });
-                                }
-
-                                AST::PropertyCall { object, property, args: call_args, line: call_line } => {
-                                    let new_call = handle_nested_arguments(AST::PropertyCall {
-                                        object,
-                                        property,
-                                        args: call_args,
-                                        line: call_line,
-                                    }, AST::Dot)?;
-
-                                    temp_ast.push(AST::LetDeclaration {
-                                        name: Some(lexer.slice().to_string()),
-                                        value: Box::new(new_call),
-                                        line,
-                                    });
-                                }
-
-                                _ => {
-                                    return Err(("Expected an identifer before '.' in let declaration".to_string(), current_line));
-                                }
-                            }
-                        }
-
-                        _ => {
-                            return Err((format!("Unexpected {:?} before '.'", value), current_line));
-                        }
-                    }
-                }
-    
-                Ok(Token::Identifer) => {
-                    let value = temp_ast.pop().unwrap_or(AST::Null);
-                    
-                    match value {
-                        AST::Import { file, as_: _, line } => {
-                            temp_ast.push(AST::Import {
-                                file,
-                                as_: Some(lexer.slice().to_string()),
-                                line,
-                            });
-                        }
-
-                        AST::LetDeclaration { name, value, line } => {
-                            if name.is_none() {
-                                temp_ast.push(AST::LetDeclaration {
-                                    name: Some(lexer.slice().to_string()),
-                                    value,
-                                    line,
-                                });
-                            } else {
-                                let value = *value;
-
-                                match value {
-                                    AST::Null => {
-                                        temp_ast.push(AST::LetDeclaration {
-                                            name,
-                                            value: Box::new(AST::Identifer(lexer.slice().to_string())),
-                                            line,
-                                        });
-                                    }
-
-                                    AST::Addition { left, right: _, line } => {
-                                        temp_ast.push(AST::LetDeclaration {
-                                            name,
-                                            value: Box::new(AST::Addition {
-                                                left,
-                                                right: Box::new(AST::Identifer(lexer.slice().to_string())),
-                                                line,
-                                            }),
-                                            line,
-                                        });
-                                    }
-
-                                    AST::Subtraction { left, right: _, line } => {
-                                        temp_ast.push(AST::LetDeclaration {
-                                            name,
-                                            value: Box::new(AST::Subtraction {
-                                                left,
-                                                right: Box::new(AST::Identifer(lexer.slice().to_string())),
-                                                line,
-                                            }),
-                                            line,
-                                        });
-                                    }
-
-                                    AST::PropertyAccess { object, property: _, line } => {
-                                        temp_ast.push(AST::LetDeclaration {
-                                            name,
-                                            value: Box::new(AST::PropertyAccess {
-                                                object,
-                                                property: Some(lexer.slice().to_string()),
-                                                line,
-                                            }),
-                                            line,
-                                        });
-                                    }
-
-                                    AST::Call { name: call_name, args, line } => {
-                                        let new_call = handle_nested_arguments(AST::Call {
-                                            name: call_name,
-                                            args,
-                                            line,
-                                        }, AST::Identifer(lexer.slice().to_string()))?;
-
-                                        temp_ast.push(AST::LetDeclaration {
-                                            name,
-                                            value: Box::new(new_call),
-                                            line,
-                                        });
-                                    }
-
-                                    AST::PropertyCall { object, property, args, line } => { 
-                                        let new_call = handle_nested_arguments(AST::PropertyCall {
-                                            object,
-                                            property,
-                                            args,
-                                            line,
-                                        }, AST::Identifer(lexer.slice().to_string()))?;
-
-                                        temp_ast.push(AST::LetDeclaration {
-                                            name,
-                                            value: Box::new(new_call),
-                                            line,
-                                        });
-                                    }
-
-                                    _ => {
-                                        return Err(("Unexpected identifier after let declaration".to_string(), current_line));
-                                    }
-                                }
-                            }
-                        }
-
-                        AST::Call { name, mut args, line } => {
-                            let value = args.pop().unwrap_or(AST::Null);
-
-                            match value {
-                                AST::PropertyAccess { object, property: _, line } => {
-                                    args.push(AST::PropertyAccess {
-                                        object,
-                                        property: Some(lexer.slice().to_string()),
-                                        line,
-                                    });
-                                }
-
-                                AST::Addition { left, right: _, line } => {
-                                    args.push(AST::Addition {
-                                        left,
-                                        right: Box::new(AST::Identifer(lexer.slice().to_string())),
-                                        line,
-                                    });
-                                }
-
-                                AST::Subtraction { left, right: _, line } => {
-                                    args.push(AST::Subtraction {
-                                        left,
-                                        right: Box::new(AST::Identifer(lexer.slice().to_string())),
-                                        line,
-                                    });
-                                }
-
-                                AST::Call { name: call_name, args: arg_args, line } => {
-                                    let new_call = handle_nested_arguments(AST::Call {
-                                        name: call_name,
-                                        args: arg_args,
-                                        line,
-                                    }, AST::Identifer(lexer.slice().to_string()))?;
-
-                                    args.push(new_call);
-                                }
-
-                                AST::PropertyCall { object, property, args: property_args, line } => {
-                                    let new_call = handle_nested_arguments(AST::PropertyCall {
-                                        object,
-                                        property,
-                                        args: property_args,
-                                        line,
-                                    }, AST::Identifer(lexer.slice().to_string()))?;
-
-                                    args.push(new_call);
-                                }
-
-                                AST::Null => {
-                                    args.push(AST::Identifer(lexer.slice().to_string()));
-                                }
-
-                                _ => {
-                                    args.push(value);
-
-                                    args.push(AST::Identifer(lexer.slice().to_string()));
-                                }
-                            }
-
-                            temp_ast.push(AST::Call {
-                                name,
-                                args,
-                                line,
-                            });
-                        }
-
-                        AST::PropertyCall { object, property, mut args, line } => {
-                            let arg = args.pop().unwrap_or(AST::Null);
-
-                            match arg {
-                                AST::PropertyAccess { object, property: _, line } => {
-                                    args.push(AST::PropertyAccess {
-                                        object,
-                                        property: Some(lexer.slice().to_string()),
-                                        line,
-                                    });
-                                }
-
-                                AST::Addition { left, right: _, line } => {
-                                    args.push(AST::Addition {
-                                        left,
-                                        right: Box::new(AST::Identifer(lexer.slice().to_string())),
-                                        line,
-                                    });
-                                }
-
-                                AST::Subtraction { left, right: _, line } => {
-                                    args.push(AST::Subtraction {
-                                        left,
-                                        right: Box::new(AST::Identifer(lexer.slice().to_string())),
-                                        line,
-                                    });
-                                }
-
-                                AST::Call { name: call_name, args: arg_args, line } => {
-                                    let new_call = handle_nested_arguments(AST::Call {
-                                        name: call_name,
-                                        args: arg_args,
-                                        line,
-                                    }, AST::Identifer(lexer.slice().to_string()))?;
-
-                                    args.push(new_call);
-                                }
-
-                                AST::PropertyCall { object, property, args: property_args, line } => {
-                                    let new_call = handle_nested_arguments(AST::PropertyCall {
-                                        object,
-                                        property,
-                                        args: property_args,
-                                        line,
-                                    }, AST::Identifer(lexer.slice().to_string()))?;
-
-                                    args.push(new_call);
-                                }
-
-                                AST::Null => {
-                                    args.push(AST::Identifer(lexer.slice().to_string()));
-                                }
-
-                                _ => {
-                                    args.push(arg);
-                                    args.push(AST::Identifer(lexer.slice().to_string()));
-                                }
-                            }
-
-                            temp_ast.push(AST::PropertyCall {
-                                object,
-                                property,
-                                args,
-                                line,
-                            });
-                        }
-
-                        AST::Function { name, mut args, body, line } => {
-                            if name.is_empty() {
-                                temp_ast.push(AST::Function {
-                                    name: lexer.slice().to_string(),
-                                    args,
-                                    body,
-                                    line,
-                                });
-                            } else {
-                                args.push(lexer.slice().to_string());
-
-                                temp_ast.push(AST::Function {
-                                    name,
-                                    args,
-                                    body,
-                                    line,
-                                });
-                            }
-                        }
-
-                        AST::PropertyAccess { object, property: _, line } => {
-                            temp_ast.push(AST::PropertyAccess {
-                                object,
-                                property: Some(lexer.slice().to_string()),
-                                line,
-                            });
-                        }
-
-                        AST::IfStatement { mut condition, body, line } => {
-                            let mut push_identifier = false;
-
-                            match *condition {
-                                AST::IsEqual { left, right: _, line } => {
-                                    condition = Box::new(AST::IsEqual {
-                                        left,
-                                        right: Box::new(AST::Identifer(lexer.slice().to_string())),
-                                        line,
-                                    });
-                                }
-
-                                AST::IsUnequal { left, right: _, line } => {
-                                    condition = Box::new(AST::IsUnequal {
-                                        left,
-                                        right: Box::new(AST::Identifer(lexer.slice().to_string())),
-                                        line,
-                                    });
-                                }
-
-                                AST::LessThan { left, right: _, line } => {
-                                    condition = Box::new(AST::LessThan {
-                                        left,
-                                        right: Box::new(AST::Identifer(lexer.slice().to_string())),
-                                        line,
-                                    });
-                                }
-
-                                AST::GreaterThan { left, right: _, line } => {
-                                    condition = Box::new(AST::GreaterThan {
-                                        left,
-                                        right: Box::new(AST::Identifer(lexer.slice().to_string())),
-                                        line,
-                                    });
-                                }
-
-                                AST::LessThanOrEqual { left, right: _, line } => {
-                                    condition = Box::new(AST::LessThanOrEqual {
-                                        left,
-                                        right: Box::new(AST::Identifer(lexer.slice().to_string())),
-                                        line,
-                                    });
-                                }
-
-                                AST::GreaterThanOrEqual { left, right: _, line } => {
-                                    condition = Box::new(AST::GreaterThanOrEqual {
-                                        left,
-                                        right: Box::new(AST::Identifer(lexer.slice().to_string())),
-                                        line,
-                                    });
-                                }
-
-                                _ => {
-                                    push_identifier = true;
-                                }
-                            }
-
-                            temp_ast.push(AST::IfStatement {
-                                condition,
-                                body,
-                                line,
-                            });
-
-                            if push_identifier {
-                                temp_ast.push(AST::Identifer(lexer.slice().to_string()));
-                            }
-                        }
-
-                        AST::Return { value, line } => {
-                            if let AST::Null = *value {
-                                temp_ast.push(AST::Return {
-                                    value: Box::new(AST::Identifer(lexer.slice().to_string())),
-                                    line,
-                                });
-                            } else {
-                                return Err(("Unexpected identifier after 'return'".to_string(), current_line));
-                            }
-                        }
-
-                        _ => {
-                            temp_ast.push(value);
-                            temp_ast.push(AST::Identifer(lexer.slice().to_string()));
-                        }
-                    }
-                }
-    
-                Ok(Token::Assign) => {
-                    if let Some(AST::LetDeclaration { name, value, line }) = temp_ast.pop() {                    
-                        if AST::Null == *value {
-                            temp_ast.push(AST::LetDeclaration {
-                                name,
-                                value: Box::new(AST::Null),
-                                line,
-                            });
-                        } else {
-                            return Err(("Unexpected '='".to_string(), current_line));
-                        }
-                    } else {
-                        return Err(("Expected a let declaration before '='".to_string(), current_line));
-                    }
-                }
+    // a string with `${}` in it becomes the text and expressions between, joined together when it is evaluated
+    fn parse_string(&self, parts: &[StringPart], lexeme: &Lexeme) -> Result<AST, ParseError> {
+        if let Some(text) = plain_string(parts) {
+            return Ok(AST::String(text, lexeme.span));
+        }
 
-                Ok(Token::Comma) => {
-                    let value = temp_ast.pop().unwrap_or(AST::Null);
-
-                    match value {
-                        AST::Call { name, args, line } => {
-                            let new_call = handle_nested_arguments(AST::Call {
-                                name,
-                                args,
-                                line,
-                            }, AST::Comma)?;
-
-                            temp_ast.push(new_call);
-                        }
-
-                        AST::PropertyCall { object, property, args, line } => {
-                            temp_ast.push(AST::PropertyCall {
-                                object,
-                                property,
-                                args,
-                                line,
-                            });
-                        }
-
-                        AST::Function { name, args, body, line } => {
-                            temp_ast.push(AST::Function {
-                                name,
-                                args,
-                                body,
-                                line,
-                            });
-                        }
-
-                        AST::LetDeclaration { name, value, line } => {
-                            temp_ast.push(AST::LetDeclaration {
-                                name,
-                                value,
-                                line,
-                            });
-                        }
-
-                        _ => {
-                            return Err((format!("Unexpected ',' after {:?}", value), current_line));
-                        }
-                    }
-                }
-    
-                Ok(Token::LParen) => {
-                    let value = temp_ast.pop().unwrap_or(AST::Null);
-
-                    match value {
-                        AST::Identifer(name) => {
-                            temp_ast.push(AST::Call {
-                                name,
-                                args: Vec::new(),
-                                line: current_line,
-                            });
-                        }
-
-                        AST::Function { name, args, body, line } => {
-                            temp_ast.push(AST::Function {
-                                name,
-                                args,
-                                body,
-                                line,
-                            });
-                        }
-
-                        AST::PropertyAccess { object, property, line } => {
-                            temp_ast.push(AST::PropertyCall {
-                                object,
-                                property,
-                                args: Vec::new(),
-                                line,
-                            });
-                        }
-
-                        AST::LetDeclaration { name, value, line } => {
-                            if let AST::Identifer(ident_name) = *value {
-                                temp_ast.push(AST::LetDeclaration {
-                                    name: name,
-                                    value: Box::new(AST::Call {
-                                        name: ident_name,
-                                        args: Vec::new(),
-                                        line,
-                                    }),
-                                    line,
-                                });
-                            } else if let AST::PropertyAccess { object, property, line } = *value {
-                                temp_ast.push(AST::LetDeclaration {
-                                    name: name,
-                                    value: Box::new(AST::PropertyCall {
-                                        object,
-                                        property,
-                                        args: Vec::new(),
-                                        line,
-                                    }),
-                                    line,
-                                });
-                            } else if let AST::Call { name: call_name, args: call_args, line: call_line } = *value {
-                                let new_call = handle_nested_arguments(AST::Call {
-                                    name: call_name,
-                                    args: call_args,
-                                    line: call_line,
-                                }, AST::Lparen)?;
-
-                                temp_ast.push(AST::LetDeclaration {
-                                    name,
-                                    value: Box::new(new_call),
-                                    line,
-                                });
-                            } else {
-                                return Err(("Expected a function call before '()'".to_string(), current_line));
-                            }
-                        }
-
-                        AST::Call { name, args, line } => {
-                            let new_call = handle_nested_arguments(AST::Call {
-                                name,
-                                args,
-                                line,
-                            }, AST::Lparen)?;
-
-                            temp_ast.push(new_call);
-                        }
-
-                        AST::PropertyCall { object, property, args, line } => {
-                            let new_call = handle_nested_arguments(AST::PropertyCall {
-                                object,
-                                property,
-                                args,
-                                line,
-                            }, AST::Lparen)?;
-
-                            temp_ast.push(new_call);
-                        }
-
-                        _ => {
-                            return Err((format!("Unexpected '()' after {:?}", value), current_line));
-                        }
-                    }
-                }
-    
-                Ok(Token::String) => {
-                    let value = temp_ast.pop().unwrap_or(AST::Null);
-    
-                    match value {
-                        AST::Import { file, as_, line } => {
-                            if file.is_none() {
-                                temp_ast.push(AST::Import {
-                                    file: Some(lexer.slice().to_string()),
-                                    as_,
-                                    line,
-                                });
-                            } else {
-                                return Err(("Expected a import state before the file path".to_string(), current_line));
-                            }
-                        }
-
-                        AST::Call { name, mut args, line } => {
-                            let arg = args.pop().unwrap_or(AST::Null);
-
-                            match arg {
-                                AST::Addition { left, right: _, line } => {
-                                    args.push(AST::Addition {
-                                        left,
-                                        right: Box::new(AST::String(lexer.slice().to_string())),
-                                        line,
-                                    });
-                                }
-
-                                AST::Subtraction { left, right: _, line } => {
-                                    args.push(AST::Subtraction {
-                                        left,
-                                        right: Box::new(AST::String(lexer.slice().to_string())),
-                                        line,
-                                    });
-                                }
-
-                                AST::Call { name: call_name, args: arg_args, line } => {
-                                    let new_call = handle_nested_arguments(AST::Call {
-                                        name: call_name,
-                                        args: arg_args,
-                                        line,
-                                    }, AST::String(lexer.slice().to_string()))?;
-
-                                    args.push(new_call);
-                                }
-
-                                AST::PropertyCall { object, property, args: arg_args, line } => {
-                                    let mut new_args = arg_args.clone();
-                                    new_args.push(AST::String(lexer.slice().to_string()));
-
-                                    args.push(AST::PropertyCall {
-                                        object,
-                                        property,
-                                        args: new_args,
-                                        line,
-                                    });
-                                }
-
-                                AST::Null => {
-                                    args.push(AST::String(lexer.slice().to_string()));
-                                }
-
-                                _ => {
-                                    args.push(arg);
-                                    args.push(AST::String(lexer.slice().to_string()));
-                                }
-                            }
-
-                            temp_ast.push(AST::Call {
-                                name,
-                                args,
-                                line,
-                            });
-                        }
-
-                        AST::PropertyCall { object, property, mut args, line } => {
-                            args.push(AST::String(lexer.slice().to_string()));
-
-                            temp_ast.push(AST::PropertyCall {
-                                object,
-                                property,
-                                args,
-                                line,
-                            });
-                        }
-
-                        AST::LetDeclaration { name, value, line } => {
-                            let val = *value;
-
-                            match val {
-                                AST::Addition { left, right: _, line } => {
-                                    temp_ast.push(AST::LetDeclaration {
-                                        name,
-                                        value: Box::new(AST::Addition {
-                                            left,
-                                            right: Box::new(AST::String(lexer.slice().to_string())),
-                                            line,
-                                        }),
-                                        line,
-                                    });
-                                }
-
-                                AST::Call { name: call_name, args, line } => {
-                                    let new_call = handle_nested_arguments(AST::Call {
-                                        name: call_name,
-                                        args,
-                                        line,
-                                    }, AST::String(lexer.slice().to_string()))?;
-
-                                    temp_ast.push(AST::LetDeclaration {
-                                        name,
-                                        value: Box::new(new_call),
-                                        line,
-                                    });
-                                }
-
-                                AST::PropertyCall { object, property, args, line } => {
-                                    let mut new_args = args.clone();
-                                    new_args.push(AST::String(lexer.slice().to_string()));
-
-                                    temp_ast.push(AST::LetDeclaration {
-                                        name,
-                                        value: Box::new(AST::PropertyCall {
-                                            object,
-                                            property,
-                                            args: new_args,
-                                            line,
-                                        }),
-                                        line,
-                                    });
-                                }
-                                
-
-                                _ => {
-                                    temp_ast.push(AST::LetDeclaration {
-                                        name,
-                                        value: Box::new(AST::String(lexer.slice().to_string())),
-                                        line,
-                                    });
-                                }
-                            }
-                        }
-
-                        AST::IfStatement { condition, body, line } => {
-                            if let AST::IsEqual { left, right, line } = condition.as_ref() {
-                                if let AST::Null = **right {
-                                    temp_ast.push(AST::IfStatement {
-                                        condition: Box::new(AST::IsEqual {
-                                            left: left.clone(),
-                                            right: Box::new(AST::String(lexer.slice().to_string())),
-                                            line: *line,
-                                        }),
-                                        body,
-                                        line: *line,
-                                    });
-                                } else {
-                                    return Err(("Expected a value before '=='".to_string(), current_line));
-                                }
-                            } else {
-                                if let AST::IsUnequal { left, right, line } = condition.as_ref() {
-                                    if let AST::Null = **right {
-                                        temp_ast.push(AST::IfStatement {
-                                            condition: Box::new(AST::IsUnequal {
-                                                left: left.clone(),
-                                                right: Box::new(AST::String(lexer.slice().to_string())),
-                                                line: *line,
-                                            }),
-                                            body,
-                                            line: *line,
-                                        });
-                                    } else {
-                                        return Err(("Expected a value before '!='".to_string(), current_line));
-                                    }
-                                } else {
-                                    temp_ast.push(AST::IfStatement {
-                                        condition,
-                                        body,
-                                        line,
-                                    });
-        
-                                    temp_ast.push(AST::String(lexer.slice().to_string()));
-                                }
-                            }
-                        }
-
-                        AST::Return { value, line } => {
-                            if let AST::Null = *value {
-                                temp_ast.push(AST::Return {
-                                    value: Box::new(AST::String(lexer.slice().to_string())),
-                                    line,
-                                });
-                            } else {
-                                return Err(("Unexpected string after 'return'".to_string(), current_line));
-                            }
-                        }
-
-                        _ => {
-                            return Err(("Expected a call or let declaration before a string".to_string(), current_line));
-                        }
-                    }
-                }
+        let mut values = Vec::new();
 
-                Ok(Token::Plus) => {
-                    let value = temp_ast.pop().unwrap_or(AST::Null);
-
-                    match value {
-                        AST::Number(n) => {
-                            temp_ast.push(AST::Addition {
-                                left: Box::new(AST::Number(n)),
-                                right: Box::new(AST::Null),
-                                line: current_line,
-                            });
-                        }
-
-                        AST::Float(f) => {
-                            temp_ast.push(AST::Addition {
-                                left: Box::new(AST::Float(f)),
-                                right: Box::new(AST::Null),
-                                line: current_line,
-                            });
-                        }
-
-                        AST::String(s) => {
-                            temp_ast.push(AST::Addition {
-                                left: Box::new(AST::String(s)),
-                                right: Box::new(AST::Null),
-                                line: current_line,
-                            });
-                        }
-
-                        AST::LetDeclaration { name, value, line } => {
-                            if let AST::Call { name: call_name, args, line } = *value {
-                                let new_call = handle_nested_arguments(AST::Call {
-                                    name: call_name,
-                                    args,
-                                    line,
-                                }, AST::Plus)?;
-
-                                temp_ast.push(AST::LetDeclaration {
-                                    name,
-                                    value: Box::new(new_call),
-                                    line,
-                                });
-                            } else if let AST::PropertyCall { object, property, args, line } = *value {
-                                let new_call = handle_nested_arguments(AST::PropertyCall {
-                                    object,
-                                    property,
-                                    args,
-                                    line,
-                                }, AST::Plus)?;
-
-                                temp_ast.push(AST::LetDeclaration {
-                                    name,
-                                    value: Box::new(new_call),
-                                    line,
-                                });
-                            } else {
-                                temp_ast.push(AST::LetDeclaration {
-                                    name,
-                                    value: Box::new(AST::Addition {
-                                        left: value,
-                                        right: Box::new(AST::Null),
-                                        line,
-                                    }),
-                                    line,
-                                });
-                            }
-                        }
-                        
-                        AST::Call { name, args, line } => {
-                            let new_call = handle_nested_arguments(AST::Call {
-                                name,
-                                args,
-                                line,
-                            }, AST::Plus)?;
-
-                            temp_ast.push(new_call);
-                        }
-
-                        AST::PropertyCall { object, property, args, line } => {
-                            let new_call = handle_nested_arguments(AST::PropertyCall {
-                                object,
-                                property,
-                                args,
-                                line,
-                            }, AST::Plus)?;
-
-                            temp_ast.push(new_call);
-                        }
-
-                        AST::IfStatement { mut condition, body, line } => {
-                            match *condition {
-                                AST::IsEqual { left, right, line } => {
-                                    condition = Box::new(AST::IsEqual {
-                                        left,
-                                        right: Box::new(AST::Addition { left: right, right: Box::new(AST::Null), line }),
-                                        line,
-                                    });
-                                }
-
-                                AST::IsUnequal { left, right, line } => {
-                                    condition = Box::new(AST::IsUnequal {
-                                        left,
-                                        right: Box::new(AST::Addition { left: right, right: Box::new(AST::Null), line }),
-                                        line,
-                                    });
-                                }
-
-                                AST::LessThan { left, right, line } => {
-                                    condition = Box::new(AST::LessThan {
-                                        left,
-                                        right: Box::new(AST::Addition { left: right, right: Box::new(AST::Null), line }),
-                                        line,
-                                    });
-                                }
-
-                                AST::GreaterThan { left, right, line } => {
-                                    condition = Box::new(AST::GreaterThan {
-                                        left,
-                                        right: Box::new(AST::Addition { left: right, right: Box::new(AST::Null), line }),
-                                        line,
-                                    });
-                                }
-
-                                AST::LessThanOrEqual { left, right, line } => {
-                                    condition = Box::new(AST::LessThanOrEqual {
-                                        left,
-                                        right: Box::new(AST::Addition { left: right, right: Box::new(AST::Null), line }),
-                                        line,
-                                    });
-                                }
-
-                                AST::GreaterThanOrEqual { left, right, line } => {
-                                    condition = Box::new(AST::GreaterThanOrEqual {
-                                        left,
-                                        right: Box::new(AST::Addition { left: right, right: Box::new(AST::Null), line }),
-                                        line,
-                                    });
-                                }
-
-                                _ => {
-                                    return Err(("Expected a value before '+'".to_string(), current_line));
-                                }
-                            }
-
-                            temp_ast.push(AST::IfStatement {
-                                condition,
-                                body,
-                                line,
-                            });
-                        }
-
-                        AST::Addition { left, right: _, line } => {
-                            temp_ast.push(AST::Addition {
-                                left,
-                                right: Box::new(AST::Null),
-                                line,
-                            });
-                        }
-
-                        AST::Subtraction { left, right: _, line } => {
-                            temp_ast.push(AST::Subtraction {
-                                left,
-                                right: Box::new(AST::Null),
-                                line,
-                            });
-                        }
-
-                        AST::Return { value, line } => {
-                            if let AST::Null = *value {
-                                return Err(("Unexpected '+' after 'return'".to_string(), current_line));
-                            } else {
-                                temp_ast.push(AST::Return {
-                                    value: Box::new(AST::Addition {
-                                        left: value,
-                                        right: Box::new(AST::Null),
-                                        line,
-                                    }),
-                                    line,
-                                });
-                            }
-                        }
-
-                        _ => {
-                            return Err((format!("Expected a number, float, string, or let declaration before '+', got {:?}", value), current_line));
-                        }
-                    }
-                }    
-
-                Ok(Token::Minus) => {
-                    let value = temp_ast.pop().unwrap_or(AST::Null);
-
-                    match value {
-                        AST::Number(n) => {
-                            temp_ast.push(AST::Subtraction {
-                                left: Box::new(AST::Number(n)),
-                                right: Box::new(AST::Null),
-                                line: current_line,
-                            });
-                        }
-
-                        AST::Float(f) => {
-                            temp_ast.push(AST::Subtraction {
-                                left: Box::new(AST::Float(f)),
-                                right: Box::new(AST::Null),
-                                line: current_line,
-                            });
-                        }
-
-                        AST::LetDeclaration { name, value, line } => {
-                            match *value {
-                                AST::Call { name: call_name, args, line } => {
-                                    let new_call = handle_nested_arguments(AST::Call {
-                                        name: call_name,
-                                        args,
-                                        line,
-                                    }, AST::Minus)?;
-
-                                    temp_ast.push(AST::LetDeclaration {
-                                        name,
-                                        value: Box::new(new_call),
-                                        line,
-                                    });
-                                }
-
-                                AST::PropertyCall { object, property, args, line } => {
-                                    let new_call = handle_nested_arguments(AST::PropertyCall {
-                                        object,
-                                        property,
-                                        args,
-                                        line,
-                                    }, AST::Minus)?;
-
-                                    temp_ast.push(AST::LetDeclaration {
-                                        name,
-                                        value: Box::new(new_call),
-                                        line,
-                                    });
-                                }
-
-                                _ => {
-                                    temp_ast.push(AST::LetDeclaration {
-                                        name,
-                                        value: Box::new(AST::Subtraction {
-                                            left: value,
-                                            right: Box::new(AST::Null),
-                                            line,
-                                        }),
-                                        line,
-                                    });
-                                }
-                            }
-                        }
-
-                        AST::Call { name, args, line } => {
-                            let new_call = handle_nested_arguments(AST::Call {
-                                name,
-                                args,
-                                line,
-                            }, AST::Minus)?;
-
-                            temp_ast.push(new_call);
-                        }
-
-                        AST::PropertyCall { object, property, args, line } => {
-                            let new_call = handle_nested_arguments(AST::PropertyCall {
-                                object,
-                                property,
-                                args,
-                                line,
-                            }, AST::Minus)?;
-
-                            temp_ast.push(new_call);
-                        }
-
-                        AST::IfStatement { mut condition, body, line } => {
-                            let mut push_sub = false;
-
-                            match *condition {
-                                AST::IsEqual { left, right, line } => {
-                                    condition = Box::new(AST::IsEqual {
-                                        left,
-                                        right: Box::new(AST::Subtraction { left: right, right: Box::new(AST::Null), line }),
-                                        line,
-                                    });
-                                }
-
-                                AST::IsUnequal { left, right, line } => {
-                                    condition = Box::new(AST::IsUnequal {
-                                        left,
-                                        right: Box::new(AST::Subtraction { left: right, right: Box::new(AST::Null), line }),
-                                        line,
-                                    });
-                                }
-
-                                AST::LessThan { left, right, line } => {
-                                    condition = Box::new(AST::LessThan {
-                                        left,
-                                        right: Box::new(AST::Subtraction { left: right, right: Box::new(AST::Null), line }),
-                                        line,
-                                    });
-                                }
-
-                                AST::GreaterThan { left, right, line } => {
-                                    condition = Box::new(AST::GreaterThan {
-                                        left,
-                                        right: Box::new(AST::Subtraction { left: right, right: Box::new(AST::Null), line }),
-                                        line,
-                                    });
-                                }
-
-                                AST::LessThanOrEqual { left, right, line } => {
-                                    condition = Box::new(AST::LessThanOrEqual {
-                                        left,
-                                        right: Box::new(AST::Subtraction { left: right, right: Box::new(AST::Null), line }),
-                                        line,
-                                    });
-                                }
-
-                                AST::GreaterThanOrEqual { left, right, line } => {
-                                    condition = Box::new(AST::GreaterThanOrEqual {
-                                        left,
-                                        right: Box::new(AST::Subtraction { left: right, right: Box::new(AST::Null), line }),
-                                        line,
-                                    });
-                                }
-
-                                _ => {
-                                    push_sub = true;
-                                }
-                            }
-
-                            temp_ast.push(AST::IfStatement {
-                                condition,
-                                body,
-                                line,
-                            });
-
-                            if push_sub {
-                                temp_ast.push(AST::Subtraction {
-                                    left: Box::new(AST::Null),
-                                    right: Box::new(AST::Null),
-                                    line,
-                                });
-                            }
-                        }
-
-                        AST::Identifer(name) => {
-                            temp_ast.push(AST::Subtraction {
-                                left: Box::new(AST::Identifer(name)),
-                                right: Box::new(AST::Null),
-                                line: current_line,
-                            });
-                        }
-
-                        AST::Subtraction { left, right: _, line } => {
-                            temp_ast.push(AST::Subtraction {
-                                left,
-                                right: Box::new(AST::Null),
-                                line,
-                            });
-                        }
-
-                        AST::Addition { left, right: _, line } => {
-                            temp_ast.push(AST::Addition {
-                                left,
-                                right: Box::new(AST::Null),
-                                line,
-                            });
-                        }
-
-                        AST::Return { value, line } => {
-                            temp_ast.push(AST::Return {
-                                value: Box::new(AST::Subtraction {
-                                    left: value,
-                                    right: Box::new(AST::Null),
-                                    line,
-                                }),
-                                line,
-                            });
-                        }
-
-                        _ => {
-                            return Err((format!("Expected a number, float, let or variable declaration before '-', got {:?}", value), current_line));
-                        }
-                    }
+        for part in parts {
+            match part {
+                StringPart::Text(text) => {
+                    values.push(AST::String(text.clone(), lexeme.span));
                 }
 
-                Ok(Token::Number(n)) => {
-                    let value = temp_ast.pop().unwrap_or(AST::Null);
-
-                    match value {
-                        AST::Call { name, args, line } => {
-                            let new_call = handle_nested_arguments(AST::Call {
-                                name,
-                                args,
-                                line,
-                            }, AST::Number(n))?;
-
-                            temp_ast.push(new_call);
-                        }
-
-                        AST::PropertyCall { object, property, args, line } => {
-                            let new_call = handle_nested_arguments(AST::PropertyCall {
-                                object,
-                                property,
-                                args,
-                                line,
-                            }, AST::Number(n))?;
-
-                            temp_ast.push(new_call);
-                        }
-
-                        AST::LetDeclaration { name, value, line } => {
-                            if let AST::Addition { left, right: _, line } = *value {
-                                temp_ast.push(AST::LetDeclaration {
-                                    name,
-                                    value: Box::new(AST::Addition {
-                                        left,
-                                        right: Box::new(AST::Number(n)),
-                                        line,
-                                    }),
-                                    line,
-                                });
-                            } else if let AST::Subtraction { left, right: _, line } = *value {
-                                temp_ast.push(
-                                    AST::LetDeclaration {
-                                        name,
-                                        value: Box::new(AST::Subtraction {
-                                            left,
-                                            right: Box::new(AST::Number(n)),
-                                            line,
-                                        }),
-                                        line,
-                                    }
-                                );
-                            } else if let AST::Call { name: call_name, args, line } = *value {
-                                let new_call = handle_nested_arguments(AST::Call {
-                                    name: call_name,
-                                    args,
-                                    line,
-                                }, AST::Number(n))?;
-
-                                temp_ast.push(AST::LetDeclaration {
-                                    name,
-                                    value: Box::new(new_call),
-                                    line,
-                                });
-                            } else if let AST::PropertyCall { object, property, args, line } = *value {
-                                let new_call = handle_nested_arguments(AST::PropertyCall {
-                                    object,
-                                    property,
-                                    args,
-                                    line,
-                                }, AST::Number(n))?;
-
-                                temp_ast.push(AST::LetDeclaration {
-                                    name,
-                                    value: Box::new(new_call),
-                                    line,
-                                });
-                            } else {
-                                temp_ast.push(AST::LetDeclaration {
-                                    name,
-                                    value: Box::new(AST::Number(n)),
-                                    line,
-                                });
-                            }
-                        }
-
-                        AST::Addition { left, right, line } => {
-                            if let AST::Null = *right {
-                                temp_ast.push(AST::Addition {
-                                    left,
-                                    right: Box::new(AST::Number(n)),
-                                    line,
-                                });
-                            } else {
-                                return Err(("Expected a value before '+'".to_string(), current_line));
-                            }
-                        }
-
-                        AST::Subtraction { left, right: _, line } => {
-                            temp_ast.push(AST::Subtraction {
-                                left,
-                                right: Box::new(AST::Number(n)),
-                                line,
-                            });
-                        }
-
-                        AST::IfStatement { mut condition, body, line } => { 
-                            let mut right;
-                            let left;
-
-                            match *condition.clone() {
-                                AST::IsEqual { left: c_l, right: c_r, line: _ } => {
-                                    left = c_l;
-                                    right = c_r;
-                                }
-
-                                AST::IsUnequal { left: c_l, right: c_r, line: _ } => {
-                                    left = c_l;
-                                    right = c_r;
-                                }
-
-                                AST::LessThan { left: c_l, right: c_r, line: _ } => {
-                                    left = c_l;
-                                    right = c_r;
-                                }
-
-                                AST::GreaterThan { left: c_l, right: c_r, line: _ } => {
-                                    left = c_l;
-                                    right = c_r;
-                                }
-
-                                AST::LessThanOrEqual { left: c_l, right: c_r, line: _ } => {
-                                    left = c_l;
-                                    right = c_r;
-                                }
-
-                                AST::GreaterThanOrEqual { left: c_l, right: c_r, line: _ } => {
-                                    left = c_l;
-                                    right = c_r;
-                                }
-
-                                _ => {
-                                    temp_ast.push(AST::IfStatement {
-                                        condition,
-                                        body,
-                                        line,
-                                    });
-                
-                                    temp_ast.push(AST::Number(n));
-
-                                    continue;
-                                }
-                            }
-
-                            match *right.clone() {
-                                AST::Null => {
-                                    right = Box::new(AST::Number(n));
-                                }
-
-                                AST::Addition { left: r_left, right: _, line } => {
-                                    right = Box::new(AST::Addition {
-                                        left: r_left,
-                                        right: Box::new(AST::Number(n)),
-                                        line,
-                                    });
-                                }
-
-                                AST::Subtraction { left: r_left, right: _, line } => {
-                                    right = Box::new(AST::Subtraction {
-                                        left: r_left,
-                                        right: Box::new(AST::Number(n)),
-                                        line,
-                                    });
-                                }
-
-                                _ => {
-                                    return Err(("Expected a value before comparison operator".to_string(), current_line));
-                                }
-                            }
-
-                            match *condition {
-                                AST::IsEqual { left: _, right: _, line }  => {
-                                    condition = Box::new(AST::IsEqual {
-                                        left,
-                                        right,
-                                        line,
-                                    });
-                                }
-
-                                AST::IsUnequal { left: _, right: _, line }  => {
-                                    condition = Box::new(AST::IsUnequal {
-                                        left,
-                                        right,
-                                        line,
-                                    });
-                                }
-
-                                AST::LessThan { left: _, right: _, line }  => {
-                                    condition = Box::new(AST::LessThan {
-                                        left,
-                                        right,
-                                        line,
-                                    });
-                                }
-
-                                AST::GreaterThan { left: _, right: _, line }  => {
-                                    condition = Box::new(AST::GreaterThan {
-                                        left,
-                                        right,
-                                        line,
-                                    });
-                                }
-
-                                AST::LessThanOrEqual { left: _, right: _, line }  => {
-                                    condition = Box::new(AST::LessThanOrEqual {
-                                        left,
-                                        right,
-                                        line,
-                                    });
-                                }
-
-                                AST::GreaterThanOrEqual { left: _, right: _, line }  => {
-                                    condition = Box::new(AST::GreaterThanOrEqual {
-                                        left,
-                                        right,
-                                        line,
-                                    });
-                                }
-
-                                _ => {
-                                    continue;
-                                }
-                            }
-
-                            temp_ast.push(AST::IfStatement {
-                                condition,
-                                body,
-                                line,
-                            });
-                        }
-
-                        AST::Return { value, line } => {
-                            if let AST::Null = *value {
-                                temp_ast.push(AST::Return {
-                                    value: Box::new(AST::Number(n)),
-                                    line,
-                                });
-                            } else if let AST::Addition { left, right: _, line } = *value {
-                                temp_ast.push(AST::Return {
-                                    value: Box::new(AST::Addition {
-                                        left,
-                                        right: Box::new(AST::Number(n)),
-                                        line,
-                                    }),
-                                    line,
-                                });
-                            } else if let AST::Subtraction { left, right: _, line } = *value {
-                                temp_ast.push(AST::Return {
-                                    value: Box::new(AST::Subtraction {
-                                        left,
-                                        right: Box::new(AST::Number(n)),
-                                        line,
-                                    }),
-                                    line,
-                                });
-                            } else {
-                                return Err(("Unexpected number after 'return'".to_string(), current_line));
-                            }
-                        }
-
-                        _ => {
-                            return Err((format!("Expected a call or let declaration before a number, got {:?}", value), current_line));
-                        }
-                    }
-                }
-    
-                Ok(Token::Boolean) => {
-                    let value = temp_ast.pop().unwrap_or(AST::Null);
-
-                    match value {
-                        AST::Call { name, mut args, line } => {
-                            let last_arg = args.pop().unwrap_or(AST::Null);
-
-                            match last_arg {
-                                AST::Call { name: call_name, args: arg_args, line } => {
-                                    let mut new_arg_args = arg_args.clone();
-                                    new_arg_args.push(AST::Boolean(lexer.slice() == "true"));
-
-                                    args.push(AST::Call {
-                                        name: call_name,
-                                        args: new_arg_args,
-                                        line,
-                                    });
-
-                                    temp_ast.push(AST::Call {
-                                        name,
-                                        args,
-                                        line,
-                                    });
-                                }
-
-                                _ => {
-                                    args.push(AST::Boolean(lexer.slice() == "true"));
-
-                                    temp_ast.push(AST::Call {
-                                        name,
-                                        args,
-                                        line,
-                                    });
-                                }
-                            }
-                        }
-
-                        AST::PropertyCall { object, property, args, line } => {
-                            let mut args = args.clone();
-
-                            args.push(AST::Boolean(lexer.slice() == "true"));
-
-                            temp_ast.push(AST::PropertyCall {
-                                object,
-                                property,
-                                args,
-                                line,
-                            });
-                        }
-
-                        AST::LetDeclaration { name, value: _, line } => {
-                            temp_ast.push(AST::LetDeclaration {
-                                name,
-                                value: Box::new(AST::Boolean(lexer.slice() == "true")),
-                                line,
-                            });
-                        }
-
-                        AST::IfStatement { condition, body, line } => {
-                            if let AST::IsEqual { left, right, line } = condition.as_ref() {
-                                if let AST::Null = **right {
-                                    temp_ast.push(AST::IfStatement {
-                                        condition: Box::new(AST::IsEqual {
-                                            left: left.clone(),
-                                            right: Box::new(AST::Boolean(lexer.slice() == "true")),
-                                            line: *line,
-                                        }),
-                                        body,
-                                        line: *line,
-                                    });
-                                } else {
-                                    return Err(("Expected a value before '=='".to_string(), current_line));
-                                }
-                            } else {
-                                if let AST::IsUnequal { left, right, line } = condition.as_ref() {
-                                    if let AST::Null = **right {
-                                        temp_ast.push(AST::IfStatement {
-                                            condition: Box::new(AST::IsUnequal {
-                                                left: left.clone(),
-                                                right: Box::new(AST::Boolean(lexer.slice() == "true")),
-                                                line: *line,
-                                            }),
-                                            body,
-                                            line: *line,
-                                        });
-                                    } else {
-                                        return Err(("Expected a value before '!='".to_string(), current_line));
-                                    }
-                                } else {
-                                    temp_ast.push(AST::IfStatement {
-                                        condition,
-                                        body,
-                                        line,
-                                    });
-        
-                                    temp_ast.push(AST::Boolean(lexer.slice() == "true"));
-                                }
-                            }
-                        }
-
-                        AST::Return { value, line } => {
-                            if let AST::Null = *value {
-                                temp_ast.push(AST::Return {
-                                    value: Box::new(AST::Boolean(lexer.slice() == "true")),
-                                    line,
-                                });
-                            } else {
-                                return Err(("Unexpected boolean after 'return'".to_string(), current_line));
-                            }
-                        }
-
-                        _ => {
-                            return Err(("Expected a call or let declaration before a boolean".to_string(), current_line));
-                        }
-                    }
-                }
+                StringPart::Code(code, offset) => {
+                    // where the code starts in the file, so errors in it point at the right place
+                    let before = &lexeme.text[..*offset];
 
-                Ok(Token::Return) => {
-                    if bodies_deep == 0 {
-                        return Err(("Unexpected return statement".to_string(), current_line));
-                    }
+                    let start = match before.rfind('\n') {
+                        Some(i) => (lexeme.span.line + before.matches('\n').count(), before[i + 1..].chars().count() + 1),
+                        None => (lexeme.span.line, lexeme.span.column + before.chars().count()),
+                    };
 
-                    temp_ast.push(AST::Return {
-                        value: Box::new(AST::Null),
-                        line: current_line,
-                    });
-                }
-    
-                Ok(Token::Float) => {
-                    let value = temp_ast.pop().unwrap_or(AST::Null);
-    
-                    match value {
-                        AST::Call { name, args, line } => {
-                            let new_call = handle_nested_arguments(AST::Call {
-                                name,
-                                args,
-                                line,
-                            }, AST::Float(lexer.slice().parse().unwrap()))?;
-
-                            temp_ast.push(new_call);
-                        }
-
-                        AST::PropertyCall { object, property, args, line } => {
-                            let new_call = handle_nested_arguments(AST::PropertyCall {
-                                object,
-                                property,
-                                args,
-                                line,
-                            }, AST::Float(lexer.slice().parse().unwrap()))?;
-
-                            temp_ast.push(new_call);
-                        }
-
-                        AST::LetDeclaration { name, value, line } => {
-                            if let AST::Addition { left, right: _, line } = *value {
-                                temp_ast.push(AST::LetDeclaration {
-                                    name,
-                                    value: Box::new(AST::Addition {
-                                        left,
-                                        right: Box::new(AST::Float(lexer.slice().parse().unwrap())),
-                                        line,
-                                    }),
-                                    line,
-                                });
-                            } else if let AST::Subtraction { left, right: _, line } = *value {
-                                temp_ast.push(
-                                    AST::LetDeclaration {
-                                        name,
-                                        value: Box::new(AST::Subtraction {
-                                            left,
-                                            right: Box::new(AST::Float(lexer.slice().parse().unwrap())),
-                                            line,
-                                        }),
-                                        line,
-                                    }
-                                );
-                            } else if let AST::Call { name: call_name, mut args, line } = *value {
-                                let arg = args.pop().unwrap_or(AST::Null);
-
-                                match arg.clone() {
-                                    AST::Addition { left, right, line } => {
-                                        if AST::Null == *right {
-                                            args.push(AST::Addition {
-                                                left,
-                                                right: Box::new(AST::Float(lexer.slice().parse().unwrap())),
-                                                line,
-                                            });
-                                        } else {
-                                            args.push(arg);
-                                            args.push(AST::Float(lexer.slice().parse().unwrap()));
-                                        }
-                                    }
-
-                                    AST::Subtraction { left, right, line } => {
-                                        if AST::Null == *right {
-                                            args.push(AST::Subtraction {
-                                                left,
-                                                right: Box::new(AST::Float(lexer.slice().parse().unwrap())),
-                                                line,
-                                            });
-                                        } else {
-                                            args.push(arg);
-                                            args.push(AST::Float(lexer.slice().parse().unwrap()));
-                                        }
-                                    }
-
-                                    AST::Call { name: call_name, args: arg_args, line } => {
-                                        let mut new_arg_args = arg_args.clone();
-                                        new_arg_args.push(AST::Float(lexer.slice().parse().unwrap()));
-
-                                        args.push(AST::Call {
-                                            name: call_name,
-                                            args: new_arg_args,
-                                            line,
-                                        });
-                                    }
-
-                                    AST::Null => {
-                                        args.push(AST::Float(lexer.slice().parse().unwrap()));
-                                    }
-
-                                    _ => {
-                                        args.push(arg);
-                                        args.push(AST::Float(lexer.slice().parse().unwrap()));
-                                    }
-                                }
-
-                                temp_ast.push(AST::LetDeclaration {
-                                    name,
-                                    value: Box::new(AST::Call {
-                                        name: call_name,
-                                        args,
-                                        line,
-                                    }),
-                                    line,
-                                });
-                            } else if let AST::PropertyCall { object, property, mut args, line } = *value {
-                                let arg = args.pop().unwrap_or(AST::Null);
-
-                                match arg.clone() {
-                                    AST::Addition { left, right, line } => {
-                                        if AST::Null == *right {
-                                            args.push(AST::Addition {
-                                                left,
-                                                right: Box::new(AST::Float(lexer.slice().parse().unwrap())),
-                                                line,
-                                            });
-                                        } else {
-                                            args.push(arg);
-                                            args.push(AST::Float(lexer.slice().parse().unwrap()));
-                                        }
-                                    }
-
-                                    AST::Subtraction { left, right, line } => {
-                                        if AST::Null == *right {
-                                            args.push(AST::Subtraction {
-                                                left,
-                                                right: Box::new(AST::Float(lexer.slice().parse().unwrap())),
-                                                line,
-                                            });
-                                        } else {
-                                            args.push(arg);
-                                            args.push(AST::Float(lexer.slice().parse().unwrap()));
-                                        }
-                                    }
-
-                                    AST::Call { name: call_name, args: arg_args, line } => {
-                                        let mut new_arg_args = arg_args.clone();
-                                        new_arg_args.push(AST::Float(lexer.slice().parse().unwrap()));
-
-                                        args.push(AST::Call {
-                                            name: call_name,
-                                            args: new_arg_args,
-                                            line,
-                                        });
-                                    }
-
-                                    _ => {
-                                        args.push(AST::Float(lexer.slice().parse().unwrap()));
-                                    }
-                                }
-
-                                temp_ast.push(AST::LetDeclaration {
-                                    name: name,
-                                    value: Box::new(AST::PropertyCall {
-                                        object,
-                                        property,
-                                        args,
-                                        line,
-                                    }),
-                                    line,
-                                });
-                            } else {
-                                temp_ast.push(AST::LetDeclaration {
-                                    name,
-                                    value: Box::new(AST::Float(lexer.slice().parse().unwrap())),
-                                    line,
-                                });
-                            }
-                        }
-
-                        AST::IfStatement { condition, body, line } => {
-                            temp_ast.push(AST::IfStatement {
-                                condition,
-                                body,
-                                line,
-                            });
-
-                            temp_ast.push(AST::Float(lexer.slice().parse().unwrap()));
-                        }
-
-                        AST::Return { value, line } => {
-                            if let AST::Null = *value {
-                                temp_ast.push(AST::Return {
-                                    value: Box::new(AST::Float(lexer.slice().parse().unwrap())),
-                                    line,
-                                });
-                            } else {
-                                return Err(("Unexpected float after 'return'".to_string(), current_line));
-                            }
-                        }
-
-                        AST::Addition { left, right, line } => {
-                            if let AST::Null = *right {
-                                temp_ast.push(AST::Addition {
-                                    left,
-                                    right: Box::new(AST::Float(lexer.slice().parse().unwrap())),
-                                    line,
-                                });
-                            } else {
-                                return Err(("Expected a value before '+'".to_string(), current_line));
-                            }
-                        }
-
-                        AST::Subtraction { left, right: _, line } => {
-                            temp_ast.push(AST::Subtraction {
-                                left,
-                                right: Box::new(AST::Float(lexer.slice().parse().unwrap())),
-                                line,
-                            });
-                        }
-
-                        _ => {
-                            return Err(("Expected a call or let declaration before a float".to_string(), current_line));
-                        }
-                    }
-                }
-    
-                Ok(Token::RParen) => {
-                    let value = temp_ast.pop().unwrap_or(AST::Null);
-
-                    match value {
-                        AST::Call { name, args, line } => {
-                            let new_call = handle_nested_arguments(
-                                AST::Call { name, args, line }, AST::Rparen
-                            )?;
-
-                            temp_ast.push(new_call);
-                        }
-
-                        AST::PropertyCall { object, property, args, line } => {
-                            let new_call = handle_nested_arguments(
-                                AST::PropertyCall { object, property, args, line }, AST::Rparen
-                            )?;
-
-                            temp_ast.push(new_call);
-                        }
-
-                        AST::Function { name, args, body, line } => {
-                            temp_ast.push(AST::Function {
-                                name,
-                                args,
-                                body,
-                                line,
-                            });
-                        }
-
-                        AST::LetDeclaration { name, value, line } => {
-                            temp_ast.push(AST::LetDeclaration {
-                                name,
-                                value,
-                                line,
-                            });
-                        }
-
-                        _ => {
-                            return Err((format!("Expected a call or property call before ')', got {:?}", value), current_line));
-                        }
-                    }
-                }
-    
-                Ok(Token::Semicolon) => {
-                    temp_ast.push(AST::Semicolon);
-                }
+                    let mut parser = Parser {
+                        tokens: tokenize(code, start)?,
+                        pos: 0,
+                        paren_depth: 1,
+                        nesting: self.nesting,
+                        loop_depth: 0,
+                        function_depth: 0,
+                    };
 
-                Ok(Token::LBracket) => {
-                    match temp_ast.pop().unwrap_or(AST::Null) {
-                        AST::Function { name, args, body, line } => {
-                            temp_ast.push(AST::Function {
-                                name,
-                                args,
-                                body,
-                                line,
-                            });
-
-                            bodies_deep += 1;
-                            body_starts = true;
-                        }
-
-                        AST::IfStatement { condition, body, line } => {
-                            temp_ast.push(AST::IfStatement {
-                                condition,
-                                body,
-                                line,
-                            });
-
-                            bodies_deep += 1;
-                            body_starts = true;
-                        }
-
-                        AST::Identifer(name) => {
-                            match temp_ast.pop() {
-                                Some(AST::IfStatement { condition: _, body, line }) => {
-                                    temp_ast.push(AST::IfStatement {
-                                        condition: Box::new(AST::Exists {
-                                            value: Box::new(AST::Identifer(name)),
-                                            line,
-                                        }),
-                                        body,
-                                        line,
-                                    });
-
-                                    bodies_deep += 1;
-                                    body_starts = true;
-                                }
-
-                                Some(_) => {
-                                    return Err(("Expected an if statement before '{'".to_string(), current_line));
-                                }
-
-                                None => {
-                                    return Err(("Expected an if statement before '{'".to_string(), current_line));
-                                }
-                            }
-                        }
-
-                        _ => {
-                            return Err(("Expected a function or if statement before '{'".to_string(), current_line));
-                        }
+                    if parser.peek().is_none() {
+                        return Err(Self::error_at("Expected an expression inside '${}'", lexeme.span));
                     }
-                }
 
-                Ok(Token::RBracket) => {
-                    let value = ast.pop().unwrap_or(AST::Null);
-
-                    match value {
-                        AST::Function { name, args, body, line } => {
-                            let new_obj = insert_right_bracket(AST::Function {
-                                name,
-                                args,
-                                body,
-                                line,
-                            });
-
-                            ast.push(new_obj);
-                        }
-
-                        AST::IfStatement { condition, body, line } => {
-                            let new_obj = insert_right_bracket(AST::IfStatement {
-                                condition,
-                                body,
-                                line,
-                            });
-
-                            ast.push(new_obj);
-                        }
-
-                        _ => {
-                            return Err(("Expected a function or if statement before '}'".to_string(), current_line));
-                        }
-                    }
+                    values.push(parser.parse_expression()?);
 
-                    bodies_deep -= 1;
-                }
-    
-                Err(err) => {
-                    match err {
-                        LexingError::UnexpectedToken => {
-                            return Err((format!("Unexpected token: {:?}", lexer.slice()), current_line));
-    
-                        }
-
-                        LexingError::InvalidInteger(str) => {
-                            return Err((format!("Could not parse integer: {:?}", str), current_line));
-                        }
+                    if parser.peek().is_some() {
+                        return Err(parser.error(format!("Unexpected {:?} inside '${{}}'", parser.tokens[parser.pos].text)));
                     }
                 }
-    
-                _ => {}
             }
-
-            line_map.insert(current_line, ast.clone());
-        }
-    
-        if verbose {
-            dbg!(&temp_ast);
         }
 
-        if bodies_deep > 0 && (!body_starts || bodies_deep > 1) {
-            ast = handle_nested_ast(ast, temp_ast, current_line)?;
-        } else {
-            ast.append(&mut temp_ast);
-        }
+        Ok(AST::InterpolatedString { parts: values, span: lexeme.span })
     }
 
-    if verbose {
-        dbg!(&ast);
-    }
+    fn parse_primary(&mut self) -> Result<AST, ParseError> {
+        let start = self.start();
 
-    for item in ast.clone() {
-        match item {
-            AST::Import { file, as_, line } => {
-                let result = eval(AST::Import { file, as_, line }, context);
+        let lexeme = match self.next() {
+            Some(lexeme) => lexeme,
 
-                if result.is_err() {
-                    return Err((result.err().unwrap(), line));
-                }
+            None => {
+                self.pos -= 1;
+                return Err(self.unexpected("an expression"));
             }
+        };
 
-            AST::LetDeclaration { name, value, line } => {
-                let cleaned_obj = clean_args(AST::LetDeclaration { name, value, line });
+        match lexeme.token {
+            Token::Number(n) => Ok(AST::Number(n, lexeme.span)),
+            Token::Float => Ok(AST::Float(lexeme.text.parse().unwrap(), lexeme.span)),
+            Token::String(ref parts) => self.parse_string(parts, &lexeme),
+            Token::Boolean => Ok(AST::Boolean(lexeme.text == "true", lexeme.span)),
+            Token::Null => Ok(AST::Null),
 
-                let result = eval(cleaned_obj, context);
+            Token::LParen => {
+                self.paren_depth += 1;
 
-                if result.is_err() {
-                    return Err((result.err().unwrap(), line));
-                }
-            }
+                let expr = self.parse_expression()?;
+                self.expect(Token::RParen, "')'")?;
 
-            AST::Call { name, args, line } => {
-                let cleaned_call = clean_args(AST::Call { name, args, line });
+                self.paren_depth -= 1;
 
-                let result = eval(cleaned_call, context);
-
-                if result.is_err() {
-                    return Err((result.err().unwrap(), line));
-                }
+                Ok(expr)
             }
 
-            AST::PropertyCall { object, property, args, line } => {
-                let cleaned_call = clean_args(AST::PropertyCall { object, property, args, line });
+            Token::LSquareBracket => {
+                let items = self.parse_array()?;
 
-                let result = eval(cleaned_call, context);
-
-                if result.is_err() {
-                    return Err((result.err().unwrap(), line));
-                }
+                Ok(AST::ArrayLiteral { items, span: self.span_from(start) })
             }
 
-            AST::Function { name, args, body, line } => {
-                let cleaned_obj = clean_args(AST::Function { name, args, body, line });
+            Token::LBracket => {
+                let properties = self.parse_object()?;
 
-                let result = eval(cleaned_obj, context);
-
-                if result.is_err() {
-                    return Err((result.err().unwrap(), line));
-                }
+                Ok(AST::ObjectLiteral { properties, span: self.span_from(start) })
             }
 
-            AST::IfStatement { condition, body, line } => {
-                let cleaned_obj = clean_args(AST::IfStatement { condition, body, line });
+            Token::Fn => {
+                let args = self.parse_params()?;
+                let body = self.parse_function_body()?;
 
-                let result = eval(cleaned_obj, context);
-
-                if result.is_err() {
-                    return Err((result.err().unwrap(), line));
-                }
+                Ok(AST::AnonymousFunction { args, body, span: self.span_from(start) })
             }
 
-            AST::Semicolon => {}
-            AST::Null => {}
+            Token::Identifer => {
+                let name = lexeme.text;
 
-            AST::Identifer(name) => {
-                let result = eval(AST::Identifer(name), context);
-                    
-                if result.is_err() {
-                    return Err((result.err().unwrap(), 1));
-                }
-
-                print_res(result.unwrap());
-            }
-
-            AST::PropertyAccess { object, property, line } => {
-                let result = eval(AST::PropertyAccess { object, property, line }, context);
+                if self.peek() == Some(&Token::LParen) && self.on_same_line() {
+                    let args = self.parse_args()?;
 
-                if result.is_err() {
-                    return Err((result.err().unwrap(), line));
+                    return Ok(AST::Call { name, args, span: self.span_from(start) });
                 }
 
-                print_res(result.unwrap());
+                Ok(AST::Identifer(name, lexeme.span))
             }
 
-            _ => {
-                if verbose {
-                    println!("I'm not sure what to do with a {:?}", item);
-                }
-            }
+            _ => Err(Self::error_at(format!("Unexpected {:?} in expression", lexeme.text), lexeme.span)),
         }
     }
-
-    Ok(())
 }
 
-fn print_res(res: AST) {
-    match res {
-        AST::String(v) => {
-            println!("{}", v);
-        }
-
-        AST::Number(v) => {
-            println!("{}", v);
-        }
+// the text of a string without `${}` in it
+fn plain_string(parts: &[StringPart]) -> Option<String> {
+    match parts {
+        [StringPart::Text(text)] => Some(text.clone()),
+        _ => None,
+    }
+}
 
-        AST::Float(v) => {
-            println!("{}", v);
-        }
+pub fn parse_program(input: &str) -> Result<Program, ParseError> {
+    Parser::new(input)?.parse_program()
+}
 
-        AST::Boolean(v) => {
-            println!("{}", v);
-        }
+// parses and runs the input in the given context
+pub fn parse(input: &str, context: &mut Environment) -> Result<(), ModuError> {
+    let program = parse_program(input)?;
 
-        _ => {}
-    }
+    interpreter::execute(&program, context)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::value::Value;
 
     #[test]
     fn let_str() {
         let mut context = crate::utils::create_context();
         parse("let x = \"test\"", &mut context).unwrap();
 
-        assert_eq!(context.get("x"), Some(&AST::String("test".to_string())));
+        assert_eq!(context.get("x"), Some(Value::Str("test".to_string())));
     }
 
 
@@ -3205,7 +960,7 @@ mod tests {
         let mut context = crate::utils::create_context();
         let result = parse("let x = y", &mut context);
 
-        assert_eq!(result, Err(("Variable y not found".to_string(), 1)));
+        assert_eq!(result.map_err(|e| (e.to_string(), e.line())), Err(("NameError: Variable y not found".to_string(), 1)));
     }
 
     #[test]
@@ -3318,4 +1073,379 @@ mod tests {
 
         assert_eq!(result, Ok(()));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn while_loop() {
+        let mut context = crate::utils::create_context();
+        let result = parse("let i = 0\nwhile i < 3 {\n let i = i + 1 \n}", &mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(context.get("i"), Some(Value::Int(3)));
+    }
+
+    #[test]
+    fn while_loop_break() {
+        let mut context = crate::utils::create_context();
+        let result = parse("let i = 0\nwhile i < 10 {\n let i = i + 1 \n if i == 4 {\n break \n}\n}", &mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(context.get("i"), Some(Value::Int(4)));
+    }
+
+    #[test]
+    fn for_loop_string() {
+        let mut context = crate::utils::create_context();
+        let result = parse("let s = \"\"\nfor c in \"abc\" {\n if c == \"b\" {\n continue \n}\n let s = s + c \n}", &mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(context.get("s"), Some(Value::Str("ac".to_string())));
+    }
+
+    #[test]
+    fn break_outside_loop() {
+        let mut context = crate::utils::create_context();
+        let result = parse("break", &mut context);
+
+        assert_eq!(result.map_err(|e| (e.to_string(), e.line())), Err(("SyntaxError: 'break' outside of a loop".to_string(), 1)));
+    }
+
+    #[test]
+    fn if_else() {
+        let mut context = crate::utils::create_context();
+        let result = parse("let a = 1\nif a == 2 {\n let b = 2 \n} else {\n let b = 3 \n}", &mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(context.get("b"), Some(Value::Int(3)));
+    }
+
+    #[test]
+    fn else_if_chain_in_function() {
+        let mut context = crate::utils::create_context();
+        let result = parse("fn f(n) {\n if n == 1 {\n return 10 \n} else if n == 2 {\n return 20 \n} else {\n return 30 \n}\n}\nlet a = f(1)\nlet b = f(2)\nlet c = f(3)", &mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(context.get("a"), Some(Value::Int(10)));
+        assert_eq!(context.get("b"), Some(Value::Int(20)));
+        assert_eq!(context.get("c"), Some(Value::Int(30)));
+    }
+
+    #[test]
+    fn else_without_if() {
+        let mut context = crate::utils::create_context();
+        let result = parse("let a = 1\nelse {\n}", &mut context);
+
+        assert_eq!(result.map_err(|e| (e.to_string(), e.line())), Err(("SyntaxError: Expected an if statement before 'else'".to_string(), 2)));
+    }
+
+    #[test]
+    fn logical_and_or() {
+        let mut context = crate::utils::create_context();
+        let result = parse("let a = 1\nlet b = 2\nlet r = 0\nif a == 1 && b == 3 || (b == 2 && a != 2) {\n let r = 1 \n}", &mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(context.get("r"), Some(Value::Int(1)));
+    }
+
+    #[test]
+    fn logical_short_circuit() {
+        let mut context = crate::utils::create_context();
+        let result = parse("let r = 0\nif true || undefined_fn() {\n let r = 1 \n}\nif false && undefined_fn() {\n let r = 2 \n}", &mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(context.get("r"), Some(Value::Int(1)));
+    }
+
+    #[test]
+    fn logical_not() {
+        let mut context = crate::utils::create_context();
+        let result = parse("let r = 0\nif !missing && !false {\n let r = 1 \n}", &mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(context.get("r"), Some(Value::Int(1)));
+    }
+
+    #[test]
+    fn arithmetic_precedence() {
+        let mut context = crate::utils::create_context();
+        let result = parse("let a = 1\nlet b = 2\nlet c = 4\nlet x = (a + b) * c / 2\nlet y = a + b * c - 10 % 4\nlet z = 2 ** 3 ** 2", &mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(context.get("x"), Some(Value::Int(6)));
+        assert_eq!(context.get("y"), Some(Value::Int(7)));
+        assert_eq!(context.get("z"), Some(Value::Int(512)));
+    }
+
+    #[test]
+    fn arithmetic_float_promotion() {
+        let mut context = crate::utils::create_context();
+        let result = parse("let a = 1.5 * 2\nlet b = 7 / 2\nlet c = -2 ** 2", &mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(context.get("a"), Some(Value::Float(3.0)));
+        assert_eq!(context.get("b"), Some(Value::Float(3.5)));
+        assert_eq!(context.get("c"), Some(Value::Int(-4)));
+    }
+
+    #[test]
+    fn division_by_zero() {
+        let mut context = crate::utils::create_context();
+        let result = parse("let a = 1 / 0", &mut context);
+
+        assert_eq!(result.map_err(|e| (e.to_string(), e.line())), Err(("RuntimeError: Division by zero".to_string(), 1)));
+    }
+
+    #[test]
+    fn multi_line_expressions() {
+        let mut context = crate::utils::create_context();
+        let result = parse("fn add(x, y) {\n return x + y \n}\nlet a = (1 +\n    2) *\n    3\nlet b = add(\n    a,\n    1,\n)", &mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(context.get("a"), Some(Value::Int(9)));
+        assert_eq!(context.get("b"), Some(Value::Int(10)));
+    }
+
+    #[test]
+    fn newline_ends_statement() {
+        let mut context = crate::utils::create_context();
+        let result = parse("let a = 1\n-1\nlet b = 2 let c = 3", &mut context);
+
+        assert_eq!(result.map_err(|e| (e.to_string(), e.line())), Err(("SyntaxError: Unexpected \"let\"".to_string(), 3)));
+    }
+
+    #[test]
+    fn spans() {
+        let ast = parse_program("let a = 1\n\nif a == 1 {\n    print(a)\n}").unwrap().body;
+
+        assert_eq!(ast[0].span(), Some(Span { line: 1, column: 1, end_line: 1, end_column: 10 }));
+        assert_eq!(ast[1].span(), Some(Span { line: 3, column: 1, end_line: 5, end_column: 2 }));
+
+        match &ast[1] {
+            AST::IfStatement { condition, body, .. } => {
+                assert_eq!(condition.span(), Some(Span { line: 3, column: 4, end_line: 3, end_column: 10 }));
+                assert_eq!(body[0].span(), Some(Span { line: 4, column: 5, end_line: 4, end_column: 13 }));
+            }
+
+            _ => panic!("Expected an if statement"),
+        }
+    }
+
+    #[test]
+    fn unclosed_block() {
+        let mut context = crate::utils::create_context();
+        let result = parse("fn a() {\n print(1)\n", &mut context);
+
+        assert_eq!(result.map_err(|e| (e.to_string(), e.line())), Err(("SyntaxError: Expected '}' to close the block opened on line 1".to_string(), 2)));
+    }
+
+    #[test]
+    fn try_catch() {
+        let mut context = crate::utils::create_context();
+
+        let result = parse("let r = 0\ntry {\n    let r = 1\n    missing()\n    let r = 2\n} catch err {\n    let kind = err.kind\n    let line = err.line\n}", &mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(context.get("r"), Some(Value::Int(1)));
+        assert_eq!(context.get("kind"), Some(Value::Str("NameError".to_string())));
+        assert_eq!(context.get("line"), Some(Value::Int(4)));
+    }
+
+    #[test]
+    fn throw_and_rethrow() {
+        let mut context = crate::utils::create_context();
+
+        let result = parse("fn f() {\n    throw \"bad\"\n}\ntry {\n    f()\n} catch e {\n    let message = e.message\n    let kind = e.kind\n}", &mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(context.get("message"), Some(Value::Str("bad".to_string())));
+        assert_eq!(context.get("kind"), Some(Value::Str("Error".to_string())));
+
+        let result = parse("try {\n    1 / 0\n} catch e {\n    throw e\n}", &mut context);
+
+        assert_eq!(result.map_err(|e| (e.to_string(), e.line())), Err(("RuntimeError: Division by zero".to_string(), 4)));
+    }
+
+    #[test]
+    fn try_without_catch() {
+        let mut context = crate::utils::create_context();
+
+        let result = parse("try {\n    print(1)\n}", &mut context);
+
+        assert_eq!(result.map_err(|e| e.to_string()), Err("SyntaxError: Expected 'catch' after the try block but reached the end of the file".to_string()));
+    }
+
+    #[test]
+    fn function_scope() {
+        let mut context = crate::utils::create_context();
+
+        let result = parse("let a = 1\nlet seen = 0\nfn f(x) {\n    let a = x\n    let b = 2\n    return a + b\n}\nlet r = f(10)", &mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(context.get("r"), Some(Value::Int(12)));
+        assert_eq!(context.get("a"), Some(Value::Int(1)));
+        assert_eq!(context.get("b"), None);
+        assert_eq!(context.get("x"), None);
+
+        // a function sees the globals, but not the variables of whoever called it
+        let result = parse("fn inner() {\n    return local\n}\nfn outer() {\n    let local = 5\n    return inner()\n}\nlet g = outer()\nfn read_global() {\n    return a\n}\nlet h = read_global()", &mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(context.get("g"), Some(Value::Null));
+        assert_eq!(context.get("h"), Some(Value::Int(1)));
+    }
+
+    #[test]
+    fn closures() {
+        let mut context = crate::utils::create_context();
+
+        let result = parse("fn make_adder(n) {\n    return fn (x) {\n        return x + n\n    }\n}\nlet add5 = make_adder(5)\nlet a = add5(10)\nlet twice = fn (f, x) { return f(f(x)) }\nlet b = twice(add5, 1)", &mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(context.get("a"), Some(Value::Int(15)));
+        assert_eq!(context.get("b"), Some(Value::Int(11)));
+        assert_eq!(context.get("n"), None);
+    }
+
+    #[test]
+    fn calling_any_expression() {
+        let mut context = crate::utils::create_context();
+
+        let result = parse("fn make_adder(n) {\n    return fn (x) { return x + n }\n}\nlet a = make_adder(5)(10)\nlet fns = [fn () { return 1 }]\nlet b = fns[0]()\nlet c = (fn (x) { return x * 2 })(4)", &mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(context.get("a"), Some(Value::Int(15)));
+        assert_eq!(context.get("b"), Some(Value::Int(1)));
+        assert_eq!(context.get("c"), Some(Value::Int(8)));
+
+        let result = parse("let n = 1\nlet d = [n][0]()", &mut context);
+        assert_eq!(result.map_err(|e| e.to_string()), Err("TypeError: value[...] is not a function".to_string()));
+    }
+
+    #[test]
+    fn nested_function_sees_enclosing_call() {
+        let mut context = crate::utils::create_context();
+
+        let result = parse("fn outer() {\n    let local = 5\n    fn inner() {\n        return local\n    }\n    return inner()\n}\nlet r = outer()", &mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(context.get("r"), Some(Value::Int(5)));
+    }
+
+    #[test]
+    fn literals_and_indexes() {
+        let mut context = crate::utils::create_context();
+
+        let result = parse("let a = [1, 2,\n    3,]\nlet first = a[0]\na[1] = 20\na[3] = 4\nlet o = { name: \"x\", \"age\": 3, tags: [\"a\"] }\no[\"age\"] = 4\no.tags[0] = \"b\"\nlet name = o[\"name\"]\nlet tag = o.tags[0]\nlet c = \"hey\"[1]", &mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(context.get("first"), Some(Value::Int(1)));
+        assert_eq!(context.get("name"), Some(Value::Str("x".to_string())));
+        assert_eq!(context.get("tag"), Some(Value::Str("b".to_string())));
+        assert_eq!(context.get("c"), Some(Value::Str("e".to_string())));
+        assert_eq!(context.get("a").map(|a| a.to_string()), Some("[1, 20, 3, 4]".to_string()));
+
+        match context.get("o") {
+            Some(Value::Map(properties)) => {
+                assert_eq!(properties.borrow().get("age"), Some(&Value::Int(4)));
+                assert_eq!(properties.borrow().len(), 3);
+            }
+
+            _ => panic!("Expected an object"),
+        }
+    }
+
+    #[test]
+    fn index_errors() {
+        let mut context = crate::utils::create_context();
+
+        let result = parse("let a = [1]\na[2] = 3", &mut context);
+        assert_eq!(result.map_err(|e| (e.to_string(), e.line())), Err(("RuntimeError: Index 2 is out of range for an array of length 1".to_string(), 2)));
+
+        let result = parse("let o = {}\nlet x = o[\"missing\"]", &mut context);
+        assert_eq!(result.map_err(|e| e.to_string()), Err("NameError: Property missing not found".to_string()));
+
+        let result = parse("let n = 1\nn + 1 = 2", &mut context);
+        assert_eq!(result.map_err(|e| e.to_string()), Err("SyntaxError: Cannot assign to this expression".to_string()));
+    }
+
+    #[test]
+    fn chained_properties() {
+        let mut context = crate::utils::create_context();
+
+        let result = parse("import \"json\" as json\nlet o = { a: { b: { c: 1 } }, items: [5, 6], child: [] }\nlet c = o.a.b.c\nlet first = o.items.at(0)\nlet x = json.parse(json.stringify({ x: [1, 2] })).get(\"x\").length\nlet n = [1, 2, 3].map(fn (x) { return x * 2 }).filter(fn (x) { return x > 2 }).length\nlet last = o.items\n    .at(1)\no.child.push(1)\nlet pushed = o[\"child\"].length", &mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(context.get("c"), Some(Value::Int(1)));
+        assert_eq!(context.get("first"), Some(Value::Int(5)));
+        assert_eq!(context.get("x"), Some(Value::Int(2)));
+        assert_eq!(context.get("n"), Some(Value::Int(2)));
+        assert_eq!(context.get("last"), Some(Value::Int(6)));
+        assert_eq!(context.get("pushed"), Some(Value::Int(1)));
+
+        let result = parse("let y = o.a.missing", &mut context);
+        assert_eq!(result.map_err(|e| e.to_string()), Err("NameError: Property missing not found in object o.a".to_string()));
+    }
+
+    #[test]
+    fn assignment() {
+        let mut context = crate::utils::create_context();
+
+        let result = parse("let i = 0\nlet s = \"a\"\nwhile i < 3 {\n    i += 1\n    s += i\n}\nlet o = { n: 10, list: [1] }\no.n -= 4\no.name = \"x\"\no.list[0] += 1\nlet total = 0\nfn count() {\n    total = total + 1\n}\ncount()\ncount()", &mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(context.get("i"), Some(Value::Int(3)));
+        assert_eq!(context.get("s"), Some(Value::Str("a123".to_string())));
+        assert_eq!(context.get("total"), Some(Value::Int(2)));
+        assert_eq!(context.get("o").map(|o| o.to_string().contains("\"n\": 6") && o.to_string().contains("\"name\": x")), Some(true));
+        assert_eq!(context.get("o").map(|o| o.to_string().contains("\"list\": [2]")), Some(true));
+
+        let result = parse("undeclared = 1", &mut context);
+        assert_eq!(result.map_err(|e| e.to_string()), Err("NameError: Variable undeclared not found".to_string()));
+
+        let result = parse("missing += 1", &mut context);
+        assert_eq!(result.map_err(|e| e.to_string()), Err("NameError: Variable missing not found".to_string()));
+
+        let result = parse("count() = 1", &mut context);
+        assert_eq!(result.map_err(|e| e.to_string()), Err("SyntaxError: Cannot assign to this expression".to_string()));
+    }
+
+    #[test]
+    fn string_interpolation() {
+        let mut context = crate::utils::create_context();
+
+        let result = parse("let name = \"Modu\"\nlet items = [1, 2]\nlet s = \"Hello ${name}, you have ${items.length + 1} items\"\nlet quoted = \"say \\\"${ \"hi\" }\\\"\"\nlet nested = 'a${ \"b${name}\" }c'\nlet empty = \"\"", &mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(context.get("s"), Some(Value::Str("Hello Modu, you have 3 items".to_string())));
+        assert_eq!(context.get("quoted"), Some(Value::Str("say \"hi\"".to_string())));
+        assert_eq!(context.get("nested"), Some(Value::Str("abModuc".to_string())));
+        assert_eq!(context.get("empty"), Some(Value::Str("".to_string())));
+
+        let result = parse("let a = 1\nlet s = \"x ${a +} y\"", &mut context);
+        assert_eq!(result.map_err(|e| (e.line(), e.info().span.map(|span| span.column))), Err((2, Some(17))));
+
+        let result = parse("let s = \"${}\"", &mut context);
+        assert_eq!(result.map_err(|e| e.to_string()), Err("SyntaxError: Expected an expression inside '${}'".to_string()));
+
+        let result = parse("let s = \"${1 2}\"", &mut context);
+        assert_eq!(result.map_err(|e| e.to_string()), Err("SyntaxError: Unexpected \"2\" inside '${}'".to_string()));
+    }
+
+    #[test]
+    fn nesting_limit() {
+        crate::eval::with_big_stack(|| {
+            let mut context = crate::utils::create_context();
+
+            let result = parse(&format!("let a = {}1{}", "[".repeat(100), "]".repeat(100)), &mut context);
+            assert_eq!(result, Ok(()));
+
+            let result = parse(&format!("let b = {}1{}", "(".repeat(1000), ")".repeat(1000)), &mut context);
+            assert_eq!(result.map_err(|e| e.to_string()), Err("SyntaxError: Nesting too deep".to_string()));
+
+            let result = parse(&format!("let c = {}1{}", "[".repeat(1000), "]".repeat(1000)), &mut context);
+            assert_eq!(result.map_err(|e| e.to_string()), Err("SyntaxError: Nesting too deep".to_string()));
+
+            let result = parse(&"if true {\n".repeat(1000), &mut context);
+            assert_eq!(result.map_err(|e| e.to_string()), Err("SyntaxError: Nesting too deep".to_string()));
+        });
+    }
+}
@@ -1,20 +1,64 @@
 use std::collections::HashMap;
 
-use crate::ast::AST;
+use crate::value::Value;
+use crate::environment::Environment;
 
 pub fn is_reserved(name: &str) -> bool {
     match name {
-        "let" | "fn" | "import" | "if" | "null" | "return" | "as" => true,
+        "let" | "fn" | "import" | "if" | "else" | "null" | "return" | "as"
+        | "while" | "for" | "in" | "break" | "continue" | "try" | "catch" | "throw" => true,
         _ => false,
     }
 }
 
-pub fn create_context() -> HashMap<String, AST> {
+// how many characters have to be added, removed, changed or swapped with the next one to turn `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b) = (a.chars().collect::<Vec<char>>(), b.chars().collect::<Vec<char>>());
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+
+            distances[i][j] = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distances[i][j] = distances[i][j].min(distances[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    distances[a.len()][b.len()]
+}
+
+// the name closest to `name`, if one is close enough to be a typo of it
+pub fn did_you_mean(name: &str, names: &[String]) -> Option<String> {
+    let max_distance = (name.chars().count() / 3).max(1);
+
+    names.iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(b.1)))
+        .map(|(_, candidate)| candidate.clone())
+}
+
+// the global scope every program starts with
+pub fn create_context() -> Environment {
     let mut context = HashMap::new();
 
     context.insert(
         "print".to_string(), 
-        AST::InternalFunction { 
+        Value::NativeFunction { 
             name: "print".to_string(), 
             args: vec!["__args__".to_string()], 
             call_fn: crate::internal::print
@@ -23,7 +67,7 @@ pub fn create_context() -> HashMap<String, AST> {
 
     context.insert(
         "input".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "input".to_string(),
             args: vec!["__args__".to_string()], 
             call_fn: crate::internal::input,
@@ -32,7 +76,7 @@ pub fn create_context() -> HashMap<String, AST> {
 
     context.insert(
         "int".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "int".to_string(),
             args: vec!["val".to_string()], 
             call_fn: crate::internal::int,
@@ -41,7 +85,7 @@ pub fn create_context() -> HashMap<String, AST> {
 
     context.insert(
         "float".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "float".to_string(),
             args: vec!["val".to_string()], 
             call_fn: crate::internal::float,
@@ -50,7 +94,7 @@ pub fn create_context() -> HashMap<String, AST> {
 
     context.insert(
         "str".to_string(),
-        AST::InternalFunction {
+        Value::NativeFunction {
             name: "str".to_string(),
             args: vec!["val".to_string()], 
             call_fn: crate::internal::str,
@@ -59,14 +103,14 @@ pub fn create_context() -> HashMap<String, AST> {
 
     context.insert(
         "exit".to_string(), 
-        AST::InternalFunction { 
+        Value::NativeFunction { 
             name: "exit".to_string(), 
             args: vec![], 
             call_fn: crate::internal::exit,
         }
     );
     
-    return context;
+    return Environment::from_map(context);
 }
 
 #[cfg(test)]
@@ -80,16 +124,30 @@ mod tests {
         assert_eq!(is_reserved("import"), true);
         assert_eq!(is_reserved("if"), true);
         assert_eq!(is_reserved("null"), true);
+        assert_eq!(is_reserved("while"), true);
+        assert_eq!(is_reserved("for"), true);
+        assert_eq!(is_reserved("try"), true);
         assert_eq!(is_reserved("potato"), false);
     }
 
+    #[test]
+    fn did_you_mean_test() {
+        let names = vec!["print_count".to_string(), "print".to_string(), "total".to_string()];
+
+        assert_eq!(did_you_mean("pritn_count", &names), Some("print_count".to_string()));
+        assert_eq!(did_you_mean("prin", &names), Some("print".to_string()));
+        assert_eq!(did_you_mean("totl", &names), Some("total".to_string()));
+        assert_eq!(did_you_mean("ottal", &names), Some("total".to_string()));
+        assert_eq!(did_you_mean("potato", &names), None);
+    }
+
     #[test]
     fn create_context_test() {
         let context = create_context();
 
-        assert_eq!(context.len(), 6);
-        assert_eq!(context.contains_key("print"), true);
-        assert_eq!(context.contains_key("exit"), true);
-        assert_eq!(context.contains_key("input"), true);
+        assert_eq!(context.variables().len(), 6);
+        assert_eq!(context.variables().contains_key("print"), true);
+        assert_eq!(context.variables().contains_key("exit"), true);
+        assert_eq!(context.variables().contains_key("input"), true);
     }
 }
\ No newline at end of file
@@ -0,0 +1,254 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use crate::ast::AST;
+use crate::environment::{Environment, WeakEnvironment};
+use crate::error::ModuError;
+
+// a function written in rust, it gets its arguments evaluated
+// methods get the array or object they were called on first, and change it in place
+pub type NativeFn = fn(Vec<Value>, &mut Environment) -> Result<Value, ModuError>;
+
+// a function written in modu
+#[derive(Debug, PartialEq, Clone)]
+pub struct Function {
+    pub name: String,
+    pub args: Vec<String>,
+    pub body: Rc<Vec<AST>>,
+    pub file: Option<String>, // set for functions from an imported file
+    pub env: DefinedIn,
+}
+
+// the scope a function was defined in, see Environment::stored for when it is held weakly
+#[derive(Debug, PartialEq, Clone)]
+pub enum DefinedIn {
+    Scope(Environment),
+    Weak(WeakEnvironment),
+}
+
+// what expressions evaluate to, arrays and objects are shared, a change through one reference is seen through all of them
+#[derive(Debug, Clone)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Bool(bool),
+    Null,
+    List(Rc<RefCell<Vec<Value>>>),
+    Map(Rc<RefCell<HashMap<String, Value>>>),
+    Function(Rc<Function>),
+
+    NativeFunction {
+        name: String,
+        args: Vec<String>, // "self" first for methods, "__args__" last for any number of arguments
+        call_fn: NativeFn,
+    },
+
+    // a package, or an imported file
+    Module {
+        name: String,
+        members: Rc<HashMap<String, Value>>,
+    },
+
+    // a compiled regex.compile() pattern
+    Regex(Rc<Pattern>),
+}
+
+#[derive(Debug)]
+pub struct Pattern(pub regex::Regex);
+
+// patterns are equal when they were compiled from the same source
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Pattern) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+// arrays and objects can contain themselves, so comparing them keeps track of the pairs it is already inside of,
+// and a pair that comes up again is taken to be equal
+fn equal(a: &Value, b: &Value, comparing: &mut Vec<(*const (), *const ())>) -> bool {
+    match (a, b) {
+        (Value::List(a), Value::List(b)) => {
+            if Rc::ptr_eq(a, b) {
+                return true;
+            }
+
+            let pair = (Rc::as_ptr(a) as *const (), Rc::as_ptr(b) as *const ());
+
+            if comparing.contains(&pair) {
+                return true;
+            }
+
+            comparing.push(pair);
+            let (a, b) = (a.borrow(), b.borrow());
+            let result = a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| equal(a, b, comparing));
+            comparing.pop();
+
+            result
+        }
+
+        (Value::Map(a), Value::Map(b)) => {
+            if Rc::ptr_eq(a, b) {
+                return true;
+            }
+
+            let pair = (Rc::as_ptr(a) as *const (), Rc::as_ptr(b) as *const ());
+
+            if comparing.contains(&pair) {
+                return true;
+            }
+
+            comparing.push(pair);
+            let result = equal_maps(&a.borrow(), &b.borrow(), comparing);
+            comparing.pop();
+
+            result
+        }
+
+        (Value::Module { name: a_name, members: a }, Value::Module { name: b_name, members: b }) => {
+            a_name == b_name && (Rc::ptr_eq(a, b) || equal_maps(a, b, comparing))
+        }
+
+        (Value::NativeFunction { name: a_name, args: a_args, call_fn: a_fn }, Value::NativeFunction { name: b_name, args: b_args, call_fn: b_fn }) => {
+            a_name == b_name && a_args == b_args && std::ptr::fn_addr_eq(*a_fn, *b_fn)
+        }
+
+        (Value::Int(a), Value::Int(b)) => a == b,
+        (Value::Float(a), Value::Float(b)) => a == b,
+        (Value::Str(a), Value::Str(b)) => a == b,
+        (Value::Bool(a), Value::Bool(b)) => a == b,
+        (Value::Null, Value::Null) => true,
+        (Value::Function(a), Value::Function(b)) => Rc::ptr_eq(a, b) || a == b,
+        (Value::Regex(a), Value::Regex(b)) => a == b,
+
+        _ => false,
+    }
+}
+
+fn equal_maps(a: &HashMap<String, Value>, b: &HashMap<String, Value>, comparing: &mut Vec<(*const (), *const ())>) -> bool {
+    a.len() == b.len() && a.iter().all(|(key, a)| b.get(key).is_some_and(|b| equal(a, b, comparing)))
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Value) -> bool {
+        equal(self, other, &mut Vec::new())
+    }
+}
+
+impl Value {
+    pub fn list(items: Vec<Value>) -> Value {
+        Value::List(Rc::new(RefCell::new(items)))
+    }
+
+    pub fn map(properties: HashMap<String, Value>) -> Value {
+        Value::Map(Rc::new(RefCell::new(properties)))
+    }
+}
+
+// an array or object inside of itself is written as [...] or {...}
+fn write_value(value: &Value, f: &mut std::fmt::Formatter, parents: &mut Vec<*const ()>) -> std::fmt::Result {
+    match value {
+        Value::Str(s) => write!(f, "{}", s),
+        Value::Int(n) => write!(f, "{}", n),
+        Value::Float(n) => write!(f, "{}", n),
+        Value::Bool(b) => write!(f, "{}", b),
+        Value::Null => write!(f, "null"),
+
+        Value::List(items) => {
+            let pointer = Rc::as_ptr(items) as *const ();
+
+            if parents.contains(&pointer) {
+                return write!(f, "[...]");
+            }
+
+            parents.push(pointer);
+            write!(f, "[")?;
+
+            for (i, item) in items.borrow().iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+
+                write_value(item, f, parents)?;
+            }
+
+            parents.pop();
+            write!(f, "]")
+        }
+
+        Value::Map(properties) => {
+            let pointer = Rc::as_ptr(properties) as *const ();
+
+            if parents.contains(&pointer) {
+                return write!(f, "{{...}}");
+            }
+
+            let properties = properties.borrow();
+
+            if properties.is_empty() {
+                return write!(f, "{{ }}");
+            }
+
+            parents.push(pointer);
+            write!(f, "{{ ")?;
+
+            for (i, (key, value)) in properties.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+
+                write!(f, "\"{}\": ", key)?;
+                write_value(value, f, parents)?;
+            }
+
+            parents.pop();
+            write!(f, " }}")
+        }
+
+        Value::Function(function) => write!(f, "<function {}>", function.name),
+        Value::NativeFunction { name, .. } => write!(f, "<function {}>", name),
+        Value::Module { name, .. } => write!(f, "<module {}>", name),
+        Value::Regex(pattern) => write!(f, "<regex {}>", pattern.0.as_str()),
+    }
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write_value(self, f, &mut Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let list = Value::list(vec![Value::Int(1), Value::Str("a".to_string()), Value::list(vec![])]);
+        let map = Value::map(HashMap::from([("k".to_string(), Value::Bool(true))]));
+
+        assert_eq!(list.to_string(), "[1, a, []]");
+        assert_eq!(map.to_string(), "{ \"k\": true }");
+        assert_eq!(Value::map(HashMap::new()).to_string(), "{ }");
+        assert_eq!(Value::Float(1.5).to_string(), "1.5");
+    }
+
+    #[test]
+    fn contains_itself() {
+        let a = Value::list(vec![Value::Int(1)]);
+        let b = Value::list(vec![Value::Int(1)]);
+
+        for list in [&a, &b] {
+            if let Value::List(items) = list {
+                items.borrow_mut().push(list.clone());
+            }
+        }
+
+        let object = Value::map(HashMap::from([("list".to_string(), a.clone())]));
+
+        assert_eq!(a.to_string(), "[1, [...]]");
+        assert_eq!(object.to_string(), "{ \"list\": [1, [...]] }");
+        assert_eq!(a, a);
+        assert_eq!(a, b);
+        assert_ne!(a, Value::list(vec![Value::Int(1), Value::Int(2)]));
+    }
+}
@@ -1,4 +1,4 @@
-import { Home, Baseline, File, FileBox, Equal, TriangleAlert, Server, Library, AppWindowIcon, Box, Braces } from "lucide-svelte"
+import { Home, Baseline, File, FileBox, Equal, TriangleAlert, Server, Library, AppWindowIcon, Box, Braces, List, Type, Regex, Globe, ServerCog, FolderTree } from "lucide-svelte"
 
 export default {
     pages: [
@@ -32,6 +32,11 @@ export default {
             "title": "File I/O",
             "icon": File,
         },
+        {
+            "path": "path",
+            "title": "Paths",
+            "icon": FolderTree,
+        },
         {
             "path": "os",
             "title": "OS Lib",
@@ -42,11 +47,36 @@ export default {
             "title": "FFI",
             "icon": Box,
         },
+        {
+            "path": "array",
+            "title": "Arrays",
+            "icon": List,
+        },
         {
             "path": "json",
             "title": "JSON",
             "icon": Braces,
         },
+        {
+            "path": "string",
+            "title": "Strings",
+            "icon": Type,
+        },
+        {
+            "path": "regex",
+            "title": "Regex",
+            "icon": Regex,
+        },
+        {
+            "path": "http",
+            "title": "HTTP",
+            "icon": Globe,
+        },
+        {
+            "path": "httpserver",
+            "title": "HTTP Server",
+            "icon": ServerCog,
+        },
         /*{
             "path": "limitations",
             "title": "Limitations",